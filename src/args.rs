@@ -1,6 +1,10 @@
-use clap::{Args, Parser};
+use std::path::PathBuf;
 
-use crate::config;
+use clap::{Args, Parser, ValueEnum};
+
+use crate::config::{self, Column};
+use crate::export::ExportFormat;
+use crate::processes::{parse_kill_signal, KillSignal};
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = Some("Pik is a simple TUI tool for searching and killing processes in interactive way."))]
@@ -9,22 +13,92 @@ pub struct CliArgs {
         default_value = "",
         help = r#"Query string for searching processes.
         You may use special prefix for different kind of search:
-        - :<port> - search by port, i.e ':8080'
-        - /<path> - search by command path, i.e. '/home/user/bin'
+        - :<port> - search by port, i.e ':8080', ':80,443' or ':8000-9000'
+        - /<path> or path:<path> - search by command path, i.e. '/home/user/bin' or glob 'path:/usr/lib/*/chrome*'
         - -<arg> - search by argument, i.e. '-i'
-        If no prefix is given search will be done by process name"#
+        - #<pid> - search by pid prefix, i.e. '#12' matches 12, 120 and 1234
+        - ^<pid> - search for children of given parent pid, i.e. '^1'
+        - @<user> - search by user name prefix, i.e. '@root'
+        - re:<regex> - search by process name or command line using regular expression, i.e. 're:^post.*res$'
+        - state:<codes> - search by process state, i.e. 'state:z' for zombies or 'state:zt' for zombie and stopped processes
+        - deleted:<path> - search for processes running binary deleted or replaced on disk, i.e. 'deleted:' or 'deleted:nginx'
+        - container:<id> - search for processes running in containers, i.e. 'container:' or 'container:4f3c2a'
+        - tty:<name> - search by controlling terminal, i.e. 'tty:pts/3', 'tty:pts' for all pseudo terminals or 'tty:-' for daemons
+        - unit:<name> - search by systemd unit, i.e. 'unit:nginx' or 'unit:user@1000'
+        - mem>/mem</mem= - search by memory usage in MB or with K, M or G suffix, i.e. 'mem>500' or 'mem>1G'
+        - time>/time< - search by run time with s, m, h or d suffix, i.e. 'time>2h'
+        - nice>/nice</nice= - search by nice value from -20 to 19, i.e. 'nice<0'
+        If no prefix is given search will be done by process name, best matches are shown first.
+        Multiple terms separated by whitespace are combined, process must match all of them, i.e. ':8080 @www-data'.
+        Terms prefixed with '!' exclude processes whose name or arguments contain them, i.e. 'java !jenkins'.
+        Use '\!' to search for literal '!'"#
     )]
     pub query: String,
+    /// Start with given query, same as query argument. With --print a single matching process is printed right away
+    /// without showing ui, i.e. `kill $(pik --filter ':8080' --print)`
+    #[arg(short = 'f', long, value_name = "QUERY", conflicts_with = "query")]
+    pub filter: Option<String>,
     /// On linux threads can be listed as processes which are ignored by default. This flag allows to include them
     #[arg(short = 't', long, default_value_t = false)]
     pub include_threads_processes: bool,
     /// By default pik shows only proceseses owned by current user. This flag allows to show all processes
     #[arg(short = 'a', long, default_value_t = false)]
     pub include_other_users_processes: bool,
+    /// Disable fuzzy search, process name, path and ports will be matched by substring
+    #[arg(short = 'e', long, default_value_t = false)]
+    pub exact: bool,
+    /// Print pid of selected process on ENTER and quit instead of killing it, i.e. `kill -9 $(pik --print)`
+    #[arg(short = 'p', long, default_value_t = false)]
+    pub print: bool,
+    /// Same as --print, but prints pid followed by tab and the full command line
+    #[arg(long, default_value_t = false)]
+    pub print_cmd: bool,
+    /// Start with query saved as bookmark in config file, query given as argument is appended to it
+    #[arg(short = 'b', long)]
+    pub bookmark: Option<String>,
+    /// Show only processes of given user, 'me' stands for the current user. Query is searched within them
+    #[arg(short = 'u', long)]
+    pub user: Option<String>,
+    /// Start with processes sorted by given column in ascending order, sorting can be changed with CTRL+S
+    #[arg(short = 's', long, value_enum)]
+    pub sort: Option<SortArg>,
+    /// Sort in descending order, i.e. `--sort mem --reverse` shows the most memory hungry processes first
+    #[arg(short = 'r', long, default_value_t = false, requires = "sort")]
+    pub reverse: bool,
+    /// Columns of the table in given order, i.e. `--columns user,pid,mem,cmd`. It overrides columns from config file
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub columns: Option<Vec<Column>>,
+    /// File processes shown in the table are exported to with ALT+S, default is pik-export.json
+    #[arg(long, value_name = "FILE")]
+    pub export: Option<PathBuf>,
+    /// Format of export file, by default it is csv for files with .csv extension and json otherwise
+    #[arg(long, value_enum)]
+    pub export_format: Option<ExportFormat>,
+    /// Draw borders and scrollbars with ascii chars and use basic 16 colors, for terminals that show garbage instead
+    #[arg(long, default_value_t = false)]
+    pub ascii: bool,
+    /// After sending signal check whether process really exited, SIGKILL is offered when it did not
+    #[arg(long, default_value_t = false)]
+    pub verify_kill: bool,
+    /// Signal sent by kill action without asking for it, given by name or number, i.e. `--signal 9` or `--signal SIGTERM`
+    #[arg(long, value_name = "NAME|NUMBER", value_parser = parse_kill_signal)]
+    pub signal: Option<KillSignal>,
     #[command(flatten)]
     pub screen_size: Option<ScreenSizeOptions>,
 }
 
+/// Columns processes can be sorted by at startup
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortArg {
+    Pid,
+    Cpu,
+    Mem,
+    /// Run time of the process
+    Time,
+    User,
+    Cmd,
+}
+
 #[derive(Args, Debug, Clone, Copy)]
 #[group(required = false, multiple = false)]
 pub struct ScreenSizeOptions {
@@ -1,5 +1,15 @@
+use std::collections::BTreeMap;
+
 use anyhow::{Context, Result};
 
+mod columns;
+mod keys;
+mod theme;
+
+pub use columns::{Column, ColumnsConfig, StartTimeFormat};
+pub use keys::{KeyBinding, KeyBindings, KeyConfig};
+pub use theme::{ThemeConfig, ThemePreset};
+
 pub fn load_app_config() -> Result<AppConfig> {
     let config_path = directories::ProjectDirs::from("", "", "pik")
         .map(|dirs| dirs.config_dir().join("config.toml"))
@@ -20,10 +30,100 @@ fn load_config_from_file(path: &std::path::PathBuf) -> Result<AppConfig> {
 
 use serde::Deserialize;
 
-#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Deserialize)]
 pub struct AppConfig {
     #[serde(default)]
     pub screen_size: ScreenSize,
+    /// Named queries, i.e. web = ":80,443"
+    #[serde(default)]
+    pub bookmarks: BTreeMap<String, String>,
+    #[serde(default)]
+    pub keys: KeyConfig,
+    /// Pik and its ancestors (shell, terminal) are not listed
+    #[serde(default = "hide_self_default")]
+    pub hide_self: bool,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    /// Idle time after last keystroke before processes are searched again, 0 searches on every key
+    #[serde(default = "search_debounce_ms_default")]
+    pub search_debounce_ms: u64,
+    #[serde(default)]
+    pub columns: ColumnsConfig,
+    /// Maximum number of open files listed for a process, the rest is only counted
+    #[serde(default = "open_files_limit_default")]
+    pub open_files_limit: usize,
+    /// Moving selection past the last or the first row continues from the other end
+    #[serde(default = "wrap_selection_default")]
+    pub wrap_selection: bool,
+    /// Ascii borders and scrollbars with basic 16 colors, same as --ascii
+    #[serde(default)]
+    pub ascii: bool,
+    /// Pik starts with process details hidden, they can be shown with ALT+D
+    #[serde(default)]
+    pub hide_details: bool,
+    /// After signal is sent pik checks whether process exited, same as --verify-kill
+    #[serde(default)]
+    pub verify_kill: bool,
+    /// How long killed process is waited for before it is reported as still running
+    #[serde(default = "verify_kill_timeout_ms_default")]
+    pub verify_kill_timeout_ms: u64,
+    /// What Enter does with selected process
+    #[serde(default)]
+    pub enter_action: EnterAction,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            screen_size: ScreenSize::default(),
+            bookmarks: BTreeMap::new(),
+            keys: KeyConfig::default(),
+            hide_self: hide_self_default(),
+            theme: ThemeConfig::default(),
+            search_debounce_ms: search_debounce_ms_default(),
+            columns: ColumnsConfig::default(),
+            open_files_limit: open_files_limit_default(),
+            wrap_selection: wrap_selection_default(),
+            ascii: false,
+            hide_details: false,
+            verify_kill: false,
+            verify_kill_timeout_ms: verify_kill_timeout_ms_default(),
+            enter_action: EnterAction::default(),
+        }
+    }
+}
+
+fn hide_self_default() -> bool {
+    true
+}
+
+fn search_debounce_ms_default() -> u64 {
+    100
+}
+
+fn open_files_limit_default() -> usize {
+    500
+}
+
+fn wrap_selection_default() -> bool {
+    true
+}
+
+fn verify_kill_timeout_ms_default() -> u64 {
+    1000
+}
+
+/// Action of Enter on selected process, with --print or --print-cmd it always prints the process
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnterAction {
+    /// Popup with all details of the process
+    #[default]
+    Details,
+    /// Signal is chosen like with kill key
+    Kill,
+    /// Pid is printed and pik quits, same as --print
+    Print,
 }
 
 #[derive(Debug, Eq, PartialEq, Deserialize, Clone, Copy)]
@@ -63,7 +163,98 @@ mod tests {
         assert_eq!(
             default_settings,
             AppConfig {
-                screen_size: ScreenSize::Fullscreen
+                screen_size: ScreenSize::Fullscreen,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn should_deserialize_bookmarks() {
+        let settings: AppConfig = toml::from_str(
+            r#"
+            [bookmarks]
+            web = ":80,443"
+            noisy = "chrome"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            settings.bookmarks,
+            BTreeMap::from([
+                ("noisy".to_string(), "chrome".to_string()),
+                ("web".to_string(), ":80,443".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn should_hide_self_by_default() {
+        assert!(AppConfig::default().hide_self);
+        let settings: AppConfig = toml::from_str("hide_self = false").unwrap();
+        assert!(!settings.hide_self);
+    }
+
+    #[test]
+    fn should_deserialize_search_debounce() {
+        assert_eq!(AppConfig::default().search_debounce_ms, 100);
+        let settings: AppConfig = toml::from_str("search_debounce_ms = 250").unwrap();
+        assert_eq!(settings.search_debounce_ms, 250);
+    }
+
+    #[test]
+    fn should_deserialize_open_files_limit() {
+        assert_eq!(AppConfig::default().open_files_limit, 500);
+        let settings: AppConfig = toml::from_str("open_files_limit = 50").unwrap();
+        assert_eq!(settings.open_files_limit, 50);
+    }
+
+    #[test]
+    fn should_deserialize_enter_action() {
+        assert_eq!(AppConfig::default().enter_action, EnterAction::Details);
+        let settings: AppConfig = toml::from_str("enter_action = \"kill\"").unwrap();
+        assert_eq!(settings.enter_action, EnterAction::Kill);
+        assert!(toml::from_str::<AppConfig>("enter_action = \"open\"").is_err());
+    }
+
+    #[test]
+    fn should_deserialize_verify_kill() {
+        assert!(!AppConfig::default().verify_kill);
+        assert_eq!(AppConfig::default().verify_kill_timeout_ms, 1000);
+        let settings: AppConfig =
+            toml::from_str("verify_kill = true\nverify_kill_timeout_ms = 3000").unwrap();
+        assert!(settings.verify_kill);
+        assert_eq!(settings.verify_kill_timeout_ms, 3000);
+    }
+
+    #[test]
+    fn should_deserialize_hide_details() {
+        assert!(!AppConfig::default().hide_details);
+        let settings: AppConfig = toml::from_str("hide_details = true").unwrap();
+        assert!(settings.hide_details);
+    }
+
+    #[test]
+    fn should_wrap_selection_by_default() {
+        assert!(AppConfig::default().wrap_selection);
+        let settings: AppConfig = toml::from_str("wrap_selection = false").unwrap();
+        assert!(!settings.wrap_selection);
+    }
+
+    #[test]
+    fn should_deserialize_keys() {
+        let settings: AppConfig = toml::from_str(
+            r#"
+            [keys]
+            quit = "ctrl+q"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            settings.keys,
+            KeyConfig {
+                quit: KeyBindings::from(["ctrl+q".parse::<KeyBinding>().unwrap()]),
+                ..Default::default()
             }
         );
     }
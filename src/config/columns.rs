@@ -0,0 +1,159 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// Optional columns of the process table, they are hidden by default
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ColumnsConfig {
+    pub threads: bool,
+    /// Disk read and write rates, READ/s and WRITE/s columns
+    pub io: bool,
+    /// Controlling terminal, TTY column
+    pub tty: bool,
+    /// Open file descriptors, FDS column, counted only for rows on screen
+    pub fds: bool,
+    /// How STARTED column and start time in details are shown, it can be switched in the app too
+    pub start_time: StartTimeFormat,
+    /// Columns shown in given order, optional column flags are ignored when set
+    pub visible: Option<Vec<Column>>,
+    /// Columns take fixed share of the table width instead of fitting values on screen
+    pub fixed_widths: bool,
+}
+
+/// Column of the process table, column with value matched by search is always added as the last one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum Column {
+    User,
+    Pid,
+    Parent,
+    Started,
+    Time,
+    Mem,
+    Cpu,
+    Threads,
+    Read,
+    Write,
+    Tty,
+    Fds,
+    Cmd,
+    CmdPath,
+}
+
+impl ColumnsConfig {
+    /// Visible columns, by default optional ones are shown after cpu usage when enabled
+    pub fn table_columns(&self) -> Vec<Column> {
+        if let Some(visible) = self.visible.as_ref() {
+            return visible.clone();
+        }
+        let mut columns = vec![
+            Column::User,
+            Column::Pid,
+            Column::Parent,
+            Column::Started,
+            Column::Time,
+            Column::Mem,
+            Column::Cpu,
+        ];
+        if self.threads {
+            columns.push(Column::Threads);
+        }
+        if self.io {
+            columns.extend([Column::Read, Column::Write]);
+        }
+        if self.tty {
+            columns.push(Column::Tty);
+        }
+        if self.fds {
+            columns.push(Column::Fds);
+        }
+        columns.extend([Column::Cmd, Column::CmdPath]);
+        columns
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StartTimeFormat {
+    /// Time of day, i.e. '14:32:05'
+    #[default]
+    Time,
+    /// Time since start, i.e. '2h ago'
+    Relative,
+    /// Time for processes started today, date for older ones, i.e. '14:32' or '2024-05-01'
+    Date,
+}
+
+impl StartTimeFormat {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Time => Self::Relative,
+            Self::Relative => Self::Date,
+            Self::Date => Self::Time,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_deserialize_columns() {
+        let columns: ColumnsConfig = toml::from_str("threads = true").unwrap();
+        assert!(columns.threads);
+        assert!(!columns.io);
+        assert!(!ColumnsConfig::default().threads);
+        assert_eq!(columns.start_time, StartTimeFormat::Time);
+
+        let columns: ColumnsConfig = toml::from_str("start_time = \"relative\"").unwrap();
+        assert_eq!(columns.start_time, StartTimeFormat::Relative);
+        assert!(toml::from_str::<ColumnsConfig>("start_time = \"iso\"").is_err());
+    }
+
+    #[test]
+    fn should_list_visible_columns() {
+        let default_columns = ColumnsConfig::default().table_columns();
+        assert_eq!(default_columns.len(), 9);
+        assert_eq!(
+            default_columns[6..],
+            [Column::Cpu, Column::Cmd, Column::CmdPath]
+        );
+
+        let columns = ColumnsConfig {
+            io: true,
+            tty: true,
+            fds: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            columns.table_columns()[6..],
+            [
+                Column::Cpu,
+                Column::Read,
+                Column::Write,
+                Column::Tty,
+                Column::Fds,
+                Column::Cmd,
+                Column::CmdPath
+            ]
+        );
+
+        let columns: ColumnsConfig =
+            toml::from_str("threads = true\nvisible = [\"pid\", \"cmd\", \"mem\", \"cmd_path\"]")
+                .unwrap();
+        assert_eq!(
+            columns.table_columns(),
+            [Column::Pid, Column::Cmd, Column::Mem, Column::CmdPath]
+        );
+    }
+
+    #[test]
+    fn should_list_valid_columns_for_unknown_one() {
+        let error = toml::from_str::<ColumnsConfig>("visible = [\"pid\", \"size\"]").unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("unknown variant `size`, expected one of `user`, `pid`, `parent`"));
+    }
+}
@@ -0,0 +1,450 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// Key bindings of actions, every action can be bound to several keys
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct KeyConfig {
+    pub quit: KeyBindings,
+    pub kill: KeyBindings,
+    /// Kills selected process with all its descendants
+    pub kill_tree: KeyBindings,
+    pub refresh: KeyBindings,
+    pub details_forward: KeyBindings,
+    pub details_backward: KeyBindings,
+    pub popup_up: KeyBindings,
+    pub popup_down: KeyBindings,
+    pub enter: KeyBindings,
+    /// Copies text of message popup, i.e. process details, to clipboard
+    pub popup_copy: KeyBindings,
+    /// Switches how terms without prefix are searched
+    pub search_mode: KeyBindings,
+    /// Copies pid of selected process to clipboard
+    pub copy_pid: KeyBindings,
+    /// Copies command line (command with all arguments) of selected process to clipboard
+    pub copy_cmd: KeyBindings,
+    /// Shows environment variables of selected process
+    pub environment: KeyBindings,
+    /// Switches process details between info, environment, open files and network tabs
+    pub details_tab: KeyBindings,
+    /// Hides or shows process details panel
+    pub toggle_details: KeyBindings,
+    /// Lists open file descriptors of selected process
+    pub open_files: KeyBindings,
+    /// Stops selected process with SIGSTOP (unix only)
+    pub pause: KeyBindings,
+    /// Continues stopped process with SIGCONT (unix only)
+    pub resume: KeyBindings,
+    /// Switches start time between time of day, time since start and date
+    pub start_time_format: KeyBindings,
+    /// Writes processes shown in the table to export file
+    pub export: KeyBindings,
+    /// Opens list of columns the table can be sorted by
+    pub sort_picker: KeyBindings,
+    /// Switches between typing into search and navigating the table with vim keys
+    pub normal_mode: KeyBindings,
+    /// Keeps selected process at the top of the table whatever is searched
+    pub pin: KeyBindings,
+    /// Shows processes running the same executable as one row
+    pub group: KeyBindings,
+    /// Switches between fuzzy and substring matching
+    pub fuzzy_search: KeyBindings,
+    /// Selects parent of selected process, it is added to the table when it does not match the query
+    pub select_parent: KeyBindings,
+    /// Replaces the query with children of selected process
+    pub show_children: KeyBindings,
+    /// Marks selected process or removes its mark
+    pub mark: KeyBindings,
+    /// Sorts by next column
+    pub sort_column: KeyBindings,
+    pub sort_order: KeyBindings,
+    pub tree_view: KeyBindings,
+    /// Opens list of bookmarked queries
+    pub bookmarks: KeyBindings,
+    pub regex_search: KeyBindings,
+    /// Switches between smart case, case sensitive and insensitive search
+    pub case_sensitivity: KeyBindings,
+    /// Shows all key bindings
+    pub help: KeyBindings,
+    /// Hides pik and its ancestors or shows them again
+    pub hide_self: KeyBindings,
+    /// Collapses selected subtree in tree view or selected group
+    pub collapse: KeyBindings,
+    /// Expands selected subtree in tree view or selected group
+    pub expand: KeyBindings,
+    /// Marks all processes shown in the table
+    pub mark_all: KeyBindings,
+}
+
+impl Default for KeyConfig {
+    fn default() -> Self {
+        Self {
+            quit: KeyBindings::from([KeyBinding::new(KeyCode::Esc), KeyBinding::ctrl('c')]),
+            kill: KeyBindings::from([KeyBinding::ctrl('x')]),
+            kill_tree: KeyBindings::from([KeyBinding::alt('x')]),
+            refresh: KeyBindings::from([KeyBinding::ctrl('r')]),
+            details_forward: KeyBindings::from([KeyBinding::ctrl('f')]),
+            details_backward: KeyBindings::from([KeyBinding::ctrl('b')]),
+            popup_up: KeyBindings::from([KeyBinding::new(KeyCode::Up)]),
+            popup_down: KeyBindings::from([KeyBinding::new(KeyCode::Down)]),
+            enter: KeyBindings::from([KeyBinding::new(KeyCode::Enter)]),
+            popup_copy: KeyBindings::from([KeyBinding::new(KeyCode::Char('y'))]),
+            search_mode: KeyBindings::from([KeyBinding::alt('m')]),
+            copy_pid: KeyBindings::from([KeyBinding::alt('p')]),
+            copy_cmd: KeyBindings::from([KeyBinding::alt('y')]),
+            environment: KeyBindings::from([KeyBinding::alt('e')]),
+            details_tab: KeyBindings::from([KeyBinding::ctrl('e')]),
+            toggle_details: KeyBindings::from([KeyBinding::alt('d')]),
+            open_files: KeyBindings::from([KeyBinding::alt('f')]),
+            pause: KeyBindings::from([KeyBinding::alt('z')]),
+            resume: KeyBindings::from([KeyBinding::alt('u')]),
+            start_time_format: KeyBindings::from([KeyBinding::alt('t')]),
+            export: KeyBindings::from([KeyBinding::alt('s')]),
+            sort_picker: KeyBindings::from([KeyBinding::alt('o')]),
+            normal_mode: KeyBindings::from([KeyBinding::alt('n')]),
+            pin: KeyBindings::from([KeyBinding::alt('i')]),
+            group: KeyBindings::from([KeyBinding::alt('g')]),
+            fuzzy_search: KeyBindings::from([KeyBinding::alt('a')]),
+            select_parent: KeyBindings::from([KeyBinding::alt('k')]),
+            show_children: KeyBindings::from([KeyBinding::alt('j')]),
+            mark: KeyBindings::from([KeyBinding::ctrl(' ')]),
+            sort_column: KeyBindings::from([KeyBinding::ctrl('s')]),
+            sort_order: KeyBindings::from([KeyBinding::ctrl('o')]),
+            tree_view: KeyBindings::from([KeyBinding::ctrl('t')]),
+            bookmarks: KeyBindings::from([KeyBinding::alt('b')]),
+            regex_search: KeyBindings::from([KeyBinding::alt('r')]),
+            case_sensitivity: KeyBindings::from([KeyBinding::alt('c')]),
+            help: KeyBindings::from([KeyBinding::new(KeyCode::F(1))]),
+            hide_self: KeyBindings::from([KeyBinding::alt('h')]),
+            collapse: KeyBindings::from([KeyBinding {
+                code: KeyCode::Left,
+                modifiers: KeyModifiers::ALT,
+            }]),
+            expand: KeyBindings::from([KeyBinding {
+                code: KeyCode::Right,
+                modifiers: KeyModifiers::ALT,
+            }]),
+            mark_all: KeyBindings::from([KeyBinding::ctrl('a')]),
+        }
+    }
+}
+
+/// Deserialized from single key, i.e. "ctrl+x", or list of keys, i.e. ["esc", "ctrl+c"]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "OneOrMany")]
+pub struct KeyBindings(Vec<KeyBinding>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyBindings {
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        self.0.iter().any(|binding| binding.matches(key))
+    }
+
+    /// Compact form used in help bar, i.e. "ESC/<C+C>"
+    pub fn short(&self) -> String {
+        let keys: Vec<String> = self.0.iter().map(|binding| binding.short()).collect();
+        keys.join("/")
+    }
+}
+
+impl<const N: usize> From<[KeyBinding; N]> for KeyBindings {
+    fn from(bindings: [KeyBinding; N]) -> Self {
+        Self(bindings.to_vec())
+    }
+}
+
+impl TryFrom<OneOrMany> for KeyBindings {
+    type Error = anyhow::Error;
+
+    fn try_from(keys: OneOrMany) -> Result<Self> {
+        let keys = match keys {
+            OneOrMany::One(key) => vec![key],
+            OneOrMany::Many(keys) => keys,
+        };
+        if keys.is_empty() {
+            return Err(anyhow!("At least one key is required"));
+        }
+        keys.iter()
+            .map(|key| key.parse())
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+}
+
+impl Display for KeyBindings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<String> = self.0.iter().map(|binding| binding.to_string()).collect();
+        write!(f, "{}", keys.join(" | "))
+    }
+}
+
+impl KeyBinding {
+    pub const fn new(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    pub const fn ctrl(c: char) -> Self {
+        Self {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::CONTROL,
+        }
+    }
+
+    pub const fn alt(c: char) -> Self {
+        Self {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::ALT,
+        }
+    }
+
+    //NOTE: shift is part of the char already, terminals do not agree whether they report it too
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        match self.code {
+            KeyCode::Char(_) => {
+                self.code == key.code
+                    && self.modifiers - KeyModifiers::SHIFT == key.modifiers - KeyModifiers::SHIFT
+            }
+            _ => self.code == key.code && self.modifiers == key.modifiers,
+        }
+    }
+
+    fn short(&self) -> String {
+        let key = key_name(self.code);
+        if self.modifiers.is_empty() {
+            return key;
+        }
+        let modifiers: String = [
+            (KeyModifiers::CONTROL, "C+"),
+            (KeyModifiers::ALT, "A+"),
+            (KeyModifiers::SHIFT, "S+"),
+        ]
+        .iter()
+        .filter(|(modifier, _)| self.modifiers.contains(*modifier))
+        .map(|(_, name)| *name)
+        .collect();
+        format!("<{}{}>", modifiers, key)
+    }
+}
+
+impl Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "CTRL+"),
+            (KeyModifiers::ALT, "ALT+"),
+            (KeyModifiers::SHIFT, "SHIFT+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{}", name)?;
+            }
+        }
+        write!(f, "{}", key_name(self.code))
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = anyhow::Error;
+
+    /// Parses keys like "esc", "ctrl+x" or "alt+shift+up", names are case insensitive but chars are not,
+    /// so "G" is the same as "shift+g"
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || anyhow!("Invalid key: '{}'", s);
+        let (modifiers, key) = match s.rsplit_once('+') {
+            //NOTE: '+' itself can be bound too, i.e. "ctrl++"
+            Some((modifiers, "")) => (modifiers.strip_suffix('+').ok_or_else(invalid)?, "+"),
+            Some((modifiers, key)) => (modifiers, key),
+            None => ("", s),
+        };
+        let modifiers = modifiers.split('+').filter(|m| !m.is_empty()).try_fold(
+            KeyModifiers::NONE,
+            |acc, modifier| match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => Ok(acc | KeyModifiers::CONTROL),
+                "alt" => Ok(acc | KeyModifiers::ALT),
+                "shift" => Ok(acc | KeyModifiers::SHIFT),
+                _ => Err(invalid()),
+            },
+        )?;
+        let code = match parse_key_code(key).ok_or_else(invalid)? {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            code => code,
+        };
+        let modifiers = match code {
+            KeyCode::Char(c) if c.is_ascii_uppercase() => modifiers | KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Ok(Self { code, modifiers })
+    }
+}
+
+fn parse_key_code(key: &str) -> Option<KeyCode> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    let code = match key.to_lowercase().as_str() {
+        "esc" => KeyCode::Esc,
+        "enter" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "space" => KeyCode::Char(' '),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        f => KeyCode::F(
+            f.strip_prefix('f')?
+                .parse()
+                .ok()
+                .filter(|n| (1..=12).contains(n))?,
+        ),
+    };
+    Some(code)
+}
+
+fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "SPACE".to_string(),
+        KeyCode::Char(c) => c.to_uppercase().to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::PageUp => "PAGE UP".to_string(),
+        KeyCode::PageDown => "PAGE DOWN".to_string(),
+        code => format!("{:?}", code).to_uppercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(code: KeyCode, modifiers: KeyModifiers) -> KeyBinding {
+        KeyBinding { code, modifiers }
+    }
+
+    #[test]
+    fn should_parse_key_bindings() {
+        assert_eq!(
+            "esc".parse::<KeyBinding>().unwrap(),
+            KeyBinding::new(KeyCode::Esc)
+        );
+        assert_eq!(
+            "Ctrl+x".parse::<KeyBinding>().unwrap(),
+            KeyBinding::ctrl('x')
+        );
+        assert_eq!(
+            "alt+shift+up".parse::<KeyBinding>().unwrap(),
+            binding(KeyCode::Up, KeyModifiers::ALT | KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            "ctrl+space".parse::<KeyBinding>().unwrap(),
+            KeyBinding::ctrl(' ')
+        );
+        assert_eq!(
+            "f5".parse::<KeyBinding>().unwrap(),
+            KeyBinding::new(KeyCode::F(5))
+        );
+        assert_eq!(
+            "ctrl++".parse::<KeyBinding>().unwrap(),
+            KeyBinding::ctrl('+')
+        );
+    }
+
+    #[test]
+    fn should_keep_case_of_char_keys() {
+        let shift_g = binding(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!("G".parse::<KeyBinding>().unwrap(), shift_g);
+        assert_eq!("shift+g".parse::<KeyBinding>().unwrap(), shift_g);
+        assert_eq!(
+            "alt+G".parse::<KeyBinding>().unwrap(),
+            binding(KeyCode::Char('G'), KeyModifiers::ALT | KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            "g".parse::<KeyBinding>().unwrap(),
+            KeyBinding::new(KeyCode::Char('g'))
+        );
+
+        assert!(shift_g.matches(&KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)));
+        assert!(shift_g.matches(&KeyEvent::new(KeyCode::Char('G'), KeyModifiers::NONE)));
+        assert!(!shift_g.matches(&KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE)));
+        assert!(!KeyBinding::new(KeyCode::Char('g'))
+            .matches(&KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)));
+    }
+
+    #[test]
+    fn should_fail_on_invalid_key_bindings() {
+        for key in ["", "hyper+x", "ctrl+", "f13", "escape", "ctrl+xx"] {
+            assert_eq!(
+                key.parse::<KeyBinding>().unwrap_err().to_string(),
+                format!("Invalid key: '{}'", key)
+            );
+        }
+    }
+
+    #[test]
+    fn should_match_key_events() {
+        let bindings = KeyBindings::from([KeyBinding::new(KeyCode::Esc), KeyBinding::ctrl('c')]);
+        assert!(bindings.matches(&KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)));
+        assert!(bindings.matches(&KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)));
+        assert!(!bindings.matches(&KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn should_display_key_bindings() {
+        let bindings = KeyBindings::from([
+            KeyBinding::new(KeyCode::Esc),
+            KeyBinding::ctrl('c'),
+            binding(KeyCode::PageDown, KeyModifiers::ALT),
+        ]);
+        assert_eq!(bindings.to_string(), "ESC | CTRL+C | ALT+PAGE DOWN");
+        assert_eq!(bindings.short(), "ESC/<C+C>/<A+PAGE DOWN>");
+    }
+
+    #[test]
+    fn should_deserialize_key_config() {
+        let keys: KeyConfig = toml::from_str(
+            r#"
+            quit = "ctrl+q"
+            kill = ["ctrl+x", "f9"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(keys.quit, KeyBindings::from([KeyBinding::ctrl('q')]));
+        assert_eq!(
+            keys.kill,
+            KeyBindings::from([KeyBinding::ctrl('x'), KeyBinding::new(KeyCode::F(9))])
+        );
+        assert_eq!(keys.refresh, KeyConfig::default().refresh);
+    }
+
+    #[test]
+    fn should_fail_to_deserialize_invalid_key() {
+        let error = toml::from_str::<KeyConfig>(r#"quit = "ctrl+qq""#).unwrap_err();
+        assert!(error.to_string().contains("Invalid key: 'ctrl+qq'"));
+    }
+}
@@ -0,0 +1,59 @@
+use serde::Deserialize;
+
+/// Colors are kept as strings, invalid ones are reported as warnings instead of failing config load
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub preset: ThemePreset,
+    /// Hex string, i.e. "#1e293b", or named color, i.e. "blue"
+    pub row_fg: Option<String>,
+    pub selected_style_fg: Option<String>,
+    pub normal_row_color: Option<String>,
+    pub alt_row_color: Option<String>,
+    pub process_table_border_color: Option<String>,
+    pub zombie_row_fg: Option<String>,
+    pub stopped_row_fg: Option<String>,
+    /// Processes run by root
+    pub root_row_fg: Option<String>,
+    /// Processes run by the user running pik
+    pub own_row_fg: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+    /// Only 16 colors of terminal palette, used with --ascii
+    Basic,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_deserialize_theme() {
+        let theme: ThemeConfig = toml::from_str(
+            r##"
+            preset = "light"
+            row_fg = "#000000"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(
+            theme,
+            ThemeConfig {
+                preset: ThemePreset::Light,
+                row_fg: Some("#000000".to_string()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn should_fail_on_unknown_preset() {
+        assert!(toml::from_str::<ThemeConfig>(r#"preset = "solarized""#).is_err());
+    }
+}
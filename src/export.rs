@@ -0,0 +1,217 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, SecondsFormat};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::processes::Process;
+
+/// File written when processes are exported, relative to the directory pik runs in
+pub const DEFAULT_EXPORT_PATH: &str = "pik-export.json";
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+/// Where and how search results are exported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Export {
+    path: PathBuf,
+    format: ExportFormat,
+}
+
+/// Exported fields of a process, missing values are null in json and empty in csv.
+/// `Process` is not serialized directly: start time is exported as RFC 3339 in local time zone,
+/// run time in seconds is computed from export time and ui state like matches is left out
+#[derive(Debug, Serialize)]
+struct ExportedProcess<'a> {
+    user: &'a str,
+    pid: u32,
+    parent_pid: Option<u32>,
+    start_time: String,
+    run_time: u64,
+    memory: u64,
+    nice: Option<i32>,
+    cmd: &'a str,
+    cmd_path: Option<&'a str>,
+    args: &'a str,
+    ports: Option<&'a str>,
+}
+
+impl<'a> ExportedProcess<'a> {
+    fn new(prc: &'a Process, now: SystemTime) -> Self {
+        let start_time: DateTime<Local> =
+            (UNIX_EPOCH + Duration::from_secs(prc.start_timestamp)).into();
+        Self {
+            user: &prc.user_name,
+            pid: prc.pid,
+            parent_pid: prc.parent_pid,
+            start_time: start_time.to_rfc3339_opts(SecondsFormat::Secs, false),
+            run_time: prc.run_duration(now).as_secs(),
+            memory: prc.memory,
+            nice: prc.nice,
+            cmd: &prc.cmd,
+            cmd_path: prc.cmd_path.as_deref(),
+            args: &prc.args,
+            ports: prc.ports.as_deref(),
+        }
+    }
+}
+
+impl Export {
+    /// Format is taken from file extension unless it is given, files without '.csv' extension are json
+    pub fn new(path: PathBuf, format: Option<ExportFormat>) -> Self {
+        let format = format.unwrap_or_else(|| match path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("csv") => ExportFormat::Csv,
+            _ => ExportFormat::Json,
+        });
+        Self { path, format }
+    }
+
+    /// Writes processes to the file, returns its absolute path
+    pub fn write<'a>(&self, processes: impl Iterator<Item = &'a Process>) -> Result<PathBuf> {
+        let now = SystemTime::now();
+        let processes: Vec<ExportedProcess> = processes
+            .map(|prc| ExportedProcess::new(prc, now))
+            .collect();
+        let content = match self.format {
+            ExportFormat::Json => to_json(&processes)?,
+            ExportFormat::Csv => to_csv(&processes)?,
+        };
+        std::fs::write(&self.path, content)
+            .with_context(|| format!("Failed to export processes to {:?}", self.path))?;
+        Ok(std::path::absolute(&self.path).unwrap_or(self.path.clone()))
+    }
+}
+
+impl Default for Export {
+    fn default() -> Self {
+        Self::new(PathBuf::from(DEFAULT_EXPORT_PATH), None)
+    }
+}
+
+fn to_json(processes: &[ExportedProcess]) -> Result<String> {
+    let mut json = serde_json::to_string_pretty(processes)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Header with field names followed by one row per process, header is written with the first row
+fn to_csv(processes: &[ExportedProcess]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for prc in processes {
+        writer.serialize(prc)?;
+    }
+    let content = writer.into_inner().map_err(|err| err.into_error())?;
+    Ok(String::from_utf8(content)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn some_process() -> Process {
+        Process {
+            pid: 1234,
+            parent_pid: Some(1),
+            user_name: "root".to_string(),
+            cmd: "nginx".to_string(),
+            cmd_path: Some("/usr/sbin/nginx".to_string()),
+            args: "-g,daemon off;,".to_string(),
+            ports: Some("80, 443".to_string()),
+            memory: 2048,
+            nice: Some(-5),
+            start_timestamp: 1_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_choose_format_by_extension() {
+        let format = |path: &str, format| Export::new(PathBuf::from(path), format).format;
+        assert_eq!(format("report.csv", None), ExportFormat::Csv);
+        assert_eq!(format("report.CSV", None), ExportFormat::Csv);
+        assert_eq!(format("report.json", None), ExportFormat::Json);
+        assert_eq!(format("report", None), ExportFormat::Json);
+        assert_eq!(
+            format("report.txt", Some(ExportFormat::Csv)),
+            ExportFormat::Csv
+        );
+    }
+
+    #[test]
+    fn should_export_processes_as_json() {
+        let now = UNIX_EPOCH + Duration::from_secs(4_600);
+        let some_process = some_process();
+        let missing = Process {
+            pid: 1,
+            user_name: "a \"quoted\"\\name\n".to_string(),
+            start_timestamp: 4_600,
+            ..Default::default()
+        };
+        let json = to_json(&[
+            ExportedProcess::new(&some_process, now),
+            ExportedProcess::new(&missing, now),
+        ])
+        .unwrap();
+        let exported: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let first = &exported[0];
+        assert_eq!(first["user"], "root");
+        assert_eq!(first["pid"], 1234);
+        assert_eq!(first["parent_pid"], 1);
+        assert_eq!(first["run_time"], 3600);
+        assert_eq!(first["memory"], 2048);
+        assert_eq!(first["nice"], -5);
+        assert_eq!(first["cmd_path"], "/usr/sbin/nginx");
+        assert_eq!(first["args"], "-g,daemon off;,");
+        assert_eq!(first["ports"], "80, 443");
+        let second = &exported[1];
+        assert_eq!(second["user"], "a \"quoted\"\\name\n");
+        assert!(second["parent_pid"].is_null());
+        assert!(second["nice"].is_null());
+        assert!(second["cmd_path"].is_null());
+        assert!(second["ports"].is_null());
+        assert_eq!(to_json(&[]).unwrap(), "[]\n");
+    }
+
+    #[test]
+    fn should_export_processes_as_csv() {
+        let now = UNIX_EPOCH + Duration::from_secs(4_600);
+        let quoted = Process {
+            cmd: "say \"hi\"".to_string(),
+            nice: None,
+            ..some_process()
+        };
+        let some_process = some_process();
+        let csv = to_csv(&[
+            ExportedProcess::new(&some_process, now),
+            ExportedProcess::new(&quoted, now),
+        ])
+        .unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "user,pid,parent_pid,start_time,run_time,memory,nice,cmd,cmd_path,args,ports"
+        );
+        assert!(lines[1].starts_with("root,1234,1,"));
+        assert!(lines[1]
+            .ends_with(",3600,2048,-5,nginx,/usr/sbin/nginx,\"-g,daemon off;,\",\"80, 443\""));
+        assert!(lines[2].contains(",2048,,\"say \"\"hi\"\"\",/usr/sbin/nginx,"));
+    }
+
+    #[test]
+    fn should_write_export_file() {
+        let path = std::env::temp_dir().join(format!("pik-export-{}.csv", std::process::id()));
+        let export = Export::new(path.clone(), None);
+        let written = export.write([some_process()].iter()).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written, path);
+        assert_eq!(content.lines().count(), 2);
+    }
+}
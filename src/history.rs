@@ -0,0 +1,172 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+/// Number of queries kept in history file
+const HISTORY_SIZE: usize = 100;
+
+/// Recently used queries, most recent first, browsed like shell history
+pub struct SearchHistory {
+    entries: Vec<String>,
+    /// Index of entry shown while browsing, None when user is not browsing
+    cursor: Option<usize>,
+    path: Option<PathBuf>,
+}
+
+impl SearchHistory {
+    /// Loads history from data directory, missing or unreadable file means empty history
+    pub fn load() -> Self {
+        let path = directories::ProjectDirs::from("", "", "pik")
+            .map(|dirs| dirs.data_dir().join("history"));
+        let entries = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|content| content.lines().map(|line| line.to_string()).collect())
+            .unwrap_or_default();
+        Self::new(entries, path)
+    }
+
+    pub(crate) fn new(entries: Vec<String>, path: Option<PathBuf>) -> Self {
+        let mut history = Self {
+            entries: vec![],
+            cursor: None,
+            path,
+        };
+        //NOTE: entries are added oldest first, so file edited by hand is deduplicated too
+        for entry in entries.iter().rev() {
+            history.add(entry);
+        }
+        history
+    }
+
+    /// Moves query to the top of history, empty queries are not stored
+    pub fn add(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| entry != query);
+        self.entries.insert(0, query.to_string());
+        self.entries.truncate(HISTORY_SIZE);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn is_browsing(&self) -> bool {
+        self.cursor.is_some()
+    }
+
+    pub fn stop_browsing(&mut self) {
+        self.cursor = None;
+    }
+
+    /// Older query than the one shown, the oldest one is kept when there is no more
+    pub fn older(&mut self) -> Option<&str> {
+        let index = match self.cursor {
+            Some(index) => (index + 1).min(self.entries.len().checked_sub(1)?),
+            None => 0,
+        };
+        let entry = self.entries.get(index)?;
+        self.cursor = Some(index);
+        Some(entry)
+    }
+
+    /// Newer query than the one shown, None when browsing went past the most recent one
+    pub fn newer(&mut self) -> Option<&str> {
+        match self.cursor {
+            Some(index) if index > 0 => {
+                self.cursor = Some(index - 1);
+                self.entries.get(index - 1).map(|entry| entry.as_str())
+            }
+            _ => {
+                self.cursor = None;
+                None
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create history directory: {:?}", dir))?;
+        }
+        let mut content = self.entries.join("\n");
+        content.push('\n');
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to save history to file: {:?}", path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(entries: &[&str]) -> SearchHistory {
+        SearchHistory::new(entries.iter().map(|e| e.to_string()).collect(), None)
+    }
+
+    #[test]
+    fn should_keep_unique_entries_most_recent_first() {
+        let mut history = history(&["java", ":8080", "java"]);
+        assert_eq!(history.entries, vec!["java", ":8080"]);
+
+        history.add("  ");
+        history.add("ssh");
+        history.add("ssh");
+        history.add(":8080 ");
+        assert_eq!(history.entries, vec![":8080", "ssh", "java"]);
+    }
+
+    #[test]
+    fn should_keep_limited_number_of_entries() {
+        let mut history = history(&[]);
+        for i in 0..HISTORY_SIZE + 10 {
+            history.add(&i.to_string());
+        }
+        assert_eq!(history.entries.len(), HISTORY_SIZE);
+        assert_eq!(history.entries[0], (HISTORY_SIZE + 9).to_string());
+    }
+
+    #[test]
+    fn should_browse_history() {
+        let mut history = history(&["ssh", "java"]);
+        assert!(!history.is_browsing());
+        assert_eq!(history.older(), Some("ssh"));
+        assert_eq!(history.older(), Some("java"));
+        assert_eq!(history.older(), Some("java"));
+        assert!(history.is_browsing());
+        assert_eq!(history.newer(), Some("ssh"));
+        assert_eq!(history.newer(), None);
+        assert!(!history.is_browsing());
+
+        history.older();
+        history.stop_browsing();
+        assert_eq!(history.older(), Some("ssh"));
+    }
+
+    #[test]
+    fn should_not_browse_empty_history() {
+        let mut history = history(&[]);
+        assert_eq!(history.older(), None);
+        assert!(!history.is_browsing());
+        assert_eq!(history.newer(), None);
+    }
+
+    #[test]
+    fn should_save_history_most_recent_first() {
+        let path = std::env::temp_dir().join(format!("pik-history-{}", std::process::id()));
+        let mut history = SearchHistory::new(vec![], Some(path.clone()));
+        history.add("java");
+        history.add(":80,443");
+        history.save().unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(content, ":80,443\njava\n");
+    }
+}
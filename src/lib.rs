@@ -1,5 +1,7 @@
 pub mod args;
 pub mod config;
+pub mod export;
+pub mod history;
 pub mod processes;
 pub mod settings;
 pub mod tui;
@@ -9,5 +9,6 @@ fn main() -> Result<()> {
     let args = CliArgs::parse();
 
     let settings = AppSettings::from(config, &args);
-    start_app(args.query, settings)
+    let query = settings.initial_query(&args)?;
+    start_app(query, settings)
 }
@@ -1,30 +1,67 @@
-use std::collections::HashMap;
-use std::time::SystemTime;
-
-use anyhow::Result;
-use sysinfo::{Pid, System, Uid, Users};
-use sysinfo::{ProcessRefreshKind, RefreshKind};
-
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, Result};
+use sysinfo::{Pid, System, Uid};
+
+mod connections;
+mod containers;
+mod cpu_time;
+mod disk_io;
+mod environment;
+mod files;
 mod filters;
+mod scan;
+mod signals;
+mod sorting;
+mod terminals;
+mod tree;
+mod units;
 mod utils;
 
+pub use connections::{process_connections, Connection, Protocol};
+pub use disk_io::IoRate;
+pub use environment::process_environment;
+pub use files::{process_fd_count, process_open_files, FileKind, OpenFile};
+pub use filters::CaseSensitivity;
 pub use filters::FilterOptions;
+pub use filters::QueryMatches;
 pub use filters::SearchBy;
+pub use signals::{kill_signals, parse_kill_signal, KillSignal, FORCE_KILL_SIGNAL};
+#[cfg(unix)]
+pub use signals::{PAUSE_SIGNAL, RESUME_SIGNAL};
+pub use sorting::{Sort, SortColumn, SortOrder};
 
 use filters::QueryFilter;
 
-pub type ProcessPorts = HashMap<u32, String>;
+type ProcessPorts = HashMap<u32, ListeningPorts>;
+
+/// Listening sockets of a process with compact list of their ports, i.e. '80, 443'
+struct ListeningPorts {
+    connections: Vec<Connection>,
+    summary: String,
+}
 
 pub struct ProcessManager {
-    sys: System,
-    users: Users,
-    process_ports: ProcessPorts,
+    scanned: Scanned,
+    /// Scan running in background thread, scanned state is empty until it is done
+    background_scan: Option<BackgroundScan>,
     current_user_id: Uid,
+    /// Only processes of this user are searched, set with --user
+    user_filter: Option<(Uid, String)>,
+    /// Pik and processes it was started from, killing any of them would kill pik too
+    self_and_ancestors: HashSet<u32>,
+    /// Processes shown at the top of results whether they match the query or not
+    pinned: HashSet<u32>,
 }
 
+use self::connections::{listening_connections, ports_summary};
+use self::containers::SHORT_ID_LENGTH;
 use self::filters::OptionsFilter;
+use self::scan::{BackgroundScan, Scanned, ScannedProcess};
 use self::utils::{
-    find_current_process_user, get_process_args, process_run_time, process_start_time,
+    find_current_process_user, format_bytes, format_cpu_time, format_run_time, get_process_args,
+    process_cmd_line, process_owner, process_run_duration, process_state, DELETED_EXE_SUFFIX,
 };
 
 pub trait ProcessInfo {
@@ -32,6 +69,9 @@ pub trait ProcessInfo {
 
     fn user_id(&self) -> Option<&Uid>;
 
+    /// User whose permissions process has, differs from real user i.e. for setuid binaries
+    fn effective_user_id(&self) -> Option<&Uid>;
+
     fn cmd(&self) -> &str;
 
     fn cmd_path(&self) -> Option<&str>;
@@ -42,66 +82,191 @@ pub trait ProcessInfo {
 
     fn memory(&self) -> u64;
 
+    fn cpu_usage(&self) -> f32;
+
     fn start_time(&self) -> u64;
 
     fn run_time(&self) -> u64;
 
     fn args(&self) -> Vec<&str>;
+
+    /// State code as shown by ps, i.e. 'Z' for zombie
+    fn state(&self) -> char;
+
+    /// Number of threads, None when platform does not provide it
+    fn threads(&self) -> Option<usize>;
+
+    /// Nice value, None on platforms without process priorities
+    fn nice(&self) -> Option<i32>;
+
+    /// Binary was deleted or replaced on disk since the process started
+    fn exe_deleted(&self) -> bool;
+
+    /// Full id of the container process runs in, None outside containers
+    fn container_id(&self) -> Option<&str>;
+
+    /// Systemd unit of the process, None on hosts without systemd
+    fn unit(&self) -> Option<&str>;
+
+    /// Controlling terminal, i.e. 'pts/3', None for daemons
+    fn tty(&self) -> Option<&str>;
+
+    /// Time spent on cpu since the process started, None when platform does not provide it
+    fn cpu_time(&self) -> Option<Duration>;
+
+    /// Current working directory, None when it cannot be read (i.e. process of other user)
+    fn cwd(&self) -> Option<&str>;
 }
 
-impl ProcessInfo for sysinfo::Process {
+//NOTE: everything comes from the last scan, so searching while typing does not touch the system
+impl ProcessInfo for ScannedProcess<'_> {
     fn is_thread(&self) -> bool {
-        self.thread_kind().is_some()
+        self.prc.thread_kind().is_some()
     }
 
     fn user_id(&self) -> Option<&Uid> {
-        self.user_id()
+        self.prc.user_id()
+    }
+
+    //NOTE: effective user is not known on every platform, real user is used then
+    fn effective_user_id(&self) -> Option<&Uid> {
+        self.prc.effective_user_id().or(self.prc.user_id())
     }
 
     fn cmd(&self) -> &str {
-        self.name().to_str().unwrap_or_default()
+        self.prc.name().to_str().unwrap_or_default()
     }
 
+    //NOTE: path is read once, it has deleted suffix only when binary was gone already
     fn cmd_path(&self) -> Option<&str> {
-        self.exe().map(|e| e.to_str()).unwrap_or_default()
+        self.prc
+            .exe()
+            .and_then(|e| e.to_str())
+            .map(|path| path.strip_suffix(DELETED_EXE_SUFFIX).unwrap_or(path))
     }
 
     fn pid(&self) -> u32 {
-        self.pid().as_u32()
+        self.prc.pid().as_u32()
     }
 
     fn parent_id(&self) -> Option<u32> {
-        self.parent().map(|p| p.as_u32())
+        self.prc.parent().map(|p| p.as_u32())
     }
 
     fn memory(&self) -> u64 {
-        self.memory()
+        self.prc.memory()
+    }
+
+    fn cpu_usage(&self) -> f32 {
+        self.prc.cpu_usage()
     }
 
     fn start_time(&self) -> u64 {
-        self.start_time()
+        self.prc.start_time()
     }
 
     fn run_time(&self) -> u64 {
-        self.start_time()
+        self.prc.start_time()
     }
 
     fn args(&self) -> Vec<&str> {
-        self.cmd().iter().filter_map(|a| a.to_str()).collect()
+        self.prc.cmd().iter().filter_map(|a| a.to_str()).collect()
+    }
+
+    fn state(&self) -> char {
+        process_state(self.prc.status())
+    }
+
+    //NOTE: tasks are known only on linux and exclude the main thread
+    fn threads(&self) -> Option<usize> {
+        self.prc.tasks().map(|tasks| tasks.len() + 1)
+    }
+
+    fn nice(&self) -> Option<i32> {
+        self.extras.nice
+    }
+
+    fn exe_deleted(&self) -> bool {
+        self.extras.exe_deleted
+    }
+
+    fn container_id(&self) -> Option<&str> {
+        self.extras.container_id.as_deref()
+    }
+
+    fn unit(&self) -> Option<&str> {
+        self.extras.unit.as_deref()
+    }
+
+    fn tty(&self) -> Option<&str> {
+        self.extras.tty.as_deref()
+    }
+
+    fn cpu_time(&self) -> Option<Duration> {
+        self.extras.cpu_time
+    }
+
+    fn cwd(&self) -> Option<&str> {
+        self.prc.cwd().and_then(|cwd| cwd.to_str())
     }
 }
 
 #[derive(Debug)]
 pub struct ProcessSearchResults {
-    pub search_by: SearchBy,
+    pub search_by: Vec<SearchBy>,
+    pub active_filters: usize,
+    /// Number of matching processes removed because they are pik or its ancestors
+    pub hidden: usize,
+    /// Memory thresholds from the query, i.e. '>500'
+    pub memory_filter: Option<String>,
+    /// Port lists and ranges from the query, i.e. '8000-8100'
+    pub port_filter: Option<String>,
+    /// Name of the user all processes are limited to, i.e. with --user
+    pub user_filter: Option<String>,
     items: Vec<Process>,
+    tree: bool,
+    /// Processes whose descendants are hidden in tree view
+    collapsed: HashSet<u32>,
+    /// Descendants of collapsed processes, kept so they can be shown again without searching
+    hidden_items: Vec<Process>,
+    /// Processes running the same executable are shown as one row, it has no effect in tree view
+    grouped: bool,
+    /// Executables whose groups show their processes below the group row
+    expanded_groups: HashSet<String>,
+    /// Groups are ordered by their totals so the last sort is kept
+    sort: Option<Sort>,
+}
+
+/// Sums over processes in search results
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ResultsTotals {
+    pub count: usize,
+    pub memory: u64,
+    pub cpu_usage: f32,
+}
+
+impl ResultsTotals {
+    pub fn memory_as_string(&self) -> String {
+        format_bytes(self.memory)
+    }
 }
 
 impl ProcessSearchResults {
     pub fn empty() -> Self {
         Self {
-            search_by: SearchBy::None,
+            search_by: vec![],
+            active_filters: 0,
+            hidden: 0,
+            memory_filter: None,
+            port_filter: None,
+            user_filter: None,
             items: vec![],
+            tree: false,
+            collapsed: HashSet::new(),
+            hidden_items: vec![],
+            grouped: false,
+            expanded_groups: HashSet::new(),
+            sort: None,
         }
     }
 
@@ -118,147 +283,627 @@ impl ProcessSearchResults {
         self.items.get(index)
     }
 
+    /// Adds process that does not match the query until next search, it is shown like context in tree view
+    pub fn insert_context(&mut self, index: usize, mut prc: Process) {
+        prc.context_only = true;
+        self.items.insert(index.min(self.items.len()), prc);
+    }
+
     pub fn remove(&mut self, pid: u32) {
-        self.items.retain(|prc| prc.pid != pid)
+        self.items.retain(|prc| prc.pid != pid);
+        self.hidden_items.retain(|prc| prc.pid != pid);
+    }
+
+    /// Counts open file descriptors of processes at given indexes, the count is kept until next search.
+    /// Group rows are skipped, they stand for several processes. Returns whether any count was read
+    pub fn load_open_fds(&mut self, indexes: impl IntoIterator<Item = usize>) -> bool {
+        let mut loaded = false;
+        for index in indexes {
+            if let Some(prc) = self.items.get_mut(index) {
+                if prc.open_fds.is_none() && prc.group.is_none() {
+                    prc.open_fds = process_fd_count(prc.pid);
+                    loaded |= prc.open_fds.is_some();
+                }
+            }
+        }
+        loaded
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &Process> {
         self.items.iter()
     }
+
+    pub fn position(&self, pid: u32) -> Option<usize> {
+        self.items.iter().position(|prc| prc.pid == pid)
+    }
+
+    /// Index of previously selected process in new results, nearest index is used when it is gone
+    pub fn selection_after_refresh(&self, pid: u32, previous_index: usize) -> Option<usize> {
+        self.position(pid).or_else(|| {
+            self.len()
+                .checked_sub(1)
+                .map(|last| previous_index.min(last))
+        })
+    }
+
+    /// Ancestors shown only for context in tree view are not counted, collapsed descendants
+    /// and processes of collapsed groups are
+    pub fn totals(&self) -> ResultsTotals {
+        self.items
+            .iter()
+            .chain(&self.hidden_items)
+            .filter(|prc| !prc.context_only && prc.group.is_none())
+            .fold(ResultsTotals::default(), |totals, prc| ResultsTotals {
+                count: totals.count + 1,
+                memory: totals.memory + prc.memory,
+                cpu_usage: totals.cpu_usage + prc.cpu_usage,
+            })
+    }
+
+    /// Process has children shown below it in tree view
+    pub fn has_children(&self, pid: u32) -> bool {
+        self.tree && self.items.iter().any(|prc| prc.parent_pid == Some(pid))
+    }
+
+    /// Hides (or shows again) descendants of given processes, it has effect only in tree view
+    pub fn collapse(&mut self, collapsed: &HashSet<u32>) {
+        if self.tree && self.collapsed != *collapsed {
+            self.collapsed = collapsed.clone();
+            self.arrange_as_tree();
+        }
+    }
+
+    /// Groups processes running the same executable, processes of expanded groups are shown
+    /// below the group row. It has no effect in tree view
+    pub fn group_by_exe(&mut self, expanded: &HashSet<String>) {
+        if self.tree {
+            return;
+        }
+        self.grouped = true;
+        self.expanded_groups = expanded.clone();
+        self.arrange_in_groups();
+    }
+
+    /// Pids of all processes in the group, only its own pid for process that is not a group
+    pub fn group_members(&self, prc: &Process) -> Vec<u32> {
+        if prc.group.is_none() {
+            return vec![prc.pid];
+        }
+        self.items
+            .iter()
+            .chain(&self.hidden_items)
+            .filter(|p| p.group.is_none() && !p.pinned && p.exe() == prc.exe())
+            .map(|p| p.pid)
+            .collect()
+    }
+
+    /// In tree view only siblings are sorted, pinned processes always stay at the top
+    pub fn sort(&mut self, sort: Sort) {
+        self.sort = Some(sort);
+        self.items.append(&mut self.hidden_items);
+        self.items.retain(|prc| prc.group.is_none());
+        self.items
+            .sort_by(|a, b| b.pinned.cmp(&a.pinned).then_with(|| sort.compare(a, b)));
+        if self.tree {
+            self.arrange_as_tree();
+        } else if self.grouped {
+            self.arrange_in_groups();
+        }
+    }
+
+    //NOTE: group is placed where its best ranked process was, unless results are sorted
+    fn arrange_in_groups(&mut self) {
+        self.items.append(&mut self.hidden_items);
+        self.items.retain(|prc| prc.group.is_none());
+        let (pinned, items): (Vec<Process>, Vec<Process>) = std::mem::take(&mut self.items)
+            .into_iter()
+            .partition(|prc| prc.pinned);
+        let mut groups: Vec<Vec<Process>> = vec![];
+        let mut group_index: HashMap<String, usize> = HashMap::new();
+        for prc in items {
+            match group_index.get(prc.exe()) {
+                Some(index) => groups[*index].push(prc),
+                None => {
+                    group_index.insert(prc.exe().to_string(), groups.len());
+                    groups.push(vec![prc]);
+                }
+            }
+        }
+        let mut rows: Vec<Vec<Process>> = groups
+            .into_iter()
+            .map(|members| self.group_rows(members))
+            .collect();
+        if let Some(sort) = self.sort {
+            rows.sort_by(|a, b| sort.compare(&a[0], &b[0]));
+        }
+        self.items = pinned;
+        self.items.extend(rows.into_iter().flatten());
+    }
+
+    /// Group row with summed memory and cpu usage, followed by its processes when it is expanded.
+    /// Processes of collapsed group are hidden
+    fn group_rows(&mut self, mut members: Vec<Process>) -> Vec<Process> {
+        if members.len() < 2 {
+            return members;
+        }
+        let expanded = self.expanded_groups.contains(members[0].exe());
+        let group = Process {
+            memory: members.iter().map(|prc| prc.memory).sum(),
+            cpu_usage: members.iter().map(|prc| prc.cpu_usage).sum(),
+            cpu_time: members.iter().map(|prc| prc.cpu_time).sum(),
+            group: Some(ProcessGroup {
+                count: members.len(),
+                expanded,
+            }),
+            tree_prefix: match expanded {
+                true => "▾ ".to_string(),
+                false => "▸ ".to_string(),
+            },
+            ..members[0].clone()
+        };
+        if !expanded {
+            self.hidden_items.append(&mut members);
+            return vec![group];
+        }
+        for member in members.iter_mut() {
+            member.tree_prefix = "  ".to_string();
+        }
+        std::iter::once(group).chain(members).collect()
+    }
+
+    //NOTE: pinned processes are kept above the tree, they are not part of it
+    fn arrange_as_tree(&mut self) {
+        self.items.append(&mut self.hidden_items);
+        let (pinned, items): (Vec<Process>, Vec<Process>) = std::mem::take(&mut self.items)
+            .into_iter()
+            .partition(|prc| prc.pinned);
+        let nodes: Vec<_> = items.iter().map(|p| (p.pid, p.parent_pid)).collect();
+        let mut items: Vec<Option<Process>> = items.into_iter().map(Some).collect();
+        self.items = pinned;
+        self.items.extend(
+            tree::tree_order(&nodes, &self.collapsed)
+                .into_iter()
+                .filter_map(|(index, prefix)| {
+                    let mut prc = items[index].take()?;
+                    prc.tree_prefix = prefix;
+                    Some(prc)
+                }),
+        );
+        //NOTE: processes left out of the tree are descendants of collapsed ones
+        self.hidden_items = items.into_iter().flatten().collect();
+    }
 }
 
+/// User name standing for the user running pik, i.e. '--user me'
+const CURRENT_USER: &str = "me";
+
 impl ProcessManager {
     pub fn new() -> Result<Self> {
-        let sys = System::new_with_specifics(
-            RefreshKind::default().with_processes(process_refresh_kind()),
-        );
-        let users = Users::new_with_refreshed_list();
-        let process_ports = refresh_ports();
-        let current_user_id = find_current_process_user(&sys)?;
+        let scanned = Scanned::new();
+        let current_user_id = find_current_process_user(&scanned.sys)?;
+        let self_and_ancestors = find_self_and_ancestors(&scanned.sys);
         Ok(Self {
-            sys,
-            users,
-            process_ports,
+            scanned,
+            background_scan: None,
             current_user_id,
+            user_filter: None,
+            self_and_ancestors,
+            pinned: HashSet::new(),
         })
     }
 
-    pub fn find_processes(&mut self, query: &str, options: FilterOptions) -> ProcessSearchResults {
-        let process_filter = QueryFilter::new(query);
-        let options_filter = OptionsFilter::new(options, &self.current_user_id);
+    /// Limits all searches to processes of the user, 'me' is the user running pik
+    pub fn filter_by_user(&mut self, name: &str) -> Result<()> {
+        let user = match name {
+            CURRENT_USER => self.scanned.users.get_user_by_id(&self.current_user_id),
+            name => self.scanned.users.iter().find(|user| user.name() == name),
+        };
+        let user = user.ok_or_else(|| anyhow!("User '{}' not found", name))?;
+        self.user_filter = Some((user.id().clone(), user.name().to_string()));
+        Ok(())
+    }
 
-        let items = self
-            .sys
+    /// Searches processes from the last scan, the system is not scanned again until refresh
+    pub fn find_processes(
+        &mut self,
+        query: &str,
+        options: FilterOptions,
+    ) -> Result<ProcessSearchResults> {
+        let process_filter = match options.regex_search {
+            true => QueryFilter::regex(query)?,
+            false => QueryFilter::with_search_mode(query, options.search_mode)?,
+        };
+        let process_filter = process_filter
+            .fuzzy(options.fuzzy_search)
+            .case_sensitivity(options.case_sensitivity)
+            .with_users(self.scanned.users.iter().map(|u| (u.id(), u.name())))
+            .with_containers(&self.scanned.container_names);
+        let user_filter = self.user_filter.as_ref().map(|(id, _)| id);
+        let options_filter = OptionsFilter::new(options, &self.current_user_id, user_filter);
+
+        let mut hidden = 0;
+        let mut ranked_items: Vec<_> = self
+            .scanned
             .processes()
-            .values()
             .filter_map(|prc| {
-                let ports = self.process_ports.get(&prc.pid().as_u32());
-                if !options_filter.accept(prc)
-                    || !process_filter.accept(prc, ports.map(|p| p.as_str()))
+                let pid = prc.pid();
+                let listening = self.scanned.process_ports.get(&pid);
+                let ports = listening.map(|p| p.summary.as_str());
+                let pinned = self.pinned.contains(&pid);
+                if !pinned && (!options_filter.accept(&prc) || !process_filter.accept(&prc, ports))
                 {
                     return None;
                 }
-                Some(self.create_process_info(prc, ports))
+                if !pinned && options.hide_self && self.self_and_ancestors.contains(&pid) {
+                    hidden += 1;
+                    return None;
+                }
+                let (score, mut matches) = process_filter.rank(&prc);
+                matches.ports = process_filter.matched_ports(ports);
+                let mut process = self.create_process_info(&prc, listening);
+                process.matches = matches;
+                process.pinned = pinned;
+                Some((score, process))
             })
             .collect();
+        //NOTE: stable sort keeps original order for processes that are not ranked
+        ranked_items.sort_by(|(a, _), (b, _)| b.cmp(a));
+        let mut items: Vec<Process> = ranked_items.into_iter().map(|(_, prc)| prc).collect();
+        let search_by = process_filter.search_by();
+        if search_by.contains(&SearchBy::User) {
+            items.sort_by(|a, b| a.user_name.cmp(&b.user_name));
+        }
+        items.sort_by_key(|prc| !prc.pinned);
+        if options.tree_view {
+            self.add_ancestors(&mut items, options.hide_self);
+        }
 
-        ProcessSearchResults {
-            search_by: process_filter.search_by,
+        let mut results = ProcessSearchResults {
+            search_by,
+            active_filters: process_filter.active_filters(),
+            hidden,
+            memory_filter: process_filter.memory_filter(),
+            port_filter: process_filter.port_filter(),
+            user_filter: self.user_filter.as_ref().map(|(_, name)| name.clone()),
             items,
+            tree: options.tree_view,
+            collapsed: HashSet::new(),
+            hidden_items: vec![],
+            grouped: false,
+            expanded_groups: HashSet::new(),
+            sort: None,
+        };
+        if results.tree {
+            results.arrange_as_tree();
+        }
+        Ok(results)
+    }
+
+    /// Adds parents of found processes up to the root, so tree shows where processes come from
+    fn add_ancestors(&self, items: &mut Vec<Process>, hide_self: bool) {
+        let mut pids: HashSet<u32> = items.iter().map(|prc| prc.pid).collect();
+        //NOTE: hidden processes are never added, tree branch ends below them
+        if hide_self {
+            pids.extend(self.self_and_ancestors.iter());
+        }
+        let mut ancestors = vec![];
+        //NOTE: pinned processes are shown above the tree, so their ancestors are not needed
+        for prc in items.iter().filter(|prc| !prc.pinned) {
+            let mut parent_pid = prc.parent_pid;
+            while let Some(pid) = parent_pid.filter(|pid| pids.insert(*pid)) {
+                let Some(parent) = self.scanned.process(pid) else {
+                    break;
+                };
+                let listening = self.scanned.process_ports.get(&pid);
+                let mut ancestor = self.create_process_info(&parent, listening);
+                ancestor.context_only = true;
+                parent_pid = ancestor.parent_pid;
+                ancestors.push(ancestor);
+            }
         }
+        items.extend(ancestors);
     }
 
+    /// Scans all processes again, it is much slower than searching them
     pub fn refresh(&mut self) {
-        self.sys
-            .refresh_processes_specifics(sysinfo::ProcessesToUpdate::All, process_refresh_kind());
-        // TODO: do we really need to refresh users?
-        self.users.refresh_list();
-        self.process_ports = refresh_ports();
-    }
-
-    fn create_process_info(&self, prc: &impl ProcessInfo, ports: Option<&String>) -> Process {
-        let user_name = prc
-            .user_id()
-            .map(|user_id| {
-                self.users
-                    .get_user_by_id(user_id)
-                    .map(|u| u.name().to_string())
-                    .unwrap_or(format!("{}?", **user_id))
-            })
-            .unwrap_or("unknown".to_string());
+        if let Some(scan) = self.background_scan.take() {
+            self.scanned = scan.wait();
+        }
+        self.scanned.refresh();
+        self.retain_pins();
+    }
+
+    /// Scans processes in background thread, processes cannot be searched until it is done.
+    /// Refresh requested while scanning makes the scan run once more, so only the latest state is used
+    pub fn start_refresh(&mut self) {
+        match self.background_scan.as_mut() {
+            Some(scan) => scan.rescan = true,
+            None => {
+                let scanned = std::mem::take(&mut self.scanned);
+                self.background_scan = Some(BackgroundScan::start(scanned));
+            }
+        }
+    }
+
+    pub fn is_refreshing(&self) -> bool {
+        self.background_scan.is_some()
+    }
+
+    /// Takes over state scanned in background, returns true once processes can be searched again
+    pub fn finish_refresh(&mut self) -> bool {
+        let Some(scanned) = self
+            .background_scan
+            .as_ref()
+            .and_then(|scan| scan.try_finish())
+        else {
+            return false;
+        };
+        let rescan = self.background_scan.take().is_some_and(|scan| scan.rescan);
+        self.scanned = scanned;
+        if rescan {
+            self.start_refresh();
+            return false;
+        }
+        self.retain_pins();
+        true
+    }
+
+    /// Waits for scan running in background, if there is any
+    pub fn wait_for_refresh(&mut self) {
+        if let Some(scan) = self.background_scan.take() {
+            let rescan = scan.rescan;
+            self.scanned = scan.wait();
+            if rescan {
+                self.scanned.refresh();
+            }
+            self.retain_pins();
+        }
+    }
+
+    //NOTE: pins are kept by pid, only processes that are gone lose them
+    fn retain_pins(&mut self) {
+        let sys = &self.scanned.sys;
+        self.pinned
+            .retain(|pid| sys.process(Pid::from_u32(*pid)).is_some());
+    }
+
+    fn create_process_info(
+        &self,
+        prc: &impl ProcessInfo,
+        listening: Option<&ListeningPorts>,
+    ) -> Process {
+        let user_name = self.user_name(prc.effective_user_id());
+        let real_user_name = self.user_name(prc.user_id());
         let cmd = prc.cmd().to_string();
         let cmd_path = prc.cmd_path().map(|p| p.to_string());
         let pid = prc.pid();
+        let is_self_or_ancestor = self.self_and_ancestors.contains(&pid);
+        let container_id = prc.container_id();
+        let container_name =
+            container_id.and_then(|id| self.scanned.container_names.get(id).cloned());
 
         Process {
             pid,
             parent_pid: prc.parent_id(),
+            parent_cmd: prc
+                .parent_id()
+                .and_then(|parent| self.scanned.process(parent))
+                .map(|parent| parent.cmd().to_string()),
             args: get_process_args(prc).join(",").to_string(),
+            cmd_line: process_cmd_line(prc),
             cmd,
             cmd_path,
+            user_id: prc.effective_user_id().cloned(),
+            real_user_id: prc.user_id().cloned(),
+            owner: process_owner(prc.effective_user_id(), &self.current_user_id),
             user_name,
-            ports: ports.cloned(),
+            real_user_name,
+            ports: listening.map(|p| p.summary.clone()),
+            connections: listening.map(|p| p.connections.clone()).unwrap_or_default(),
             memory: prc.memory(),
-            start_time: process_start_time(prc.start_time()),
-            run_time: process_run_time(prc.run_time(), SystemTime::now()),
+            state: prc.state(),
+            threads: prc.threads(),
+            nice: prc.nice(),
+            exe_deleted: prc.exe_deleted(),
+            io_rate: self.scanned.io_monitor.rate(pid),
+            container_id: container_id.map(|id| id.to_string()),
+            container_name,
+            unit: prc.unit().map(|unit| unit.to_string()),
+            tty: prc.tty().map(|tty| tty.to_string()),
+            cwd: prc.cwd().map(|cwd| cwd.to_string()),
+            cpu_usage: prc.cpu_usage(),
+            cpu_time: prc.cpu_time(),
+            open_fds: None,
+            start_timestamp: prc.start_time(),
+            matches: QueryMatches::default(),
+            tree_prefix: String::new(),
+            context_only: false,
+            pinned: false,
+            is_self_or_ancestor,
+            group: None,
         }
     }
 
-    pub fn kill_process(&self, pid: u32) -> bool {
-        return match self.sys.process(Pid::from_u32(pid)) {
-            Some(prc) => {
-                if sysinfo::SUPPORTED_SIGNALS.contains(&sysinfo::Signal::Term) {
-                    prc.kill_with(sysinfo::Signal::Term).unwrap_or(false)
-                } else {
-                    prc.kill()
-                }
-            }
-            None => false,
+    /// Name of the user, numeric id is used for users without name (i.e. without passwd entry)
+    fn user_name(&self, user_id: Option<&Uid>) -> String {
+        let Some(user_id) = user_id else {
+            return "unknown".to_string();
         };
+        self.scanned
+            .users
+            .get_user_by_id(user_id)
+            .map(|user| user.name())
+            .filter(|name| !name.is_empty())
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| (**user_id).to_string())
+    }
+
+    /// Process with all its descendants, children come before parents so they are killed first
+    pub fn process_subtree(&self, pid: u32) -> Vec<u32> {
+        let nodes: Vec<_> = self
+            .scanned
+            .processes()
+            //NOTE: threads are gone together with their process
+            .filter(|prc| !prc.is_thread())
+            .map(|prc| (prc.pid(), prc.parent_id()))
+            .collect();
+        tree::subtree_children_first(pid, &nodes)
+    }
+
+    pub fn is_self_or_ancestor(&self, pid: u32) -> bool {
+        self.self_and_ancestors.contains(&pid)
+    }
+
+    /// Pins process or unpins it when it is already pinned, returns whether it is pinned now
+    pub fn toggle_pin(&mut self, pid: u32) -> bool {
+        if !self.pinned.remove(&pid) {
+            self.pinned.insert(pid);
+            return true;
+        }
+        false
+    }
+
+    /// Process from the last scan whether it matches the query or not, i.e. parent of found process
+    pub fn process(&self, pid: u32) -> Option<Process> {
+        let prc = self.scanned.process(pid)?;
+        let listening = self.scanned.process_ports.get(&pid);
+        Some(self.create_process_info(&prc, listening))
+    }
+
+    /// Arguments of the process one by one, unlike joined args they can be told apart when they contain commas
+    pub fn process_args(&self, pid: u32) -> Option<Vec<String>> {
+        let prc = self.scanned.process(pid)?;
+        Some(
+            get_process_args(&prc)
+                .into_iter()
+                .map(|arg| arg.to_string())
+                .collect(),
+        )
+    }
+
+    /// Process was found by the last refresh
+    pub fn is_running(&self, pid: u32) -> bool {
+        self.scanned.sys.process(Pid::from_u32(pid)).is_some()
+    }
+
+    /// Sends any signal, not only the ones that end process, i.e. SIGSTOP
+    pub fn kill_process(&self, pid: u32, signal: KillSignal) -> Result<()> {
+        signals::send_signal(pid, signal)
+    }
+
+    /// Checks the process right now, unlike is_running it does not wait for refresh
+    pub fn is_terminated(&self, pid: u32) -> bool {
+        signals::is_terminated(pid)
     }
 }
 
-fn process_refresh_kind() -> ProcessRefreshKind {
-    ProcessRefreshKind::default()
-        .with_cpu()
-        .with_memory()
-        .with_cmd(sysinfo::UpdateKind::OnlyIfNotSet)
-        .with_exe(sysinfo::UpdateKind::OnlyIfNotSet)
-        .with_user(sysinfo::UpdateKind::OnlyIfNotSet)
+/// Walks up from pik's own process, init is never included
+fn find_self_and_ancestors(sys: &System) -> HashSet<u32> {
+    let mut pids = HashSet::new();
+    let mut pid = Some(std::process::id());
+    while let Some(current) = pid.filter(|pid| *pid > 1 && !pids.contains(pid)) {
+        pids.insert(current);
+        pid = sys
+            .process(Pid::from_u32(current))
+            .and_then(|prc| prc.parent())
+            .map(|parent| parent.as_u32());
+    }
+    pids
 }
 
-fn refresh_ports() -> HashMap<u32, String> {
-    listeners::get_all()
-        //NOTE: we ignore errors comming from listeners
-        .unwrap_or_default()
+fn refresh_ports() -> ProcessPorts {
+    listening_connections()
         .into_iter()
-        .fold(HashMap::new(), |mut acc: ProcessPorts, l| {
-            match acc.get_mut(&l.process.pid) {
-                Some(ports) => {
-                    ports.push_str(&format!(", {}", l.socket.port()));
-                }
-                None => {
-                    acc.insert(l.process.pid, format!("{}", l.socket.port()));
-                }
-            }
-            acc
+        .map(|(pid, connections)| {
+            let summary = ports_summary(&connections);
+            let ports = ListeningPorts {
+                connections,
+                summary,
+            };
+            (pid, ports)
         })
+        .collect()
 }
 
-#[derive(Debug)]
+/// Who runs the process, judged by its effective user
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Owner {
+    Root,
+    /// User running pik
+    CurrentUser,
+    #[default]
+    Other,
+}
+
+/// Processes running the same executable shown as one row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessGroup {
+    pub count: usize,
+    /// Processes of the group are shown below its row
+    pub expanded: bool,
+}
+
+#[derive(Debug, Default, Clone)]
 pub struct Process {
     pub pid: u32,
     pub parent_pid: Option<u32>,
+    /// Command of the parent process, it is resolved when processes are searched, None when parent is gone
+    pub parent_cmd: Option<String>,
+    /// Effective user, it is shown in the table like in ps
+    pub user_id: Option<Uid>,
+    /// Real user that started the process
+    pub real_user_id: Option<Uid>,
+    pub owner: Owner,
     pub user_name: String,
+    pub real_user_name: String,
     pub cmd: String,
     pub cmd_path: Option<String>,
     pub args: String,
+    /// Full command line with arguments separated by space
+    pub cmd_line: String,
+    /// Listening ports shown in the table, i.e. '80, 443'
     pub ports: Option<String>,
+    /// Listening sockets the ports come from, all connections are read only for selected process
+    pub connections: Vec<Connection>,
     pub memory: u64,
-    //FIXME: cpu rquires refresh twice!
-    // pub cpu_usage: f32,
-    pub start_time: String,
-    pub run_time: String,
+    pub state: char,
+    /// Number of threads, None when it is unknown
+    pub threads: Option<usize>,
+    /// Nice value from -20 to 19, None when it is not known
+    pub nice: Option<i32>,
+    /// Process runs binary that was deleted or replaced on disk, i.e. it needs restart after upgrade
+    pub exe_deleted: bool,
+    /// Disk read and write rate since previous refresh, None when it is not known
+    pub io_rate: Option<IoRate>,
+    /// Full id of Docker, Podman or Kubernetes container, None for processes outside containers
+    pub container_id: Option<String>,
+    /// Container name, resolved only when built with 'docker' feature
+    pub container_name: Option<String>,
+    /// Systemd unit, i.e. 'nginx.service' or 'user@1000/app-firefox.scope'
+    pub unit: Option<String>,
+    /// Controlling terminal, i.e. 'pts/3', None for daemons and on platforms other than linux
+    pub tty: Option<String>,
+    /// Current working directory, None when it cannot be read
+    pub cwd: Option<String>,
+    /// Percent of single cpu, can be above 100 for multithreaded processes
+    pub cpu_usage: f32,
+    /// Time spent on cpu, unlike run time it grows only while the process works
+    pub cpu_time: Option<Duration>,
+    /// Number of open file descriptors, it is counted only for processes on screen, see `load_open_fds`
+    pub open_fds: Option<usize>,
+    /// Seconds since epoch, it is formatted when rendered so format can be switched at any time
+    pub start_timestamp: u64,
+    pub matches: QueryMatches,
+    /// Box drawing prefix rendered before the name in tree view
+    pub tree_prefix: String,
+    /// Process does not match the query, it is shown only as ancestor of matching one in tree view
+    pub context_only: bool,
+    /// Pinned by user, it is shown at the top of results even when it does not match the query
+    pub pinned: bool,
+    /// Pik itself or its ancestor, i.e. shell or terminal it runs in
+    pub is_self_or_ancestor: bool,
+    /// Row stands for all processes running the same executable, pid is the one of the first
+    pub group: Option<ProcessGroup>,
 }
 
 impl Process {
@@ -266,9 +911,103 @@ impl Process {
         self.cmd_path.as_ref().unwrap_or(&self.cmd)
     }
 
+    pub fn memory_as_string(&self) -> String {
+        format_bytes(self.memory)
+    }
+
+    /// Number of processes in the group shown after its name, i.e. ' (37)'
+    pub fn group_count_as_string(&self) -> String {
+        self.group
+            .map(|group| format!(" ({})", group.count))
+            .unwrap_or_default()
+    }
+
+    /// Computed from start time, so it is current even between refreshes
+    pub fn run_duration(&self, now: SystemTime) -> Duration {
+        process_run_duration(self.start_timestamp, now)
+    }
+
+    pub fn run_time_as_string(&self) -> String {
+        format_run_time(self.run_duration(SystemTime::now()))
+    }
+
+    pub fn cpu_usage_as_string(&self) -> String {
+        format!("{:.1}", self.cpu_usage)
+    }
+
+    /// Formatted like in ps, i.e. '1:02:03', dash is shown when it is unknown
+    pub fn cpu_time_as_string(&self) -> String {
+        self.cpu_time
+            .map(format_cpu_time)
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    /// Dash is shown for processes without terminal, like in ps
+    pub fn tty_as_string(&self) -> &str {
+        self.tty.as_deref().unwrap_or("-")
+    }
+
+    /// Dash is shown when working directory cannot be read, i.e. without permissions
+    pub fn cwd_as_string(&self) -> &str {
+        self.cwd.as_deref().unwrap_or("-")
+    }
+
+    /// Dash is shown when open descriptors were not counted or cannot be read
+    pub fn open_fds_as_string(&self) -> String {
+        self.open_fds
+            .map(|fds| fds.to_string())
+            .unwrap_or("-".to_string())
+    }
+
+    /// Dash is shown when thread count is unknown
+    pub fn threads_as_string(&self) -> String {
+        self.threads
+            .map(|threads| threads.to_string())
+            .unwrap_or("-".to_string())
+    }
+
+    /// Dash is shown when io counters cannot be read, so it is not mistaken for idle process
+    pub fn io_read_as_string(&self) -> String {
+        self.io_rate
+            .map(|rate| format_bytes(rate.read))
+            .unwrap_or("-".to_string())
+    }
+
+    pub fn io_write_as_string(&self) -> String {
+        self.io_rate
+            .map(|rate| format_bytes(rate.write))
+            .unwrap_or("-".to_string())
+    }
+
+    pub fn is_zombie(&self) -> bool {
+        self.state == 'Z'
+    }
+
+    /// Stopped by signal (i.e. paused with SIGSTOP) or by debugger
+    pub fn is_stopped(&self) -> bool {
+        self.state == 'T'
+    }
+
+    /// Real user when it differs from effective one, i.e. 'nobody' for 'USER: root (real: nobody)'
+    pub fn other_real_user(&self) -> Option<&str> {
+        (self.real_user_id != self.user_id).then_some(self.real_user_name.as_str())
+    }
+
     pub fn parent_as_string(&self) -> String {
         self.parent_pid
             .map(|pid| pid.to_string())
             .unwrap_or_default()
     }
+
+    /// Short container id followed by its name when it is known, i.e. '4f3c2a1b0e9d web'
+    pub fn container_as_string(&self) -> String {
+        let Some(id) = self.container_id.as_deref() else {
+            return String::new();
+        };
+        let short_id = &id[..id.len().min(SHORT_ID_LENGTH)];
+        match self.container_name.as_deref() {
+            Some(name) => format!("{} {}", short_id, name),
+            None => short_id.to_string(),
+        }
+    }
 }
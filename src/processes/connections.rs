@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Network socket opened by a process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Connection {
+    pub protocol: Protocol,
+    /// Tcp state, i.e. 'LISTEN' or 'ESTABLISHED', udp sockets are 'UNCONN' unless connected
+    pub state: &'static str,
+    pub local: SocketAddr,
+    /// Peer address, None for listening and unconnected sockets
+    pub remote: Option<SocketAddr>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Protocol::Tcp => write!(f, "TCP"),
+            Protocol::Udp => write!(f, "UDP"),
+        }
+    }
+}
+
+impl Display for Connection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:<11} {}", self.protocol, self.state, self.local)?;
+        match self.remote {
+            Some(remote) => write!(f, " -> {}", remote),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Sockets of the process, on linux they are read from /proc, elsewhere only listening ports are known.
+/// Processes that cannot be read (i.e. of other users) have no connections
+pub fn process_connections(pid: u32) -> Vec<Connection> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::process_connections(pid)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        listening_connections().remove(&pid).unwrap_or_default()
+    }
+}
+
+/// Listening tcp sockets of all processes by pid, they are known on every platform
+pub(super) fn listening_connections() -> HashMap<u32, Vec<Connection>> {
+    listeners::get_all()
+        //NOTE: we ignore errors comming from listeners
+        .unwrap_or_default()
+        .into_iter()
+        .fold(HashMap::new(), |mut acc, listener| {
+            acc.entry(listener.process.pid)
+                .or_insert_with(Vec::new)
+                .push(Connection {
+                    protocol: Protocol::Tcp,
+                    state: "LISTEN",
+                    local: listener.socket,
+                    remote: None,
+                });
+            acc
+        })
+}
+
+/// Compact list of local ports shown in the table, i.e. '80, 443'
+pub(super) fn ports_summary(connections: &[Connection]) -> String {
+    let ports: Vec<String> = connections
+        .iter()
+        .map(|connection| connection.local.port().to_string())
+        .collect();
+    ports.join(", ")
+}
+
+#[cfg(target_os = "linux")]
+pub(super) use linux::socket_connections;
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::collections::{HashMap, HashSet};
+    use std::fs;
+
+    use super::{parse_proc_net_line, Connection, Protocol};
+
+    pub(super) fn process_connections(pid: u32) -> Vec<Connection> {
+        let inodes = socket_inodes(pid);
+        if inodes.is_empty() {
+            return vec![];
+        }
+        network_sockets(pid)
+            .into_iter()
+            .filter(|(inode, _)| inodes.contains(inode))
+            .map(|(_, connection)| connection)
+            .collect()
+    }
+
+    /// Network sockets of the process by their inode, used to describe its socket file descriptors
+    pub(in crate::processes) fn socket_connections(pid: u32) -> HashMap<u64, Connection> {
+        network_sockets(pid).into_iter().collect()
+    }
+
+    /// All sockets from net files of the process namespace, i.e. also sockets of other processes
+    fn network_sockets(pid: u32) -> Vec<(u64, Connection)> {
+        //NOTE: net files of the process are used, it may live in other network namespace
+        [
+            ("tcp", Protocol::Tcp),
+            ("tcp6", Protocol::Tcp),
+            ("udp", Protocol::Udp),
+            ("udp6", Protocol::Udp),
+        ]
+        .into_iter()
+        .flat_map(|(file, protocol)| {
+            let content = fs::read_to_string(format!("/proc/{}/net/{}", pid, file));
+            let sockets: Vec<(u64, Connection)> = content
+                .unwrap_or_default()
+                .lines()
+                .skip(1)
+                .filter_map(|line| parse_proc_net_line(line, protocol))
+                .collect();
+            sockets
+        })
+        .collect()
+    }
+
+    /// Inodes of sockets from file descriptors of the process, i.e. 'socket:[12345]'
+    fn socket_inodes(pid: u32) -> HashSet<u64> {
+        let Ok(fds) = fs::read_dir(format!("/proc/{}/fd", pid)) else {
+            return HashSet::new();
+        };
+        fds.filter_map(|fd| fs::read_link(fd.ok()?.path()).ok())
+            .filter_map(|link| {
+                link.to_str()?
+                    .strip_prefix("socket:[")?
+                    .strip_suffix(']')?
+                    .parse()
+                    .ok()
+            })
+            .collect()
+    }
+}
+
+/// Parses socket line of /proc/net/{tcp,udp}[6], returns socket inode with connection
+fn parse_proc_net_line(line: &str, protocol: Protocol) -> Option<(u64, Connection)> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let local = parse_socket_addr(fields.get(1)?)?;
+    let remote = parse_socket_addr(fields.get(2)?)?;
+    let state = u8::from_str_radix(fields.get(3)?, 16).ok()?;
+    let inode = fields.get(9)?.parse().ok()?;
+    let connection = Connection {
+        protocol,
+        state: socket_state(protocol, state),
+        local,
+        remote: (!remote.ip().is_unspecified() || remote.port() != 0).then_some(remote),
+    };
+    Some((inode, connection))
+}
+
+/// Address is hex encoded in host byte order, i.e. '0100007F:1F90' is 127.0.0.1:8080
+fn parse_socket_addr(addr: &str) -> Option<SocketAddr> {
+    let (ip, port) = addr.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let ip = match ip.len() {
+        8 => IpAddr::V4(Ipv4Addr::from(
+            u32::from_str_radix(ip, 16).ok()?.to_le_bytes(),
+        )),
+        //NOTE: ipv6 is written as four 32 bit words, each of them in host byte order
+        32 => {
+            let mut octets = [0u8; 16];
+            for (i, word) in octets.chunks_mut(4).enumerate() {
+                let value = u32::from_str_radix(&ip[i * 8..i * 8 + 8], 16).ok()?;
+                word.copy_from_slice(&value.to_le_bytes());
+            }
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+fn socket_state(protocol: Protocol, state: u8) -> &'static str {
+    match (protocol, state) {
+        (Protocol::Udp, 0x01) => "ESTABLISHED",
+        (Protocol::Udp, _) => "UNCONN",
+        (Protocol::Tcp, 0x01) => "ESTABLISHED",
+        (Protocol::Tcp, 0x02) => "SYN_SENT",
+        (Protocol::Tcp, 0x03) => "SYN_RECV",
+        (Protocol::Tcp, 0x04) => "FIN_WAIT1",
+        (Protocol::Tcp, 0x05) => "FIN_WAIT2",
+        (Protocol::Tcp, 0x06) => "TIME_WAIT",
+        (Protocol::Tcp, 0x07) => "CLOSE",
+        (Protocol::Tcp, 0x08) => "CLOSE_WAIT",
+        (Protocol::Tcp, 0x09) => "LAST_ACK",
+        (Protocol::Tcp, 0x0A) => "LISTEN",
+        (Protocol::Tcp, 0x0B) => "CLOSING",
+        (Protocol::Tcp, _) => "UNKNOWN",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_listening_tcp_socket() {
+        let line = "   0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41234 1 0000000000000000 100 0 0 10 0";
+        let (inode, connection) = parse_proc_net_line(line, Protocol::Tcp).unwrap();
+        assert_eq!(inode, 41234);
+        assert_eq!(
+            connection,
+            Connection {
+                protocol: Protocol::Tcp,
+                state: "LISTEN",
+                local: "127.0.0.1:8080".parse().unwrap(),
+                remote: None,
+            }
+        );
+        assert_eq!(connection.to_string(), "TCP LISTEN      127.0.0.1:8080");
+    }
+
+    #[test]
+    fn should_parse_established_tcp6_socket() {
+        let line = "   1: 00000000000000000000000001000000:A1B2 0000000000000000FFFF00000100007F:01BB 01 00000000:00000000 00:00000000 00000000  1000        0 555 1 0000000000000000 20 4 30 10 -1";
+        let (inode, connection) = parse_proc_net_line(line, Protocol::Tcp).unwrap();
+        assert_eq!(inode, 555);
+        assert_eq!(connection.state, "ESTABLISHED");
+        assert_eq!(connection.local, "[::1]:41394".parse().unwrap());
+        assert_eq!(
+            connection.remote,
+            Some("[::ffff:127.0.0.1]:443".parse().unwrap())
+        );
+        assert_eq!(
+            connection.to_string(),
+            "TCP ESTABLISHED [::1]:41394 -> [::ffff:127.0.0.1]:443"
+        );
+    }
+
+    #[test]
+    fn should_parse_unconnected_udp_socket() {
+        let line = "  10: 00000000:14E9 00000000:0000 07 00000000:00000000 00:00000000 00000000   104        0 777 2 0000000000000000 0";
+        let (_, connection) = parse_proc_net_line(line, Protocol::Udp).unwrap();
+        assert_eq!(connection.state, "UNCONN");
+        assert_eq!(connection.local, "0.0.0.0:5353".parse().unwrap());
+        assert_eq!(connection.remote, None);
+    }
+
+    #[test]
+    fn should_summarize_ports() {
+        let connection = |local: &str| Connection {
+            protocol: Protocol::Tcp,
+            state: "LISTEN",
+            local: local.parse().unwrap(),
+            remote: None,
+        };
+        let connections = [connection("0.0.0.0:80"), connection("[::]:443")];
+        assert_eq!(ports_summary(&connections), "80, 443");
+        assert_eq!(connections[1].to_string(), "TCP LISTEN      [::]:443");
+        assert_eq!(ports_summary(&[]), "");
+    }
+
+    #[test]
+    fn should_skip_invalid_lines() {
+        let header = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode";
+        assert_eq!(parse_proc_net_line(header, Protocol::Tcp), None);
+        assert_eq!(parse_proc_net_line("", Protocol::Tcp), None);
+    }
+}
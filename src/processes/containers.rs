@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+/// Length of container id shown to the user, same as in 'docker ps'
+pub const SHORT_ID_LENGTH: usize = 12;
+
+/// Full id of Docker, Podman or Kubernetes container the process runs in, read from /proc/<pid>/cgroup.
+/// None for processes outside containers and on platforms without cgroups
+#[cfg(target_os = "linux")]
+pub(super) fn process_container_id(pid: u32) -> Option<String> {
+    let cgroup = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    parse_container_id(&cgroup)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(super) fn process_container_id(_pid: u32) -> Option<String> {
+    None
+}
+
+/// Runtime prefixes of cgroup directories, i.e. 'docker-<id>.scope' with systemd cgroup driver
+const RUNTIME_PREFIXES: [&str; 4] = ["docker-", "libpod-", "cri-containerd-", "crio-"];
+const CONTAINER_ID_LENGTH: usize = 64;
+
+/// Finds container id in cgroup paths, lines look like '0::/system.slice/docker-<id>.scope'
+/// with cgroup v2 or '12:cpuset:/docker/<id>' with cgroup v1
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_container_id(cgroup: &str) -> Option<String> {
+    cgroup
+        .lines()
+        .filter_map(|line| line.splitn(3, ':').nth(2))
+        .flat_map(|path| path.split('/'))
+        .filter_map(|dir| {
+            let dir = dir.strip_suffix(".scope").unwrap_or(dir);
+            let id = RUNTIME_PREFIXES
+                .iter()
+                .find_map(|prefix| dir.strip_prefix(prefix))
+                .unwrap_or(dir);
+            //NOTE: monitor of the container (i.e. 'libpod-conmon-<id>.scope') runs outside of it
+            let is_id =
+                id.len() == CONTAINER_ID_LENGTH && id.chars().all(|c| c.is_ascii_hexdigit());
+            is_id.then(|| id.to_string())
+        })
+        .next_back()
+}
+
+/// Names of running docker containers by their full id, empty when docker is not available
+#[cfg(feature = "docker")]
+pub(super) fn container_names() -> HashMap<String, String> {
+    let output = std::process::Command::new("docker")
+        .args(["ps", "--no-trunc", "--format", "{{.ID}} {{.Names}}"])
+        .stderr(std::process::Stdio::null())
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_container_names(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => HashMap::new(),
+    }
+}
+
+/// Container names are resolved only when built with 'docker' feature
+#[cfg(not(feature = "docker"))]
+pub(super) fn container_names() -> HashMap<String, String> {
+    HashMap::new()
+}
+
+#[cfg_attr(not(feature = "docker"), allow(dead_code))]
+fn parse_container_names(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(id, name)| (id.to_string(), name.trim().to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "4f3c2a1b0e9d8c7b6a5f4e3d2c1b0a9f8e7d6c5b4a3f2e1d0c9b8a7f6e5d4c3b";
+
+    #[test]
+    fn should_find_docker_container_id() {
+        let cgroup_v1 = format!("12:cpuset:/docker/{}\n11:memory:/docker/{}\n", ID, ID);
+        assert_eq!(parse_container_id(&cgroup_v1), Some(ID.to_string()));
+        let cgroup_v2 = format!("0::/system.slice/docker-{}.scope\n", ID);
+        assert_eq!(parse_container_id(&cgroup_v2), Some(ID.to_string()));
+    }
+
+    #[test]
+    fn should_find_podman_and_kubernetes_container_id() {
+        let podman = format!("0::/machine.slice/libpod-{}.scope/container\n", ID);
+        assert_eq!(parse_container_id(&podman), Some(ID.to_string()));
+        let kubernetes = format!(
+            "0::/kubepods.slice/kubepods-besteffort.slice/kubepods-besteffort-pod1234.slice/cri-containerd-{}.scope\n",
+            ID
+        );
+        assert_eq!(parse_container_id(&kubernetes), Some(ID.to_string()));
+    }
+
+    #[test]
+    fn should_not_find_container_id_outside_container() {
+        assert_eq!(
+            parse_container_id("0::/user.slice/user-1000.slice/session-2.scope\n"),
+            None
+        );
+        assert_eq!(parse_container_id("0::/\n"), None);
+        assert_eq!(parse_container_id(""), None);
+        let conmon = format!("0::/machine.slice/libpod-conmon-{}.scope\n", ID);
+        assert_eq!(parse_container_id(&conmon), None);
+    }
+
+    #[test]
+    fn should_parse_container_names() {
+        let names = parse_container_names(&format!("{} web\n{} db \n", ID, "a".repeat(64)));
+        assert_eq!(names.get(ID), Some(&"web".to_string()));
+        assert_eq!(names.get(&"a".repeat(64)), Some(&"db".to_string()));
+    }
+}
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+/// Time the process spent on cpu in user and kernel mode, read from /proc/<pid>/stat.
+/// None on platforms other than linux
+#[cfg(target_os = "linux")]
+pub(super) fn process_cpu_time(pid: u32) -> Option<Duration> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let ticks = parse_cpu_ticks(&stat)?;
+    let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    let ticks_per_second = u64::try_from(ticks_per_second).ok().filter(|t| *t > 0)?;
+    Some(ticks_to_duration(ticks, ticks_per_second))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(super) fn process_cpu_time(_pid: u32) -> Option<Duration> {
+    None
+}
+
+/// Sum of utime (field 14) and stime (field 15) in clock ticks.
+/// Process name (field 2) is in parentheses and may contain spaces, so fields are counted after it
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cpu_ticks(stat: &str) -> Option<u64> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    let mut fields = fields.split_whitespace().skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+    Some(utime + stime)
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn ticks_to_duration(ticks: u64, ticks_per_second: u64) -> Duration {
+    Duration::from_millis(ticks * 1000 / ticks_per_second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_cpu_ticks_of_process() {
+        let stat = "4321 (bash) S 4320 4321 4321 34819 4400 4194560 1234 0 0 0 150 25 0 0 20 0 1 0";
+        assert_eq!(parse_cpu_ticks(stat), Some(175));
+        let stat = "77 (tmux: server (1)) S 1 77 77 0 -1 4194368 300 0 0 0 2 1 0 0 20 0 1 0";
+        assert_eq!(parse_cpu_ticks(stat), Some(3));
+        assert_eq!(parse_cpu_ticks("77 (sh) S 1 77"), None);
+        assert_eq!(parse_cpu_ticks(""), None);
+    }
+
+    #[test]
+    fn should_convert_clock_ticks_to_duration() {
+        assert_eq!(ticks_to_duration(175, 100), Duration::from_millis(1750));
+        assert_eq!(ticks_to_duration(0, 100), Duration::ZERO);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn should_read_cpu_time_of_own_process() {
+        assert!(process_cpu_time(std::process::id()).is_some());
+        assert_eq!(process_cpu_time(u32::MAX), None);
+    }
+}
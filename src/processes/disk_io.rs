@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Disk throughput of a process in bytes per second, averaged since previous refresh
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IoRate {
+    pub read: u64,
+    pub write: u64,
+}
+
+/// Bytes process has read from and written to storage since it started
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct IoCounters {
+    read_bytes: u64,
+    write_bytes: u64,
+}
+
+struct IoSample {
+    /// Start time of the process, sample of reused pid is not compared with new process
+    start_time: u64,
+    counters: IoCounters,
+    time: Instant,
+}
+
+/// Keeps last sample of every process, so rates can be computed on next refresh
+#[derive(Default)]
+pub(super) struct IoMonitor {
+    samples: HashMap<u32, IoSample>,
+    rates: HashMap<u32, IoRate>,
+}
+
+impl IoMonitor {
+    /// Samples given processes (pid, start time), samples of processes that are gone are dropped
+    pub(super) fn refresh(&mut self, processes: impl Iterator<Item = (u32, u64)>, now: Instant) {
+        self.refresh_with(processes, now, process_io_counters)
+    }
+
+    fn refresh_with(
+        &mut self,
+        processes: impl Iterator<Item = (u32, u64)>,
+        now: Instant,
+        read_counters: impl Fn(u32) -> Option<IoCounters>,
+    ) {
+        let mut samples = HashMap::new();
+        let mut rates = HashMap::new();
+        for (pid, start_time) in processes {
+            let Some(counters) = read_counters(pid) else {
+                continue;
+            };
+            let previous = self
+                .samples
+                .get(&pid)
+                .filter(|previous| previous.start_time == start_time);
+            if let Some(rate) = previous.and_then(|previous| previous.rate(counters, now)) {
+                rates.insert(pid, rate);
+            }
+            let sample = IoSample {
+                start_time,
+                counters,
+                time: now,
+            };
+            samples.insert(pid, sample);
+        }
+        self.samples = samples;
+        self.rates = rates;
+    }
+
+    /// None when counters are not readable (i.e. process of other user) or process was sampled only once
+    pub(super) fn rate(&self, pid: u32) -> Option<IoRate> {
+        self.rates.get(&pid).copied()
+    }
+}
+
+impl IoSample {
+    fn rate(&self, counters: IoCounters, now: Instant) -> Option<IoRate> {
+        let elapsed = now.checked_duration_since(self.time)?.as_secs_f64();
+        if elapsed <= 0.0 {
+            return None;
+        }
+        //NOTE: counters never decrease for the same process, saturating just in case
+        let per_second = |current: u64, previous: u64| {
+            (current.saturating_sub(previous) as f64 / elapsed).round() as u64
+        };
+        Some(IoRate {
+            read: per_second(counters.read_bytes, self.counters.read_bytes),
+            write: per_second(counters.write_bytes, self.counters.write_bytes),
+        })
+    }
+}
+
+/// Storage io counters from /proc/<pid>/io, it is readable only by the owner of the process or root
+#[cfg(target_os = "linux")]
+fn process_io_counters(pid: u32) -> Option<IoCounters> {
+    let io = std::fs::read_to_string(format!("/proc/{}/io", pid)).ok()?;
+    parse_proc_io(&io)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_io_counters(_pid: u32) -> Option<IoCounters> {
+    None
+}
+
+/// Lines look like 'read_bytes: 4096', rchar and wchar include cached reads so they are not used
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_io(io: &str) -> Option<IoCounters> {
+    let field = |name: &str| {
+        io.lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(key, _)| *key == name)
+            .and_then(|(_, value)| value.trim().parse().ok())
+    };
+    Some(IoCounters {
+        read_bytes: field("read_bytes")?,
+        write_bytes: field("write_bytes")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn counters(read_bytes: u64, write_bytes: u64) -> IoCounters {
+        IoCounters {
+            read_bytes,
+            write_bytes,
+        }
+    }
+
+    #[test]
+    fn should_parse_proc_io() {
+        let io = "rchar: 323934931\nwchar: 323929600\nsyscr: 632687\nsyscw: 632675\nread_bytes: 4096\nwrite_bytes: 323932160\ncancelled_write_bytes: 0\n";
+        assert_eq!(parse_proc_io(io), Some(counters(4096, 323932160)));
+        assert_eq!(parse_proc_io("rchar: 1\nwchar: 2\n"), None);
+        assert_eq!(parse_proc_io(""), None);
+    }
+
+    #[test]
+    fn should_compute_rates_between_refreshes() {
+        let start = Instant::now();
+        let mut monitor = IoMonitor::default();
+        monitor.refresh_with([(1, 100)].into_iter(), start, |_| Some(counters(0, 1000)));
+        assert_eq!(monitor.rate(1), None);
+
+        let now = start + Duration::from_secs(2);
+        monitor.refresh_with([(1, 100)].into_iter(), now, |_| Some(counters(4096, 3000)));
+        assert_eq!(
+            monitor.rate(1),
+            Some(IoRate {
+                read: 2048,
+                write: 1000
+            })
+        );
+    }
+
+    #[test]
+    fn should_not_compare_samples_of_reused_pid() {
+        let start = Instant::now();
+        let mut monitor = IoMonitor::default();
+        monitor.refresh_with([(1, 100)].into_iter(), start, |_| {
+            Some(counters(5000, 5000))
+        });
+        let now = start + Duration::from_secs(1);
+        monitor.refresh_with([(1, 200)].into_iter(), now, |_| Some(counters(10, 10)));
+        assert_eq!(monitor.rate(1), None);
+
+        monitor.refresh_with([(1, 200)].into_iter(), now + Duration::from_secs(1), |_| {
+            Some(counters(20, 10))
+        });
+        assert_eq!(monitor.rate(1), Some(IoRate { read: 10, write: 0 }));
+    }
+
+    #[test]
+    fn should_have_no_rate_for_unreadable_counters() {
+        let start = Instant::now();
+        let mut monitor = IoMonitor::default();
+        monitor.refresh_with([(1, 100)].into_iter(), start, |_| Some(counters(0, 0)));
+        monitor.refresh_with(
+            [(1, 100)].into_iter(),
+            start + Duration::from_secs(1),
+            |_| None,
+        );
+        assert_eq!(monitor.rate(1), None);
+    }
+}
@@ -0,0 +1,91 @@
+use anyhow::{anyhow, Result};
+
+/// Environment variables of the process as 'NAME=value', sorted by name.
+/// Reading environment of other users' processes usually requires elevated privileges
+pub fn process_environment(pid: u32) -> Result<Vec<String>> {
+    let mut variables = read_environment(pid)?;
+    variables.sort();
+    Ok(variables)
+}
+
+#[cfg(target_os = "linux")]
+fn read_environment(pid: u32) -> Result<Vec<String>> {
+    use std::io::ErrorKind;
+
+    match std::fs::read(format!("/proc/{}/environ", pid)) {
+        Ok(environ) => Ok(parse_environ(&environ)),
+        Err(err) if err.kind() == ErrorKind::PermissionDenied => Err(permission_denied(pid)),
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            Err(anyhow!("Process {} no longer exists", pid))
+        }
+        Err(err) => Err(anyhow!(
+            "Failed to read environment of process {}: {}",
+            pid,
+            err
+        )),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_environment(pid: u32) -> Result<Vec<String>> {
+    use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+    let pid = Pid::from_u32(pid);
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        ProcessRefreshKind::new().with_environ(UpdateKind::Always),
+    );
+    let prc = sys
+        .process(pid)
+        .ok_or_else(|| anyhow!("Process {} no longer exists", pid))?;
+    //NOTE: sysinfo does not report errors, empty environment is most likely not permitted
+    match prc.environ() {
+        [] => Err(permission_denied(pid.as_u32())),
+        environ => Ok(environ
+            .iter()
+            .map(|variable| variable.to_string_lossy().to_string())
+            .collect()),
+    }
+}
+
+fn permission_denied(pid: u32) -> anyhow::Error {
+    anyhow!(
+        "Permission denied, environment of process {} can be read only by its owner or root. Try running pik with sudo.",
+        pid
+    )
+}
+
+/// Variables in /proc/<pid>/environ are separated with null byte
+#[cfg(target_os = "linux")]
+fn parse_environ(environ: &[u8]) -> Vec<String> {
+    environ
+        .split(|byte| *byte == 0)
+        .filter(|variable| !variable.is_empty())
+        .map(|variable| String::from_utf8_lossy(variable).to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn should_parse_environ() {
+        assert_eq!(
+            parse_environ(b"PATH=/usr/bin\0HOME=/root\0EMPTY=\0"),
+            vec!["PATH=/usr/bin", "HOME=/root", "EMPTY="]
+        );
+        assert!(parse_environ(b"").is_empty());
+    }
+
+    #[test]
+    fn should_read_sorted_environment_of_own_process() {
+        let variables = process_environment(std::process::id()).unwrap();
+        assert!(variables
+            .iter()
+            .any(|v| v.starts_with("CARGO_PKG_NAME=pik")));
+        assert!(variables.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+}
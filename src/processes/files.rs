@@ -0,0 +1,221 @@
+use std::fmt::{self, Display};
+
+use anyhow::Result;
+
+/// File descriptor opened by a process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenFile {
+    pub fd: u32,
+    pub kind: FileKind,
+    /// Resolved target, sockets also show their connection when it is known,
+    /// i.e. 'socket:[41234] TCP LISTEN 127.0.0.1:8080'
+    pub target: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    File,
+    Dir,
+    Device,
+    Socket,
+    Pipe,
+    /// Kernel object without file, i.e. eventfd or epoll
+    Anon,
+    Unknown,
+}
+
+impl Display for FileKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            FileKind::File => "FILE",
+            FileKind::Dir => "DIR",
+            FileKind::Device => "DEV",
+            FileKind::Socket => "SOCK",
+            FileKind::Pipe => "PIPE",
+            FileKind::Anon => "ANON",
+            FileKind::Unknown => "?",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+impl Display for OpenFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>5} {:<4} {}", self.fd, self.kind, self.target)
+    }
+}
+
+/// Open file descriptors of the process sorted by number, they can be listed only on linux.
+/// Descriptors of other users' processes usually require elevated privileges
+pub fn process_open_files(pid: u32) -> Result<Vec<OpenFile>> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::process_open_files(pid)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err(anyhow::anyhow!(
+            "Open files of process {} can be listed only on linux",
+            pid
+        ))
+    }
+}
+
+/// Number of open file descriptors, it is much cheaper than listing them but still reads a directory,
+/// so it is counted only for processes on screen. None when it can't be read
+pub fn process_fd_count(pid: u32) -> Option<usize> {
+    #[cfg(target_os = "linux")]
+    {
+        std::fs::read_dir(format!("/proc/{}/fd", pid))
+            .ok()
+            .map(|fds| fds.count())
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs;
+    use std::io::ErrorKind;
+    use std::os::unix::fs::FileTypeExt;
+
+    use anyhow::{anyhow, Result};
+
+    use super::{classify_target, FileKind, OpenFile};
+    use crate::processes::connections::socket_connections;
+
+    pub(super) fn process_open_files(pid: u32) -> Result<Vec<OpenFile>> {
+        let fds = fs::read_dir(format!("/proc/{}/fd", pid)).map_err(|err| match err.kind() {
+            ErrorKind::PermissionDenied => anyhow!(
+                "Permission denied, open files of process {} can be listed only by its owner or root. Try running pik with sudo.",
+                pid
+            ),
+            ErrorKind::NotFound => anyhow!("Process {} no longer exists", pid),
+            _ => anyhow!("Failed to list open files of process {}: {}", pid, err),
+        })?;
+        //NOTE: descriptors closed while listing are skipped
+        let mut files: Vec<OpenFile> = fds
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let fd = path.file_name()?.to_str()?.parse().ok()?;
+                let target = fs::read_link(&path).ok()?;
+                let target = target.to_string_lossy().to_string();
+                let kind = match classify_target(&target) {
+                    FileKind::File => file_kind(&path),
+                    kind => kind,
+                };
+                Some(OpenFile { fd, kind, target })
+            })
+            .collect();
+        files.sort_by_key(|file| file.fd);
+        describe_sockets(pid, &mut files);
+        Ok(files)
+    }
+
+    /// Metadata of the descriptor itself is used, path it points to may be deleted
+    fn file_kind(fd_path: &std::path::Path) -> FileKind {
+        match fs::metadata(fd_path).map(|metadata| metadata.file_type()) {
+            Ok(file_type) if file_type.is_dir() => FileKind::Dir,
+            Ok(file_type) if file_type.is_char_device() || file_type.is_block_device() => {
+                FileKind::Device
+            }
+            _ => FileKind::File,
+        }
+    }
+
+    fn describe_sockets(pid: u32, files: &mut [OpenFile]) {
+        if !files.iter().any(|file| file.kind == FileKind::Socket) {
+            return;
+        }
+        let connections = socket_connections(pid);
+        for file in files.iter_mut() {
+            let connection =
+                super::socket_inode(&file.target).and_then(|inode| connections.get(&inode));
+            if let Some(connection) = connection {
+                file.target = format!("{} {}", file.target, connection);
+            }
+        }
+    }
+}
+
+/// Kind of descriptor from its link target, i.e. 'socket:[41234]' or 'pipe:[1234]'
+#[cfg(target_os = "linux")]
+fn classify_target(target: &str) -> FileKind {
+    match target {
+        _ if socket_inode(target).is_some() => FileKind::Socket,
+        _ if target.starts_with("pipe:") => FileKind::Pipe,
+        _ if target.starts_with("anon_inode:") => FileKind::Anon,
+        _ if target.starts_with('/') => FileKind::File,
+        _ => FileKind::Unknown,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn socket_inode(target: &str) -> Option<u64> {
+    target
+        .strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn should_classify_descriptor_targets() {
+        assert_eq!(classify_target("socket:[41234]"), FileKind::Socket);
+        assert_eq!(socket_inode("socket:[41234]"), Some(41234));
+        assert_eq!(classify_target("pipe:[1234]"), FileKind::Pipe);
+        assert_eq!(classify_target("anon_inode:[eventfd]"), FileKind::Anon);
+        assert_eq!(classify_target("/var/log/syslog"), FileKind::File);
+        assert_eq!(classify_target("net:[4026531840]"), FileKind::Unknown);
+    }
+
+    #[test]
+    fn should_format_open_file() {
+        let file = OpenFile {
+            fd: 3,
+            kind: FileKind::Socket,
+            target: "socket:[41234]".to_string(),
+        };
+        assert_eq!(file.to_string(), "    3 SOCK socket:[41234]");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn should_list_open_files_of_own_process() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let files = process_open_files(std::process::id()).unwrap();
+        assert!(files.windows(2).all(|pair| pair[0].fd < pair[1].fd));
+        assert!(files.iter().any(|file| file.kind == FileKind::Socket
+            && file
+                .target
+                .ends_with(&format!("LISTEN      127.0.0.1:{}", port))));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn should_count_open_fds_of_own_process() {
+        //NOTE: stdin, stdout and stderr at least
+        assert!(process_fd_count(std::process::id()).unwrap() >= 3);
+        assert_eq!(process_fd_count(u32::MAX), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn should_fail_for_missing_process() {
+        let error = process_open_files(u32::MAX).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("Process {} no longer exists", u32::MAX)
+        );
+    }
+}
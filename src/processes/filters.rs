@@ -1,15 +1,102 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::time::SystemTime;
+
+use anyhow::{anyhow, Result};
 use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use regex::{Regex, RegexBuilder};
 use sysinfo::Uid;
 
-use super::{utils::get_process_args, ProcessInfo};
+use super::{
+    utils::{get_process_args, process_run_duration, PROCESS_STATES},
+    ProcessInfo,
+};
 
 pub(super) struct QueryFilter {
+    pub(super) criteria: Vec<Criterion>,
+    excluded: Vec<String>,
+}
+
+/// Single search term, i.e. ':8080' or 'firefox'
+pub(super) struct Criterion {
+    pattern: String,
     query: String,
     pub(super) search_by: SearchBy,
     matcher: SkimMatcherV2,
+    /// Compiled query of regex search or path glob, i.e. 'path:/usr/lib/*/chrome*'
+    regex: Option<Regex>,
+    /// Set when searching by port list or range, i.e. ':80,443' or ':8000-9000'
+    port_ranges: Vec<RangeInclusive<u16>>,
+    /// Set when searching by memory, run time or nice value, i.e. 'mem>500M', 'time>2h' or 'nice<0'
+    threshold: Option<Threshold>,
+    fuzzy: bool,
+    case_sensitive: bool,
+    user_ids: Vec<Uid>,
+    /// Ids of containers whose name contains the query, set when searching by container
+    container_ids: Vec<String>,
+}
+
+/// Sorted char indices of process fields matched by the query, used for highlighting.
+/// Char indices (not bytes) are used so multi-byte text can be split safely
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct QueryMatches {
+    /// Length of pid prefix matched by pid search
+    pub pid: usize,
+    pub cmd: Vec<usize>,
+    pub cmd_path: Vec<usize>,
+    /// Indices in args joined with ',', as they are shown in the table
+    pub args: Vec<usize>,
+    pub ports: Vec<usize>,
+}
+
+/// Value compared with process memory (bytes), run time (seconds) or nice value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Threshold {
+    /// How process value must compare to the threshold, i.e. Greater for '>'
+    ordering: Ordering,
+    //NOTE: signed because nice values can be negative
+    value: i64,
+    /// Size of the unit used in query, equality is checked in whole units
+    unit: i64,
+}
+
+/// Describes how well process matched the query, exact matches always rank above fuzzy ones
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) struct MatchScore {
+    exact: bool,
+    score: i64,
+}
+
+/// How letter case is treated when matching text, smart case works like in ripgrep:
+/// query with uppercase letter is case sensitive, lowercase query is not
+#[derive(PartialEq, Eq, Debug, Default, Clone, Copy)]
+pub enum CaseSensitivity {
+    Smart,
+    Sensitive,
+    #[default]
+    Insensitive,
+}
+
+impl CaseSensitivity {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Smart => Self::Sensitive,
+            Self::Sensitive => Self::Insensitive,
+            Self::Insensitive => Self::Smart,
+        }
+    }
+
+    fn is_sensitive(self, query: &str) -> bool {
+        match self {
+            Self::Smart => query.chars().any(char::is_uppercase),
+            Self::Sensitive => true,
+            Self::Insensitive => false,
+        }
+    }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum SearchBy {
     Cmd,
     Port,
@@ -17,38 +104,435 @@ pub enum SearchBy {
     Args,
     Everywhere,
     Pid,
+    ParentPid,
     ProcessFamily,
+    User,
+    Regex,
+    State,
+    Memory,
+    RunTime,
+    Nice,
+    DeletedExe,
+    Container,
+    Unit,
+    Tty,
     None,
 }
 
+/// Modes that can be cycled in the ui, terms without prefix are searched by the selected one
+const SEARCH_MODES: [SearchBy; 5] = [
+    SearchBy::Cmd,
+    SearchBy::Args,
+    SearchBy::Port,
+    SearchBy::User,
+    SearchBy::Path,
+];
+
+impl SearchBy {
+    /// Next search mode, other kinds of search start over from search by name
+    pub fn next_mode(self) -> Self {
+        let index = SEARCH_MODES.iter().position(|mode| *mode == self);
+        match index {
+            Some(index) => SEARCH_MODES[(index + 1) % SEARCH_MODES.len()],
+            None => SEARCH_MODES[0],
+        }
+    }
+}
+
+const REGEX_PREFIX: &str = "re:";
+const PATH_PREFIX: &str = "path:";
+const GLOB_WILDCARDS: [char; 2] = ['*', '?'];
+const STATE_PREFIX: &str = "state:";
+const DELETED_EXE_PREFIX: &str = "deleted:";
+const CONTAINER_PREFIX: &str = "container:";
+const UNIT_PREFIX: &str = "unit:";
+const TTY_PREFIX: &str = "tty:";
+/// Processes without controlling terminal are shown with dash, like in ps
+const NO_TTY: &str = "-";
+const MEMORY_PREFIX: &str = "mem";
+const RUN_TIME_PREFIX: &str = "time";
+const NICE_PREFIX: &str = "nice";
+const NICE_RANGE: RangeInclusive<i64> = -20..=19;
+const MEMORY_UNITS: [(char, u64); 3] = [('K', 1024), ('M', 1024 * 1024), ('G', 1024 * 1024 * 1024)];
+const DEFAULT_MEMORY_UNIT: char = 'M';
+const RUN_TIME_UNITS: [(char, u64); 4] = [('s', 1), ('m', 60), ('h', 60 * 60), ('d', 24 * 60 * 60)];
+
 impl QueryFilter {
-    pub fn new(query: &str) -> Self {
+    #[cfg(test)]
+    pub fn new(query: &str) -> Result<Self> {
+        Self::with_search_mode(query, SearchBy::Cmd)
+    }
+
+    /// Query is split by whitespace, process must match all terms.
+    /// Terms without prefix are searched by given mode, prefixed terms are not affected
+    pub fn with_search_mode(query: &str, search_mode: SearchBy) -> Result<Self> {
+        let (terms, excluded) = split_negations(query);
+        let criteria = match terms.is_empty() {
+            true => vec![Criterion::new("")?],
+            false => terms
+                .iter()
+                .map(|term| Criterion::parse(term, search_mode))
+                .collect::<Result<_>>()?,
+        };
+        Ok(Self { criteria, excluded })
+    }
+
+    /// Whole query is a single regular expression matched against process name and arguments
+    pub fn regex(query: &str) -> Result<Self> {
+        let criterion = match query.is_empty() {
+            true => Criterion::new("")?,
+            false => Criterion::regex(query)?,
+        };
+        Ok(Self {
+            criteria: vec![criterion],
+            excluded: vec![],
+        })
+    }
+
+    /// Resolves ids of users whose name starts with the query, required for search by user
+    pub fn with_users<'a>(mut self, users: impl IntoIterator<Item = (&'a Uid, &'a str)>) -> Self {
+        let users: Vec<_> = users.into_iter().collect();
+        self.criteria = self
+            .criteria
+            .into_iter()
+            .map(|c| c.with_users(&users))
+            .collect();
+        self
+    }
+
+    /// Resolves ids of containers by their names, i.e. 'container:web'
+    pub fn with_containers(mut self, names: &HashMap<String, String>) -> Self {
+        self.criteria = self
+            .criteria
+            .into_iter()
+            .map(|c| c.with_containers(names))
+            .collect();
+        self
+    }
+
+    pub fn fuzzy(mut self, fuzzy: bool) -> Self {
+        self.criteria = self.criteria.into_iter().map(|c| c.fuzzy(fuzzy)).collect();
+        self
+    }
+
+    pub fn case_sensitivity(mut self, case_sensitivity: CaseSensitivity) -> Self {
+        self.criteria = self
+            .criteria
+            .into_iter()
+            .map(|c| c.case_sensitivity(case_sensitivity))
+            .collect();
+        self
+    }
+
+    pub(super) fn search_by(&self) -> Vec<SearchBy> {
+        self.criteria.iter().map(|c| c.search_by).collect()
+    }
+
+    /// Memory thresholds as typed, i.e. '>500,<1G' for 'mem>500 mem<1G'
+    pub(super) fn memory_filter(&self) -> Option<String> {
+        let thresholds: Vec<&str> = self
+            .criteria
+            .iter()
+            .filter(|c| c.search_by == SearchBy::Memory && c.threshold.is_some())
+            .map(|c| c.pattern.as_str())
+            .collect();
+        (!thresholds.is_empty()).then(|| thresholds.join(","))
+    }
+
+    /// Port lists and ranges as typed, i.e. '8000-8100' for ':8000-8100'
+    pub(super) fn port_filter(&self) -> Option<String> {
+        let ranges: Vec<&str> = self
+            .criteria
+            .iter()
+            .filter(|c| c.search_by == SearchBy::Port && !c.port_ranges.is_empty())
+            .map(|c| c.pattern.as_str())
+            .collect();
+        (!ranges.is_empty()).then(|| ranges.join(" "))
+    }
+
+    pub(super) fn active_filters(&self) -> usize {
+        let criteria = self
+            .criteria
+            .iter()
+            .filter(|c| c.search_by != SearchBy::None)
+            .count();
+        criteria + self.excluded.len()
+    }
+
+    pub(super) fn accept(&self, prc: &impl ProcessInfo, ports: Option<&str>) -> bool {
+        self.criteria.iter().all(|c| c.query_matches(prc, ports)) && !self.is_excluded(prc)
+    }
+
+    fn is_excluded(&self, prc: &impl ProcessInfo) -> bool {
+        if self.excluded.is_empty() {
+            return false;
+        }
+        let cmd = prc.cmd().to_lowercase();
+        let args: Vec<String> = get_process_args(prc)
+            .iter()
+            .map(|a| a.to_lowercase())
+            .collect();
+        self.excluded
+            .iter()
+            .any(|term| cmd.contains(term) || args.iter().any(|a| a.contains(term)))
+    }
+
+    /// Ranks process name against all name terms, other terms do not affect ranking
+    pub(super) fn rank(&self, prc: &impl ProcessInfo) -> (MatchScore, QueryMatches) {
+        let ranks: Vec<_> = self.criteria.iter().filter_map(|c| c.rank(prc)).collect();
+        let score = match ranks.is_empty() {
+            true => MatchScore::default(),
+            false => MatchScore {
+                exact: ranks.iter().all(|(score, _)| score.exact),
+                score: ranks.iter().map(|(score, _)| score.score).sum(),
+            },
+        };
+        let cmd = ranks
+            .into_iter()
+            .flat_map(|(_, indices)| indices)
+            .chain(self.text_matches(prc.cmd(), &[SearchBy::Everywhere]));
+        let cmd_path = prc.cmd_path().unwrap_or_default();
+        let args = get_process_args(prc).join(",");
+        let matches = QueryMatches {
+            cmd: merge_indices(cmd),
+            cmd_path: merge_indices(
+                self.text_matches(cmd_path, &[SearchBy::Path, SearchBy::Everywhere]),
+            ),
+            args: merge_indices(self.text_matches(&args, &[SearchBy::Args, SearchBy::Everywhere])),
+            pid: self
+                .criteria
+                .iter()
+                .filter(|c| c.search_by == SearchBy::Pid)
+                .map(|c| c.query.len())
+                .max()
+                .unwrap_or(0),
+            ..Default::default()
+        };
+        (score, matches)
+    }
+
+    /// Indices of text matched by criteria of given kinds
+    fn text_matches<'a>(
+        &'a self,
+        text: &'a str,
+        search_by: &'a [SearchBy],
+    ) -> impl Iterator<Item = usize> + 'a {
+        self.criteria
+            .iter()
+            .filter(|c| search_by.contains(&c.search_by))
+            .flat_map(|c| c.text_matches(text))
+    }
+
+    /// Char indices of ports matched by port criteria
+    pub(super) fn matched_ports(&self, ports: Option<&str>) -> Vec<usize> {
+        let Some(ports) = ports else {
+            return vec![];
+        };
+        split_ports(ports)
+            .filter(|(_, port)| self.criteria.iter().any(|c| c.port_matches(port)))
+            .flat_map(|(start, port)| start..start + port.len())
+            .collect()
+    }
+}
+
+impl Criterion {
+    pub fn new(query: &str) -> Result<Self> {
+        Self::parse(query, SearchBy::Cmd)
+    }
+
+    fn parse(query: &str, search_mode: SearchBy) -> Result<Self> {
         let (search_by, query) = match query.chars().next() {
             Some(':') => (SearchBy::Port, &query[1..]),
             Some('/') => (SearchBy::Path, &query[1..]),
             Some('-') => (SearchBy::Args, &query[1..]),
             Some('~') => (SearchBy::Everywhere, &query[1..]),
-            Some('!') => (SearchBy::Pid, &query[1..]),
-            Some('@') => (SearchBy::ProcessFamily, &query[1..]),
-            Some(_) => (SearchBy::Cmd, query),
+            Some('#') => (SearchBy::Pid, &query[1..]),
+            Some('^') => (SearchBy::ParentPid, &query[1..]),
+            Some('@') if is_number(&query[1..]) => (SearchBy::ProcessFamily, &query[1..]),
+            Some('@') => (SearchBy::User, query[1..].trim()),
+            Some(_) if query.starts_with(REGEX_PREFIX) => {
+                (SearchBy::Regex, &query[REGEX_PREFIX.len()..])
+            }
+            Some(_) if query.starts_with(PATH_PREFIX) => {
+                (SearchBy::Path, &query[PATH_PREFIX.len()..])
+            }
+            Some(_) if query.starts_with(STATE_PREFIX) => {
+                (SearchBy::State, &query[STATE_PREFIX.len()..])
+            }
+            Some(_) if query.starts_with(DELETED_EXE_PREFIX) => {
+                (SearchBy::DeletedExe, &query[DELETED_EXE_PREFIX.len()..])
+            }
+            Some(_) if query.starts_with(CONTAINER_PREFIX) => {
+                (SearchBy::Container, &query[CONTAINER_PREFIX.len()..])
+            }
+            Some(_) if query.starts_with(TTY_PREFIX) => (
+                SearchBy::Tty,
+                query[TTY_PREFIX.len()..].trim_start_matches("/dev/"),
+            ),
+            Some(_) if query.starts_with(UNIT_PREFIX) => {
+                (SearchBy::Unit, &query[UNIT_PREFIX.len()..])
+            }
+            Some(_) if is_threshold(query, MEMORY_PREFIX) => {
+                (SearchBy::Memory, &query[MEMORY_PREFIX.len()..])
+            }
+            Some(_) if is_threshold(query, RUN_TIME_PREFIX) => {
+                (SearchBy::RunTime, &query[RUN_TIME_PREFIX.len()..])
+            }
+            Some(_) if is_threshold(query, NICE_PREFIX) => {
+                (SearchBy::Nice, &query[NICE_PREFIX.len()..])
+            }
+            Some(_) => (search_mode, query),
             None => (SearchBy::None, query),
         };
-        let matcher = SkimMatcherV2::default();
-        Self {
+        let regex = match search_by {
+            SearchBy::Regex => Some(build_regex(query, false)?),
+            SearchBy::Path if is_glob(query) => Some(build_glob_regex(query, false)?),
+            _ => None,
+        };
+        if matches!(
+            search_by,
+            SearchBy::Pid | SearchBy::ParentPid | SearchBy::ProcessFamily
+        ) {
+            validate_pid(query)?;
+        }
+        if search_by == SearchBy::State {
+            validate_states(query)?;
+        }
+        let port_ranges = match search_by {
+            //NOTE: malformed list or range is searched as it is, user is probably still typing
+            SearchBy::Port if query.contains([',', '-']) => {
+                parse_port_ranges(query).unwrap_or_default()
+            }
+            _ => vec![],
+        };
+        let threshold = match search_by {
+            SearchBy::Memory => {
+                parse_threshold(query, "memory", &MEMORY_UNITS, Some(DEFAULT_MEMORY_UNIT))?
+            }
+            SearchBy::RunTime => parse_threshold(query, "run time", &RUN_TIME_UNITS, None)?,
+            SearchBy::Nice => parse_nice_threshold(query)?,
+            _ => None,
+        };
+        let matcher = SkimMatcherV2::default().ignore_case();
+        Ok(Self {
+            pattern: query.to_string(),
             query: query.to_lowercase(),
             search_by,
             matcher,
+            regex,
+            port_ranges,
+            threshold,
+            fuzzy: true,
+            case_sensitive: false,
+            user_ids: vec![],
+            container_ids: vec![],
+        })
+    }
+
+    fn regex(pattern: &str) -> Result<Self> {
+        Ok(Self {
+            pattern: pattern.to_string(),
+            query: pattern.to_string(),
+            search_by: SearchBy::Regex,
+            matcher: SkimMatcherV2::default().ignore_case(),
+            regex: Some(build_regex(pattern, false)?),
+            port_ranges: vec![],
+            threshold: None,
+            fuzzy: true,
+            case_sensitive: false,
+            user_ids: vec![],
+            container_ids: vec![],
+        })
+    }
+
+    fn with_users(mut self, users: &[(&Uid, &str)]) -> Self {
+        if self.search_by == SearchBy::User {
+            self.user_ids = users
+                .iter()
+                .filter(|(_, name)| name.to_lowercase().starts_with(&self.query))
+                .map(|(id, _)| (*id).clone())
+                .collect();
         }
+        self
     }
 
-    pub(super) fn accept(&self, prc: &impl ProcessInfo, ports: Option<&str>) -> bool {
+    fn with_containers(mut self, names: &HashMap<String, String>) -> Self {
+        if self.search_by == SearchBy::Container && !self.query.is_empty() {
+            self.container_ids = names
+                .iter()
+                .filter(|(_, name)| name.to_lowercase().contains(&self.query))
+                .map(|(id, _)| id.clone())
+                .collect();
+        }
+        self
+    }
+
+    fn fuzzy(mut self, fuzzy: bool) -> Self {
+        self.fuzzy = fuzzy;
+        self
+    }
+
+    /// Only text searches respect case, pids and user names are always matched case insensitive
+    fn case_sensitivity(mut self, case_sensitivity: CaseSensitivity) -> Self {
+        let text_search = matches!(
+            self.search_by,
+            SearchBy::Cmd
+                | SearchBy::Path
+                | SearchBy::Args
+                | SearchBy::Everywhere
+                | SearchBy::Regex
+        );
+        if !text_search || !case_sensitivity.is_sensitive(&self.pattern) {
+            return self;
+        }
+        self.case_sensitive = true;
+        self.query = self.pattern.clone();
+        self.matcher = SkimMatcherV2::default().respect_case();
+        //NOTE: pattern already compiled case insensitive, so it is valid
+        match self.search_by {
+            SearchBy::Regex => self.regex = build_regex(&self.pattern, true).ok(),
+            SearchBy::Path if self.regex.is_some() => {
+                self.regex = build_glob_regex(&self.pattern, true).ok()
+            }
+            _ => {}
+        }
+        self
+    }
+
+    fn query_matches(&self, prc: &impl ProcessInfo, ports: Option<&str>) -> bool {
         match self.search_by {
             SearchBy::Cmd => self.query_match_str(prc.cmd()),
-            SearchBy::Path => self.query_matches_opt(prc.cmd_path()),
+            SearchBy::Path => match self.regex.as_ref() {
+                Some(glob) => prc.cmd_path().is_some_and(|path| glob.is_match(path)),
+                None => self.query_matches_opt(prc.cmd_path()),
+            },
             SearchBy::Args => self.query_contains_vec(get_process_args(prc)),
-            SearchBy::Port => self.query_matches_opt(ports),
-            SearchBy::Pid => self.query_eq_u32(prc.pid()),
+            SearchBy::Port => self.query_matches_ports(ports),
+            SearchBy::Pid => prc.pid().to_string().starts_with(&self.query),
+            SearchBy::ParentPid => self.query_matches_parent(prc),
             SearchBy::ProcessFamily => self.query_matches_process_family(prc),
+            SearchBy::User => self.query_matches_user(prc),
+            SearchBy::Regex => self.regex_matches_cmd_line(prc),
+            SearchBy::State => self.query_matches_state(prc),
+            SearchBy::DeletedExe => {
+                prc.exe_deleted()
+                    && (self.query.is_empty() || self.query_matches_opt(prc.cmd_path()))
+            }
+            SearchBy::Container => self.query_matches_container(prc),
+            SearchBy::Tty => self.query_matches_tty(prc),
+            SearchBy::Unit => prc
+                .unit()
+                .is_some_and(|unit| unit.to_lowercase().contains(&self.query)),
+            SearchBy::Memory => self.threshold_matches(prc.memory() as i64),
+            SearchBy::RunTime => {
+                let run_duration = process_run_duration(prc.run_time(), SystemTime::now());
+                self.threshold_matches(run_duration.as_secs() as i64)
+            }
+            //NOTE: process with unknown nice value matches only while threshold is being typed
+            SearchBy::Nice => match prc.nice() {
+                Some(nice) => self.threshold_matches(nice.into()),
+                None => self.threshold.is_none(),
+            },
             SearchBy::Everywhere => {
                 self.query_match_str(prc.cmd())
                     || self.query_matches_opt(prc.cmd_path())
@@ -59,7 +543,55 @@ impl QueryFilter {
         }
     }
 
+    /// Ranks process name against the query, only search by name is ranked
+    fn rank(&self, prc: &impl ProcessInfo) -> Option<(MatchScore, Vec<usize>)> {
+        if self.search_by != SearchBy::Cmd {
+            return None;
+        }
+        let cmd = prc.cmd();
+        let rank = match find_substring(cmd, &self.query, self.case_sensitive) {
+            Some(indices) => {
+                let score = self.matcher.fuzzy_match(cmd, &self.query).unwrap_or(0);
+                (MatchScore { exact: true, score }, indices)
+            }
+            None if self.fuzzy => match self.matcher.fuzzy_indices(cmd, &self.query) {
+                Some((score, indices)) => (
+                    MatchScore {
+                        exact: false,
+                        score,
+                    },
+                    indices,
+                ),
+                None => Default::default(),
+            },
+            None => Default::default(),
+        };
+        Some(rank)
+    }
+
+    /// Indices of all occurrences of the query, fuzzy match is used only when there is none
+    fn text_matches(&self, text: &str) -> Vec<usize> {
+        if self.query.is_empty() {
+            return vec![];
+        }
+        if let (SearchBy::Path, Some(glob)) = (self.search_by, self.regex.as_ref()) {
+            return glob_matches(glob, text);
+        }
+        let found = find_all_substrings(text, &self.query, self.case_sensitive);
+        match found.is_empty() && self.fuzzy && self.search_by != SearchBy::Args {
+            true => self
+                .matcher
+                .fuzzy_indices(text, &self.query)
+                .map(|(_, indices)| indices)
+                .unwrap_or_default(),
+            false => found,
+        }
+    }
+
     fn query_match_str(&self, s: &str) -> bool {
+        if !self.fuzzy {
+            return find_substring(s, &self.query, self.case_sensitive).is_some();
+        }
         let score = self.matcher.fuzzy_match(s, self.query.as_str());
         // TODO: fine-tune the score threshold or make it configurable?
         score.map(|s| s >= 0).unwrap_or(false)
@@ -69,14 +601,43 @@ impl QueryFilter {
         s.map(|s| self.query_match_str(s)).unwrap_or(false)
     }
 
+    fn query_matches_ports(&self, ports: Option<&str>) -> bool {
+        match self.port_ranges.is_empty() {
+            true => self.query_matches_opt(ports),
+            false => {
+                ports.is_some_and(|ports| split_ports(ports).any(|(_, p)| self.port_matches(p)))
+            }
+        }
+    }
+
+    /// Checks single port, plain query matches ports containing it
+    fn port_matches(&self, port: &str) -> bool {
+        match self.search_by {
+            SearchBy::Port if self.port_ranges.is_empty() => {
+                !self.query.is_empty() && port.contains(&self.query)
+            }
+            SearchBy::Port => port
+                .parse::<u16>()
+                .is_ok_and(|port| self.port_ranges.iter().any(|r| r.contains(&port))),
+            _ => false,
+        }
+    }
+
     fn query_contains_vec(&self, s: Vec<&str>) -> bool {
-        s.iter().any(|a| a.to_lowercase().contains(&self.query))
+        match self.case_sensitive {
+            true => s.iter().any(|a| a.contains(&self.query)),
+            false => s.iter().any(|a| a.to_lowercase().contains(&self.query)),
+        }
     }
 
     fn query_eq_u32(&self, s: u32) -> bool {
         s.to_string() == self.query
     }
 
+    fn query_matches_parent(&self, prc: &impl ProcessInfo) -> bool {
+        self.query.is_empty() || prc.parent_id().is_some_and(|pid| self.query_eq_u32(pid))
+    }
+
     fn query_matches_process_family(&self, prc: &impl ProcessInfo) -> bool {
         self.query_eq_u32(prc.pid())
             || prc
@@ -84,6 +645,344 @@ impl QueryFilter {
                 .map(|pid| self.query_eq_u32(pid))
                 .unwrap_or(false)
     }
+
+    /// Query can contain several states, i.e. 'zt' shows zombie and stopped processes
+    fn query_matches_state(&self, prc: &impl ProcessInfo) -> bool {
+        self.query.is_empty() || self.query.contains(prc.state().to_ascii_lowercase())
+    }
+
+    //NOTE: threshold without value matches all, user is probably still typing i.e. 'mem>'
+    fn threshold_matches(&self, value: i64) -> bool {
+        self.threshold
+            .is_none_or(|threshold| match threshold.ordering {
+                //NOTE: exact byte count is never known by user, i.e. 'mem=500' matches 500.x MB
+                Ordering::Equal => value / threshold.unit == threshold.value / threshold.unit,
+                ordering => value.cmp(&threshold.value) == ordering,
+            })
+    }
+
+    /// Both real and effective user are matched, so setuid processes are found by either of them
+    fn query_matches_user(&self, prc: &impl ProcessInfo) -> bool {
+        //NOTE: empty query shows all processes, even those with unknown user
+        self.query.is_empty()
+            || [prc.user_id(), prc.effective_user_id()]
+                .into_iter()
+                .flatten()
+                .any(|user_id| self.user_ids.contains(user_id))
+    }
+
+    /// Empty query matches processes with terminal, dash the ones without it. Terminal is matched
+    /// by whole name, so 'pts/3' does not match 'pts/30', names without number match all of its kind
+    fn query_matches_tty(&self, prc: &impl ProcessInfo) -> bool {
+        let tty = prc.tty().map(|tty| tty.to_lowercase());
+        match (tty, self.query.as_str()) {
+            (tty, NO_TTY) => tty.is_none(),
+            (None, _) => false,
+            (Some(_), "") => true,
+            (Some(tty), query) if query.ends_with(|c: char| c.is_ascii_digit()) => tty == query,
+            (Some(tty), query) => tty.starts_with(query),
+        }
+    }
+
+    /// Container is matched by id prefix (as shown by 'docker ps') or by its name
+    fn query_matches_container(&self, prc: &impl ProcessInfo) -> bool {
+        prc.container_id().is_some_and(|id| {
+            id.starts_with(&self.query) || self.container_ids.iter().any(|c| c == id)
+        })
+    }
+
+    /// Matches process name alone or whole command line, so both '^java$' and 'java .*-jar' work
+    fn regex_matches_cmd_line(&self, prc: &impl ProcessInfo) -> bool {
+        let Some(regex) = self.regex.as_ref() else {
+            return false;
+        };
+        if regex.is_match(prc.cmd()) {
+            return true;
+        }
+        let args = get_process_args(prc);
+        !args.is_empty() && regex.is_match(&format!("{} {}", prc.cmd(), args.join(" ")))
+    }
+}
+
+const NEGATION: char = '!';
+const ESCAPED_NEGATION: &str = "\\!";
+
+/// Splits query into positive terms and lowercased terms that should be excluded (prefixed with '!')
+fn split_negations(query: &str) -> (Vec<String>, Vec<String>) {
+    let mut positive = vec![];
+    let mut excluded = vec![];
+    for token in query.split_whitespace() {
+        if let Some(literal) = token.strip_prefix(ESCAPED_NEGATION) {
+            positive.push(format!("{}{}", NEGATION, literal));
+        } else if let Some(term) = token.strip_prefix(NEGATION) {
+            //NOTE: lone '!' is ignored, user is probably still typing
+            if !term.is_empty() {
+                excluded.push(term.to_lowercase());
+            }
+        } else {
+            positive.push(token.to_string());
+        }
+    }
+    (positive, excluded)
+}
+
+/// Parses comma separated ports and ranges, reversed ranges are normalized
+fn parse_port_ranges(query: &str) -> Result<Vec<RangeInclusive<u16>>> {
+    let parse_port = |port: &str, part: &str| {
+        port.parse::<u16>()
+            .ok()
+            .filter(|_| is_number(port))
+            .ok_or_else(|| anyhow!("Invalid port range: {}", part))
+    };
+    let ranges: Vec<_> = query
+        .split(',')
+        //NOTE: empty parts are skipped, user is probably still typing i.e. ':80,'
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.split_once('-') {
+            Some((from, to)) => {
+                let (from, to) = (parse_port(from, part)?, parse_port(to, part)?);
+                Ok(from.min(to)..=from.max(to))
+            }
+            None => parse_port(part, part).map(|port| port..=port),
+        })
+        .collect::<Result<_>>()?;
+    if ranges.is_empty() {
+        return Err(anyhow!("Invalid port range: {}", query));
+    }
+    Ok(ranges)
+}
+
+/// Splits ports string (i.e. '80, 443') into ports with their char offset, ports are always ascii
+fn split_ports(ports: &str) -> impl Iterator<Item = (usize, &str)> {
+    ports.split(',').scan(0, |offset, part| {
+        let start = *offset + part.len() - part.trim_start().len();
+        *offset += part.len() + 1;
+        Some((start, part.trim()))
+    })
+}
+
+fn validate_states(query: &str) -> Result<()> {
+    let is_valid = |c: char| PROCESS_STATES.iter().any(|s| s.eq_ignore_ascii_case(&c));
+    match query.chars().find(|c| !is_valid(*c)) {
+        Some(invalid) => {
+            let valid: Vec<String> = PROCESS_STATES
+                .iter()
+                .map(|state| state.to_ascii_lowercase().to_string())
+                .collect();
+            Err(anyhow!(
+                "Unknown state '{}', valid states: {}",
+                invalid,
+                valid.join(", ")
+            ))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Threshold term is a prefix followed by comparison operator, so 'memcached' is still searched by name
+fn is_threshold(query: &str, prefix: &str) -> bool {
+    query
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.starts_with(['>', '<', '=']))
+}
+
+/// Parses operator and value with unit, i.e. '>500M', value is converted to the smallest unit.
+/// Default unit is used for values without suffix, when there is none suffix is required
+fn parse_threshold(
+    query: &str,
+    name: &str,
+    units: &[(char, u64)],
+    default_unit: Option<char>,
+) -> Result<Option<Threshold>> {
+    let (ordering, value) = parse_ordering(query, name)?;
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let invalid = || {
+        let suffixes: Vec<String> = units.iter().map(|(unit, _)| unit.to_string()).collect();
+        anyhow!(
+            "Invalid {} '{}', accepted suffixes: {}",
+            name,
+            value,
+            suffixes.join(", ")
+        )
+    };
+    let (number, unit) = match value.chars().last() {
+        Some(last) if last.is_ascii_digit() => (value, default_unit.ok_or_else(invalid)?),
+        Some(last) => (&value[..value.len() - last.len_utf8()], last),
+        None => return Err(invalid()),
+    };
+    let multiplier = units
+        .iter()
+        .find(|(u, _)| u.eq_ignore_ascii_case(&unit))
+        .map(|(_, multiplier)| *multiplier)
+        .ok_or_else(invalid)?;
+    let number = number
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite() && *n >= 0.0)
+        .ok_or_else(invalid)?;
+    Ok(Some(Threshold {
+        ordering,
+        value: (number * multiplier as f64) as i64,
+        unit: multiplier as i64,
+    }))
+}
+
+/// Parses operator and nice value without unit, i.e. '<0' or '>-5'
+fn parse_nice_threshold(query: &str) -> Result<Option<Threshold>> {
+    let (ordering, value) = parse_ordering(query, "nice")?;
+    //NOTE: lone minus sign is a negative value being typed
+    if value.is_empty() || value == "-" {
+        return Ok(None);
+    }
+    let nice = value
+        .parse::<i64>()
+        .ok()
+        .filter(|nice| NICE_RANGE.contains(nice))
+        .ok_or_else(|| {
+            anyhow!(
+                "Invalid nice '{}', accepted values: {} to {}",
+                value,
+                NICE_RANGE.start(),
+                NICE_RANGE.end()
+            )
+        })?;
+    Ok(Some(Threshold {
+        ordering,
+        value: nice,
+        unit: 1,
+    }))
+}
+
+/// Splits comparison operator from the value, i.e. '>500M' to Greater and '500M'
+fn parse_ordering<'a>(query: &'a str, name: &str) -> Result<(Ordering, &'a str)> {
+    let mut chars = query.chars();
+    let ordering = match chars.next() {
+        Some('>') => Ordering::Greater,
+        Some('<') => Ordering::Less,
+        Some('=') => Ordering::Equal,
+        _ => return Err(anyhow!("Invalid {} threshold: {}", name, query)),
+    };
+    Ok((ordering, chars.as_str()))
+}
+
+fn validate_pid(query: &str) -> Result<()> {
+    if query.is_empty() {
+        return Ok(());
+    }
+    if !is_number(query) {
+        return Err(anyhow!("Invalid PID: {}", query));
+    }
+    query
+        .parse::<u32>()
+        .map(|_| ())
+        .map_err(|_| anyhow!("PID {} is out of range", query))
+}
+
+fn is_number(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Substring search, returns char indices of the match
+fn find_substring(text: &str, query: &str, case_sensitive: bool) -> Option<Vec<usize>> {
+    let text: Vec<char> = text.chars().collect();
+    let query: Vec<char> = query.chars().collect();
+    if query.is_empty() {
+        return Some(vec![]);
+    }
+    text.windows(query.len())
+        .position(|window| {
+            window
+                .iter()
+                .zip(&query)
+                .all(|(t, q)| match case_sensitive {
+                    true => t == q,
+                    false => t.to_lowercase().eq(q.to_lowercase()),
+                })
+        })
+        .map(|start| (start..start + query.len()).collect())
+}
+
+/// All non overlapping occurrences of query, returns char indices
+fn find_all_substrings(text: &str, query: &str, case_sensitive: bool) -> Vec<usize> {
+    let byte_offsets: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+    let mut indices = vec![];
+    let mut start = 0;
+    while let Some(&byte_offset) = byte_offsets.get(start) {
+        let Some(found) = find_substring(&text[byte_offset..], query, case_sensitive) else {
+            break;
+        };
+        let Some(&last) = found.last() else {
+            break;
+        };
+        indices.extend(found.iter().map(|i| start + i));
+        start += last + 1;
+    }
+    indices
+}
+
+fn merge_indices(indices: impl Iterator<Item = usize>) -> Vec<usize> {
+    let mut indices: Vec<usize> = indices.collect();
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+fn is_glob(query: &str) -> bool {
+    query.contains(GLOB_WILDCARDS)
+}
+
+/// Translates glob to regex matched against whole path, '*' matches any text (including '/')
+/// and '?' single char. Glob not starting with '/' may start anywhere in the path, i.e. 'lib/*.so'.
+/// Literal parts are captured so they can be highlighted
+fn build_glob_regex(glob: &str, case_sensitive: bool) -> Result<Regex> {
+    let mut pattern = String::from(if glob.starts_with('/') { "^" } else { "^.*?" });
+    let push_literal = |pattern: &mut String, literal: &str| {
+        if !literal.is_empty() {
+            pattern.push_str(&format!("({})", regex::escape(literal)));
+        }
+    };
+    let mut rest = glob;
+    while let Some(wildcard) = rest.find(GLOB_WILDCARDS) {
+        push_literal(&mut pattern, &rest[..wildcard]);
+        pattern.push_str(match rest[wildcard..].starts_with('*') {
+            true => ".*?",
+            false => ".",
+        });
+        rest = &rest[wildcard + 1..];
+    }
+    push_literal(&mut pattern, rest);
+    pattern.push('$');
+    build_regex(&pattern, case_sensitive)
+}
+
+/// Char indices of literal parts of glob matched in text
+fn glob_matches(glob: &Regex, text: &str) -> Vec<usize> {
+    let Some(captures) = glob.captures(text) else {
+        return vec![];
+    };
+    captures
+        .iter()
+        .skip(1)
+        .flatten()
+        .flat_map(|found| {
+            let start = text[..found.start()].chars().count();
+            start..start + found.as_str().chars().count()
+        })
+        .collect()
+}
+
+fn build_regex(pattern: &str, case_sensitive: bool) -> Result<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|e| {
+            //NOTE: regex errors are multiline, last line holds the actual reason
+            let message = e.to_string();
+            let reason = message.lines().last().unwrap_or_default();
+            anyhow!("Invalid regex: {}", reason.trim_start_matches("error: "))
+        })
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -91,6 +990,16 @@ pub struct FilterOptions {
     //NOTE: On linux threads can be listed as processes and thus needs filtering
     pub ignore_threads: bool,
     pub include_all_processes: bool,
+    pub fuzzy_search: bool,
+    /// Whole query is treated as regular expression
+    pub regex_search: bool,
+    pub case_sensitivity: CaseSensitivity,
+    /// Children are shown below their parents, ancestors of found processes are included
+    pub tree_view: bool,
+    /// Pik itself and its ancestors (shell, terminal) are removed from results
+    pub hide_self: bool,
+    /// How terms without prefix are searched
+    pub search_mode: SearchBy,
 }
 
 impl Default for FilterOptions {
@@ -98,6 +1007,12 @@ impl Default for FilterOptions {
         Self {
             ignore_threads: true,
             include_all_processes: false,
+            fuzzy_search: true,
+            regex_search: false,
+            case_sensitivity: CaseSensitivity::default(),
+            tree_view: false,
+            hide_self: false,
+            search_mode: SearchBy::Cmd,
         }
     }
 }
@@ -105,13 +1020,16 @@ impl Default for FilterOptions {
 pub(super) struct OptionsFilter<'a> {
     opt: FilterOptions,
     current_user_id: &'a Uid,
+    /// User chosen at startup, takes precedence over showing all or only own processes
+    user_id: Option<&'a Uid>,
 }
 
 impl<'a> OptionsFilter<'a> {
-    pub fn new(opt: FilterOptions, current_user_id: &'a Uid) -> Self {
+    pub fn new(opt: FilterOptions, current_user_id: &'a Uid, user_id: Option<&'a Uid>) -> Self {
         Self {
             opt,
             current_user_id,
+            user_id,
         }
     }
 
@@ -120,6 +1038,9 @@ impl<'a> OptionsFilter<'a> {
             if self.opt.ignore_threads && prc.is_thread() {
                 return false;
             }
+            if let Some(user_id) = self.user_id {
+                return prc.user_id() == Some(user_id);
+            }
             if self.opt.include_all_processes {
                 return true;
             }
@@ -138,43 +1059,67 @@ pub mod tests {
     use super::*;
 
     #[test]
-    fn should_create_proper_query_filter() {
-        let filter = QueryFilter::new("FOO");
-        assert_eq!(filter.search_by, SearchBy::Cmd);
-        assert_eq!(filter.query, "foo");
-
-        let filter = QueryFilter::new("/Foo");
-        assert_eq!(filter.search_by, SearchBy::Path);
-        assert_eq!(filter.query, "foo");
-
-        let filter = QueryFilter::new("-fOo");
-        assert_eq!(filter.search_by, SearchBy::Args);
-        assert_eq!(filter.query, "foo");
-
-        let filter = QueryFilter::new(":foo");
-        assert_eq!(filter.search_by, SearchBy::Port);
-        assert_eq!(filter.query, "foo");
-
-        let filter = QueryFilter::new("~fOO");
-        assert_eq!(filter.search_by, SearchBy::Everywhere);
-        assert_eq!(filter.query, "foo");
-
-        let filter = QueryFilter::new("!1234");
-        assert_eq!(filter.search_by, SearchBy::Pid);
-        assert_eq!(filter.query, "1234");
-
-        let filter = QueryFilter::new("@1234");
-        assert_eq!(filter.search_by, SearchBy::ProcessFamily);
-        assert_eq!(filter.query, "1234");
-
-        let filter = QueryFilter::new("");
-        assert_eq!(filter.search_by, SearchBy::None);
-        assert_eq!(filter.query, "");
+    fn should_create_proper_criterion() {
+        let criterion = Criterion::new("FOO").unwrap();
+        assert_eq!(criterion.search_by, SearchBy::Cmd);
+        assert_eq!(criterion.query, "foo");
+
+        let criterion = Criterion::new("/Foo").unwrap();
+        assert_eq!(criterion.search_by, SearchBy::Path);
+        assert_eq!(criterion.query, "foo");
+
+        let criterion = Criterion::new("-fOo").unwrap();
+        assert_eq!(criterion.search_by, SearchBy::Args);
+        assert_eq!(criterion.query, "foo");
+
+        let criterion = Criterion::new(":foo").unwrap();
+        assert_eq!(criterion.search_by, SearchBy::Port);
+        assert_eq!(criterion.query, "foo");
+
+        let criterion = Criterion::new("~fOO").unwrap();
+        assert_eq!(criterion.search_by, SearchBy::Everywhere);
+        assert_eq!(criterion.query, "foo");
+
+        let criterion = Criterion::new("#1234").unwrap();
+        assert_eq!(criterion.search_by, SearchBy::Pid);
+        assert_eq!(criterion.query, "1234");
+
+        let criterion = Criterion::new("^1234").unwrap();
+        assert_eq!(criterion.search_by, SearchBy::ParentPid);
+        assert_eq!(criterion.query, "1234");
+
+        let criterion = Criterion::new("@1234").unwrap();
+        assert_eq!(criterion.search_by, SearchBy::ProcessFamily);
+        assert_eq!(criterion.query, "1234");
+
+        let criterion = Criterion::new("@Alice").unwrap();
+        assert_eq!(criterion.search_by, SearchBy::User);
+        assert_eq!(criterion.query, "alice");
+
+        let criterion = Criterion::new("@al ").unwrap();
+        assert_eq!(criterion.search_by, SearchBy::User);
+        assert_eq!(criterion.query, "al");
+
+        let criterion = Criterion::new("@  ").unwrap();
+        assert_eq!(criterion.search_by, SearchBy::User);
+        assert_eq!(criterion.query, "");
+
+        let criterion = Criterion::new("@").unwrap();
+        assert_eq!(criterion.search_by, SearchBy::User);
+        assert_eq!(criterion.query, "");
+
+        let criterion = Criterion::new("re:^Post.*").unwrap();
+        assert_eq!(criterion.search_by, SearchBy::Regex);
+        assert_eq!(criterion.query, "^post.*");
+
+        let criterion = Criterion::new("").unwrap();
+        assert_eq!(criterion.search_by, SearchBy::None);
+        assert_eq!(criterion.query, "");
     }
 
     #[test]
     fn query_filter_search_by_cmd() {
-        let filter = QueryFilter::new("test");
+        let filter = QueryFilter::new("test").unwrap();
         let mut process = MockProcessInfo {
             cmd: "TeSt".to_string(),
             ..Default::default()
@@ -197,9 +1142,63 @@ pub mod tests {
         assert!(!filter.accept(&process, None));
     }
 
+    #[test]
+    fn query_filter_search_by_cmd_without_fuzzy() {
+        let filter = QueryFilter::new("dokr").unwrap().fuzzy(false);
+        let mut process = MockProcessInfo {
+            cmd: "dockerd".to_string(),
+            ..Default::default()
+        };
+        assert!(!filter.accept(&process, None));
+
+        process.cmd = "DOKR-agent".to_string();
+        assert!(filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_should_rank_exact_matches_above_fuzzy_ones() {
+        let filter = QueryFilter::new("dock").unwrap();
+        let exact = MockProcessInfo {
+            cmd: "my-docker".to_string(),
+            ..Default::default()
+        };
+        let fuzzy = MockProcessInfo {
+            cmd: "docuserk".to_string(),
+            ..Default::default()
+        };
+        let (exact_score, exact_matches) = filter.rank(&exact);
+        let (fuzzy_score, fuzzy_matches) = filter.rank(&fuzzy);
+
+        assert!(exact_score > fuzzy_score);
+        assert_eq!(exact_matches.cmd, vec![3, 4, 5, 6]);
+        assert_eq!(fuzzy_matches.cmd, vec![0, 1, 2, 7]);
+    }
+
+    #[test]
+    fn query_filter_should_rank_only_search_by_cmd() {
+        let filter = QueryFilter::new("-dock").unwrap();
+        let process = MockProcessInfo {
+            cmd: "docker".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(filter.rank(&process), Default::default());
+    }
+
+    #[test]
+    fn find_substring_should_return_char_indices() {
+        assert_eq!(
+            find_substring("żółw-TEST", "test", false),
+            Some(vec![5, 6, 7, 8])
+        );
+        assert_eq!(find_substring("żółw-TEST", "test", true), None);
+        assert_eq!(find_substring("żółw-TEST", "TE", true), Some(vec![5, 6]));
+        assert_eq!(find_substring("test", "xyz", false), None);
+        assert_eq!(find_substring("test", "", false), Some(vec![]));
+    }
+
     #[test]
     fn query_filter_search_by_path() {
-        let filter = QueryFilter::new("/test");
+        let filter = QueryFilter::new("/test").unwrap();
         let mut process = MockProcessInfo {
             cmd_path: Some("/TeSt".to_string()),
             ..Default::default()
@@ -228,7 +1227,7 @@ pub mod tests {
 
     #[test]
     fn query_filter_search_by_args() {
-        let filter = QueryFilter::new("-test");
+        let filter = QueryFilter::new("-test").unwrap();
         let mut process = MockProcessInfo::default();
 
         process = process.with_args(&["-TeSt"]);
@@ -252,7 +1251,7 @@ pub mod tests {
 
     #[test]
     fn query_filter_search_by_args_ignores_cmd_in_args() {
-        let filter = QueryFilter::new("-test");
+        let filter = QueryFilter::new("-test").unwrap();
         let process = MockProcessInfo {
             cmd: "test".into(),
             args: vec!["-test".into(), "-xxx".into()],
@@ -263,7 +1262,7 @@ pub mod tests {
 
     #[test]
     fn query_filter_search_by_port() {
-        let filter = QueryFilter::new(":12");
+        let filter = QueryFilter::new(":12").unwrap();
         let process = MockProcessInfo::default();
 
         assert!(filter.accept(&process, Some("1234")));
@@ -279,42 +1278,1012 @@ pub mod tests {
 
     #[test]
     fn query_filter_search_by_pid() {
-        let filter = QueryFilter::new("!1234");
+        let filter = QueryFilter::new("#1234").unwrap();
         let mut process = MockProcessInfo {
             pid: 1234,
             ..Default::default()
         };
 
         assert!(filter.accept(&process, None));
-        process.pid = 12345;
+        process.pid = 123;
+        assert!(!filter.accept(&process, None));
+        process.pid = 51234;
         assert!(!filter.accept(&process, None));
     }
 
     #[test]
-    fn query_filter_search_by_process_family() {
-        let filter = QueryFilter::new("@1234");
+    fn query_filter_search_by_pid_prefix() {
+        let filter = QueryFilter::new("#12").unwrap();
         let mut process = MockProcessInfo {
-            pid: 1234,
+            pid: 12,
             ..Default::default()
         };
+        assert!(filter.accept(&process, None));
 
+        process.pid = 120;
         assert!(filter.accept(&process, None));
-        process.pid = 555;
-        assert!(!filter.accept(&process, None));
 
-        process.parent_pid = Some(1234);
+        process.pid = 1234;
         assert!(filter.accept(&process, None));
-        process.parent_pid = Some(555);
-        assert!(!filter.accept(&process, None));
-        process.parent_pid = None;
+
+        process.pid = 312;
         assert!(!filter.accept(&process, None));
     }
 
     #[test]
-    fn query_filter_search_everywhere() {
-        let mut filter = QueryFilter::new("~test");
+    fn query_filter_should_return_matched_pid_prefix() {
+        let process = MockProcessInfo {
+            pid: 1234,
+            ..Default::default()
+        };
+        let (_, matches) = QueryFilter::new("#12").unwrap().rank(&process);
+        assert_eq!(matches.pid, 2);
+
+        let (_, matches) = QueryFilter::new("xxx").unwrap().rank(&process);
+        assert_eq!(matches.pid, 0);
+    }
+
+    #[test]
+    fn query_filter_search_by_parent_pid() {
+        let filter = QueryFilter::new("^1234").unwrap();
         let mut process = MockProcessInfo {
-            cmd: "TEST".into(),
+            pid: 1234,
+            parent_pid: None,
+            ..Default::default()
+        };
+        assert!(!filter.accept(&process, None));
+
+        process.parent_pid = Some(1234);
+        assert!(filter.accept(&process, None));
+
+        process.parent_pid = Some(12345);
+        assert!(!filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_should_reject_invalid_pids() {
+        let error = QueryFilter::new("#99999999999").err().unwrap();
+        assert_eq!(error.to_string(), "PID 99999999999 is out of range");
+
+        let error = QueryFilter::new("^99999999999").err().unwrap();
+        assert_eq!(error.to_string(), "PID 99999999999 is out of range");
+
+        let error = QueryFilter::new("#12a").err().unwrap();
+        assert_eq!(error.to_string(), "Invalid PID: 12a");
+
+        assert!(QueryFilter::new("#").is_ok());
+    }
+
+    #[test]
+    fn query_filter_search_by_process_family() {
+        let filter = QueryFilter::new("@1234").unwrap();
+        let mut process = MockProcessInfo {
+            pid: 1234,
+            ..Default::default()
+        };
+
+        assert!(filter.accept(&process, None));
+        process.pid = 555;
+        assert!(!filter.accept(&process, None));
+
+        process.parent_pid = Some(1234);
+        assert!(filter.accept(&process, None));
+        process.parent_pid = Some(555);
+        assert!(!filter.accept(&process, None));
+        process.parent_pid = None;
+        assert!(!filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_search_by_user_prefix() {
+        let alice = Uid::from_str("1000").unwrap();
+        let bob = Uid::from_str("1001").unwrap();
+        let users = [(&alice, "alice"), (&bob, "bob")];
+        let filter = QueryFilter::new("@al").unwrap().with_users(users);
+        let mut process = MockProcessInfo {
+            user_id: alice.clone(),
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+
+        process.user_id = bob.clone();
+        assert!(!filter.accept(&process, None));
+
+        process.user_id = Uid::from_str("1002").unwrap();
+        assert!(!filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_search_by_empty_user_accepts_all() {
+        let alice = Uid::from_str("1000").unwrap();
+        let filter = QueryFilter::new("@ ")
+            .unwrap()
+            .with_users([(&alice, "alice")]);
+        let process = MockProcessInfo {
+            user_id: Uid::from_str("1002").unwrap(),
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_search_by_regex_with_anchors() {
+        let filter = QueryFilter::new("re:^post.*res$").unwrap();
+        let mut process = MockProcessInfo {
+            cmd: "postgres".to_string(),
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+
+        process.cmd = "PostgreS".to_string();
+        assert!(filter.accept(&process, None));
+
+        process.cmd = "my_postgres".to_string();
+        assert!(!filter.accept(&process, None));
+
+        process.cmd = "postgres_exporter".to_string();
+        assert!(!filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_search_by_regex_with_character_classes() {
+        let filter = QueryFilter::new(r"re:^worker-[a-c]\d+$").unwrap();
+        let mut process = MockProcessInfo {
+            cmd: "worker-a1".to_string(),
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+
+        process.cmd = "worker-c42".to_string();
+        assert!(filter.accept(&process, None));
+
+        process.cmd = "worker-d1".to_string();
+        assert!(!filter.accept(&process, None));
+
+        process.cmd = "worker-a".to_string();
+        assert!(!filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_search_by_regex_fails_on_invalid_pattern() {
+        let error = QueryFilter::new("re:[abc").err().unwrap();
+        assert_eq!(error.to_string(), "Invalid regex: unclosed character class");
+    }
+
+    #[test]
+    fn query_filter_in_regex_mode_should_match_name_or_args() {
+        let filter = QueryFilter::regex(r"java .*-Dapp=(billing|orders)").unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::Regex]);
+        let mut process = MockProcessInfo {
+            cmd: "java".to_string(),
+            ..Default::default()
+        }
+        .with_args(&["java", "-jar", "-Dapp=billing", "app.jar"]);
+        assert!(filter.accept(&process, None));
+
+        process = process.with_args(&["java", "-jar", "-Dapp=reports", "app.jar"]);
+        assert!(!filter.accept(&process, None));
+
+        let filter = QueryFilter::regex("^ja.a$").unwrap();
+        assert!(filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_in_regex_mode_should_not_parse_prefixes() {
+        let filter = QueryFilter::regex(":80 !foo").unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::Regex]);
+        assert_eq!(filter.active_filters(), 1);
+
+        let filter = QueryFilter::regex("").unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::None]);
+    }
+
+    #[test]
+    fn query_filter_in_regex_mode_fails_on_invalid_pattern() {
+        let error = QueryFilter::regex("(java").err().unwrap();
+        assert_eq!(error.to_string(), "Invalid regex: unclosed group");
+    }
+
+    #[test]
+    fn case_sensitivity_should_cycle_through_all_modes() {
+        let mode = CaseSensitivity::Smart;
+        assert_eq!(mode.next(), CaseSensitivity::Sensitive);
+        assert_eq!(mode.next().next(), CaseSensitivity::Insensitive);
+        assert_eq!(mode.next().next().next(), CaseSensitivity::Smart);
+    }
+
+    #[test]
+    fn query_filter_should_respect_case_sensitivity() {
+        let process = MockProcessInfo {
+            cmd: "MyApp".to_string(),
+            ..Default::default()
+        }
+        .with_args(&["MyApp", "--Verbose"]);
+        let accepts = |query: &str, case_sensitivity: CaseSensitivity| {
+            QueryFilter::new(query)
+                .unwrap()
+                .fuzzy(false)
+                .case_sensitivity(case_sensitivity)
+                .accept(&process, None)
+        };
+        assert!(accepts("myapp", CaseSensitivity::Insensitive));
+        assert!(accepts("MYAPP", CaseSensitivity::Insensitive));
+        assert!(!accepts("myapp", CaseSensitivity::Sensitive));
+        assert!(accepts("MyApp", CaseSensitivity::Sensitive));
+        assert!(!accepts("-verbose", CaseSensitivity::Sensitive));
+        assert!(accepts("-Verbose", CaseSensitivity::Sensitive));
+        assert!(accepts("re:^my", CaseSensitivity::Insensitive));
+        assert!(!accepts("re:^my", CaseSensitivity::Sensitive));
+    }
+
+    #[test]
+    fn query_filter_with_smart_case_should_be_sensitive_only_for_uppercase_query() {
+        let process = MockProcessInfo {
+            cmd: "myapp".to_string(),
+            ..Default::default()
+        };
+        let filter = QueryFilter::new("app")
+            .unwrap()
+            .case_sensitivity(CaseSensitivity::Smart);
+        assert!(filter.accept(&process, None));
+
+        let filter = QueryFilter::new("App")
+            .unwrap()
+            .case_sensitivity(CaseSensitivity::Smart);
+        assert!(!filter.accept(&process, None));
+
+        //NOTE: each term decides on its own, like in ripgrep
+        let filter = QueryFilter::new("my App")
+            .unwrap()
+            .case_sensitivity(CaseSensitivity::Smart);
+        assert!(!filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_case_sensitivity_should_not_affect_users() {
+        let alice = Uid::from_str("1000").unwrap();
+        let process = MockProcessInfo {
+            user_id: alice.clone(),
+            ..Default::default()
+        };
+        let filter = QueryFilter::new("@Ali")
+            .unwrap()
+            .case_sensitivity(CaseSensitivity::Sensitive)
+            .with_users([(&alice, "alice")]);
+        assert!(filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_search_by_port_list() {
+        let filter = QueryFilter::new(":80,443").unwrap();
+        let process = MockProcessInfo::default();
+        assert!(filter.accept(&process, Some("443")));
+        assert!(filter.accept(&process, Some("22, 80")));
+        assert!(!filter.accept(&process, Some("8080, 4430")));
+        assert!(!filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_search_by_port_range() {
+        let filter = QueryFilter::new(":8000-9000").unwrap();
+        let process = MockProcessInfo::default();
+        assert!(filter.accept(&process, Some("8000")));
+        assert!(filter.accept(&process, Some("22, 8443")));
+        assert!(filter.accept(&process, Some("9000")));
+        assert!(!filter.accept(&process, Some("7999, 9001")));
+
+        let filter = QueryFilter::new(":22,8000-8080").unwrap();
+        assert!(filter.accept(&process, Some("22")));
+        assert!(filter.accept(&process, Some("8042")));
+        assert!(!filter.accept(&process, Some("443")));
+    }
+
+    #[test]
+    fn should_parse_port_ranges() {
+        assert_eq!(
+            parse_port_ranges("80,443").unwrap(),
+            vec![80..=80, 443..=443]
+        );
+        assert_eq!(parse_port_ranges("9000-8000").unwrap(), vec![8000..=9000]);
+        assert_eq!(parse_port_ranges("80,").unwrap(), vec![80..=80]);
+        assert_eq!(
+            parse_port_ranges("80-abc").err().unwrap().to_string(),
+            "Invalid port range: 80-abc"
+        );
+        assert_eq!(
+            parse_port_ranges("8000-").err().unwrap().to_string(),
+            "Invalid port range: 8000-"
+        );
+        assert_eq!(
+            parse_port_ranges("1-70000").err().unwrap().to_string(),
+            "Invalid port range: 1-70000"
+        );
+        assert_eq!(
+            parse_port_ranges("+80,1").err().unwrap().to_string(),
+            "Invalid port range: +80"
+        );
+        assert!(parse_port_ranges(",").is_err());
+    }
+
+    #[test]
+    fn query_filter_should_search_malformed_port_range_literally() {
+        let filter = QueryFilter::new(":80-90-100").unwrap();
+        assert!(filter.accept(&MockProcessInfo::default(), Some("80-90-100")));
+        assert!(!filter.accept(&MockProcessInfo::default(), Some("85")));
+        assert_eq!(filter.port_filter(), None);
+
+        let filter = QueryFilter::new(":8000-8100 :22").unwrap();
+        assert!(filter.accept(&MockProcessInfo::default(), Some("22, 8080")));
+        assert_eq!(filter.port_filter(), Some("8000-8100".to_string()));
+    }
+
+    #[test]
+    fn query_filter_should_return_matched_ports() {
+        let filter = QueryFilter::new(":8000-9000").unwrap();
+        assert_eq!(
+            filter.matched_ports(Some("22, 8080, 443")),
+            vec![4, 5, 6, 7]
+        );
+        assert_eq!(filter.matched_ports(None), Vec::<usize>::new());
+
+        let filter = QueryFilter::new(":443 nginx").unwrap();
+        assert_eq!(filter.matched_ports(Some("80, 443")), vec![4, 5, 6]);
+
+        let filter = QueryFilter::new("nginx").unwrap();
+        assert_eq!(filter.matched_ports(Some("80, 443")), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn query_filter_search_by_state() {
+        let filter = QueryFilter::new("state:z").unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::State]);
+        let mut process = MockProcessInfo {
+            state: 'Z',
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+
+        process.state = 'S';
+        assert!(!filter.accept(&process, None));
+
+        let filter = QueryFilter::new("state:ZT").unwrap();
+        assert!(!filter.accept(&process, None));
+        process.state = 'T';
+        assert!(filter.accept(&process, None));
+
+        let filter = QueryFilter::new("state:").unwrap();
+        assert!(filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_search_by_deleted_exe() {
+        let filter = QueryFilter::new("deleted:").unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::DeletedExe]);
+        let mut process = MockProcessInfo {
+            cmd_path: Some("/usr/sbin/nginx".to_string()),
+            exe_deleted: true,
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+        assert!(QueryFilter::new("deleted:nginx")
+            .unwrap()
+            .accept(&process, None));
+        assert!(!QueryFilter::new("deleted:postgres")
+            .unwrap()
+            .accept(&process, None));
+
+        process.exe_deleted = false;
+        assert!(!filter.accept(&process, None));
+        assert!(!QueryFilter::new("deleted:nginx")
+            .unwrap()
+            .accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_search_by_container() {
+        let id = format!("4f3c2a1b0e9d{}", "0".repeat(52));
+        let names = HashMap::from([(id.clone(), "web-frontend".to_string())]);
+        let filter = QueryFilter::new("container:").unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::Container]);
+        let mut process = MockProcessInfo {
+            container_id: Some(id),
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+        for query in ["container:4f3c", "container:4F3C", "container:web"] {
+            let filter = QueryFilter::new(query).unwrap().with_containers(&names);
+            assert!(filter.accept(&process, None), "{}", query);
+        }
+        assert!(!QueryFilter::new("container:3c2a")
+            .unwrap()
+            .with_containers(&names)
+            .accept(&process, None));
+
+        process.container_id = None;
+        assert!(!filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_search_by_tty() {
+        let terminal = MockProcessInfo {
+            tty: Some("pts/3".to_string()),
+            ..Default::default()
+        };
+        let daemon = MockProcessInfo::default();
+        let accepts =
+            |query: &str, prc: &MockProcessInfo| QueryFilter::new(query).unwrap().accept(prc, None);
+        assert_eq!(
+            QueryFilter::new("tty:pts/3").unwrap().search_by(),
+            vec![SearchBy::Tty]
+        );
+        for query in ["tty:pts/3", "tty:/dev/pts/3", "tty:pts", "tty:"] {
+            assert!(accepts(query, &terminal), "{}", query);
+            assert!(!accepts(query, &daemon), "{}", query);
+        }
+        assert!(!accepts("tty:pts/30", &terminal));
+        let serial = MockProcessInfo {
+            tty: Some("ttyS0".to_string()),
+            ..Default::default()
+        };
+        assert!(accepts("tty:ttyS0", &serial));
+        assert!(!accepts("tty:pts/", &daemon));
+        assert!(accepts("tty:-", &daemon));
+        assert!(!accepts("tty:-", &terminal));
+    }
+
+    #[test]
+    fn query_filter_search_by_unit() {
+        let filter = QueryFilter::new("unit:").unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::Unit]);
+        let mut process = MockProcessInfo {
+            unit: Some("nginx.service".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+        for query in ["unit:nginx", "unit:NGINX", "unit:.service"] {
+            assert!(
+                QueryFilter::new(query).unwrap().accept(&process, None),
+                "{}",
+                query
+            );
+        }
+        assert!(!QueryFilter::new("unit:sshd")
+            .unwrap()
+            .accept(&process, None));
+
+        process.unit = None;
+        assert!(!filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_should_fail_on_unknown_state() {
+        let error = QueryFilter::new("state:zq").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Unknown state 'q', valid states: r, s, d, z, t, i, x"
+        );
+    }
+
+    #[test]
+    fn query_filter_search_by_memory_threshold() {
+        let filter = QueryFilter::new("java mem>500M").unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::Cmd, SearchBy::Memory]);
+        let mut process = MockProcessInfo {
+            cmd: "java".to_string(),
+            memory: 600 * 1024 * 1024,
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+
+        process.memory = 400 * 1024 * 1024;
+        assert!(!filter.accept(&process, None));
+        assert!(QueryFilter::new("mem<1.5g").unwrap().accept(&process, None));
+
+        let filter = QueryFilter::new("mem>").unwrap();
+        assert!(filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_search_by_memory_in_megabytes() {
+        let mut process = MockProcessInfo {
+            memory: 500 * 1024 * 1024 + 1000,
+            ..Default::default()
+        };
+        assert!(QueryFilter::new("mem>500").unwrap().accept(&process, None));
+        assert!(QueryFilter::new("mem=500").unwrap().accept(&process, None));
+        assert!(!QueryFilter::new("mem<500").unwrap().accept(&process, None));
+
+        process.memory = 499 * 1024 * 1024;
+        assert!(!QueryFilter::new("mem=500").unwrap().accept(&process, None));
+        assert!(QueryFilter::new("mem<500").unwrap().accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_should_describe_memory_filter() {
+        let filter = QueryFilter::new("java mem>500 mem<1G").unwrap();
+        assert_eq!(filter.memory_filter(), Some(">500,<1G".to_string()));
+        assert_eq!(QueryFilter::new("java mem>").unwrap().memory_filter(), None);
+    }
+
+    #[test]
+    fn query_filter_search_by_run_time_threshold() {
+        let now = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let mut process = MockProcessInfo {
+            run_time: now - 3 * 60 * 60,
+            ..Default::default()
+        };
+        let filter = QueryFilter::new("time>2h").unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::RunTime]);
+        assert!(filter.accept(&process, None));
+        assert!(!QueryFilter::new("time>1d").unwrap().accept(&process, None));
+
+        process.run_time = now - 30;
+        assert!(!filter.accept(&process, None));
+        assert!(QueryFilter::new("time<1m").unwrap().accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_search_by_nice_threshold() {
+        let filter = QueryFilter::new("nice<0").unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::Nice]);
+        let mut process = MockProcessInfo {
+            nice: Some(-5),
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+        assert!(QueryFilter::new("nice=-5").unwrap().accept(&process, None));
+        assert!(!QueryFilter::new("nice>-5").unwrap().accept(&process, None));
+
+        process.nice = Some(10);
+        assert!(!filter.accept(&process, None));
+        assert!(QueryFilter::new("nice>-5").unwrap().accept(&process, None));
+
+        process.nice = None;
+        assert!(!filter.accept(&process, None));
+        assert!(QueryFilter::new("nice>-").unwrap().accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_should_fail_on_nice_out_of_range() {
+        let error = QueryFilter::new("nice>20").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Invalid nice '20', accepted values: -20 to 19"
+        );
+        assert!(QueryFilter::new("nice<x").is_err());
+        assert_eq!(
+            QueryFilter::new("nicer").unwrap().search_by(),
+            vec![SearchBy::Cmd]
+        );
+    }
+
+    #[test]
+    fn query_filter_should_search_terms_without_prefix_by_search_mode() {
+        let filter = QueryFilter::with_search_mode("8080 /nginx", SearchBy::Port).unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::Port, SearchBy::Path]);
+        assert!(filter.accept(
+            &MockProcessInfo {
+                cmd_path: Some("/usr/bin/nginx".to_string()),
+                ..Default::default()
+            },
+            Some("80, 8080")
+        ));
+
+        let filter = QueryFilter::with_search_mode("java", SearchBy::Args).unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::Args]);
+        let filter = QueryFilter::with_search_mode("", SearchBy::Args).unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::None]);
+    }
+
+    #[test]
+    fn should_cycle_search_modes() {
+        let mut mode = SearchBy::Cmd;
+        let mut modes = vec![];
+        for _ in 0..5 {
+            mode = mode.next_mode();
+            modes.push(mode);
+        }
+        assert_eq!(
+            modes,
+            vec![
+                SearchBy::Args,
+                SearchBy::Port,
+                SearchBy::User,
+                SearchBy::Path,
+                SearchBy::Cmd
+            ]
+        );
+        assert_eq!(SearchBy::Regex.next_mode(), SearchBy::Cmd);
+    }
+
+    #[test]
+    fn query_filter_should_search_by_name_starting_with_threshold_prefix() {
+        let filter = QueryFilter::new("memcached").unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::Cmd]);
+    }
+
+    #[test]
+    fn query_filter_should_fail_on_invalid_threshold_unit() {
+        let error = QueryFilter::new("mem>500X").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Invalid memory '500X', accepted suffixes: K, M, G"
+        );
+        let error = QueryFilter::new("mem>1.2.3").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Invalid memory '1.2.3', accepted suffixes: K, M, G"
+        );
+        let error = QueryFilter::new("time>2").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Invalid run time '2', accepted suffixes: s, m, h, d"
+        );
+    }
+
+    #[test]
+    fn query_filter_search_by_path_glob() {
+        let path = |cmd_path: &str| MockProcessInfo {
+            cmd_path: Some(cmd_path.to_string()),
+            ..Default::default()
+        };
+        let filter = QueryFilter::new("path:/usr/lib/*/chrome*").unwrap();
+        assert!(filter.accept(&path("/usr/lib/chromium/chrome"), None));
+        assert!(filter.accept(&path("/usr/lib/google/chrome/chrome_crashpad"), None));
+        assert!(!filter.accept(&path("/opt/lib/chromium/chrome"), None));
+        assert!(!filter.accept(&path("/usr/lib/chromium"), None));
+
+        //NOTE: glob without leading '/' may start anywhere in the path
+        let filter = QueryFilter::new("/lib/ch?ome").unwrap();
+        assert!(filter.accept(&path("/usr/lib/chrome"), None));
+        assert!(!filter.accept(&path("/usr/lib/chrome/x"), None));
+    }
+
+    #[test]
+    fn query_filter_search_by_path_should_skip_processes_without_path() {
+        let process = MockProcessInfo {
+            cmd: "kworker/0:1".to_string(),
+            cmd_path: None,
+            ..Default::default()
+        };
+        for query in ["/kworker", "path:/usr/local/bin", "path:*"] {
+            let filter = QueryFilter::new(query).unwrap();
+            assert!(!filter.accept(&process, None), "{}", query);
+        }
+    }
+
+    #[test]
+    fn query_filter_path_glob_should_match_nothing() {
+        let filter = QueryFilter::new("path:/nowhere/*").unwrap();
+        let process = MockProcessInfo {
+            cmd_path: Some("/usr/bin/nowhere/app".to_string()),
+            ..Default::default()
+        };
+        assert!(!filter.accept(&process, None));
+        assert!(!filter.accept(&MockProcessInfo::default(), None));
+        assert!(filter.rank(&process).1.cmd_path.is_empty());
+    }
+
+    #[test]
+    fn query_filter_path_glob_with_multiple_wildcards() {
+        let filter = QueryFilter::new("path:/home/*/.cargo/*/rust-*").unwrap();
+        let process = MockProcessInfo {
+            cmd_path: Some("/home/dev/.cargo/bin/rust-analyzer".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+        let (_, matches) = filter.rank(&process);
+        //NOTE: only literal parts are highlighted
+        let highlighted: String = matches
+            .cmd_path
+            .iter()
+            .map(|i| process.cmd_path.as_ref().unwrap().chars().nth(*i).unwrap())
+            .collect();
+        assert_eq!(highlighted, "/home//.cargo//rust-");
+
+        let process = MockProcessInfo {
+            cmd_path: Some("/home/dev/.cargo/rust-analyzer".to_string()),
+            ..Default::default()
+        };
+        assert!(!filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_path_glob_should_match_paths_with_spaces() {
+        let filter = QueryFilter::new("path:/Applications/*/Contents/MacOS/*").unwrap();
+        let process = MockProcessInfo {
+            cmd_path: Some(
+                "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome".to_string(),
+            ),
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+
+        let filter = QueryFilter::new("path:*Chrome?app*").unwrap();
+        assert!(filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_path_without_wildcards_should_match_substring() {
+        let filter = QueryFilter::new("path:/usr/lib").unwrap().fuzzy(false);
+        let process = MockProcessInfo {
+            cmd_path: Some("/opt/usr/lib/app".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+        let (_, matches) = filter.rank(&process);
+        assert_eq!(matches.cmd_path, (4..12).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn query_filter_path_glob_should_respect_case_sensitivity() {
+        let process = MockProcessInfo {
+            cmd_path: Some("/usr/lib/Chrome".to_string()),
+            ..Default::default()
+        };
+        let filter = QueryFilter::new("path:/usr/*/chrome").unwrap();
+        assert!(filter.accept(&process, None));
+        let filter = QueryFilter::new("path:/usr/*/chrome")
+            .unwrap()
+            .case_sensitivity(CaseSensitivity::Sensitive);
+        assert!(!filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_should_return_matches_in_path_and_args() {
+        let process = MockProcessInfo {
+            cmd: "ssh".to_string(),
+            cmd_path: Some("/usr/bin/ssh".to_string()),
+            ..Default::default()
+        }
+        .with_args(&["ssh", "-i", "~/.ssh/id", "host"]);
+
+        let filter = QueryFilter::new("/ssh").unwrap();
+        let (_, matches) = filter.rank(&process);
+        assert_eq!(matches.cmd_path, vec![9, 10, 11]);
+        assert!(matches.cmd.is_empty());
+        assert!(matches.args.is_empty());
+
+        //NOTE: args are joined with ',' -> "-i,~/.ssh/id,host"
+        let filter = QueryFilter::new("-ssh").unwrap();
+        let (_, matches) = filter.rank(&process);
+        assert_eq!(matches.args, vec![6, 7, 8]);
+
+        let filter = QueryFilter::new("~ssh").unwrap();
+        let (_, matches) = filter.rank(&process);
+        assert_eq!(matches.cmd, vec![0, 1, 2]);
+        assert_eq!(matches.cmd_path, vec![9, 10, 11]);
+        assert_eq!(matches.args, vec![6, 7, 8]);
+    }
+
+    #[test]
+    fn query_filter_should_return_matches_in_non_ascii_path() {
+        let process = MockProcessInfo {
+            cmd: "zażółć".to_string(),
+            cmd_path: Some("/home/żółw/bin/zażółć".to_string()),
+            ..Default::default()
+        }
+        .with_args(&["zażółć", "--gęślą=jaźń", "--jaźń"]);
+
+        let filter = QueryFilter::new("/ŻÓŁW").unwrap();
+        let (_, matches) = filter.rank(&process);
+        assert_eq!(matches.cmd_path, vec![6, 7, 8, 9]);
+
+        let filter = QueryFilter::new("-jaźń").unwrap();
+        let (_, matches) = filter.rank(&process);
+        assert_eq!(matches.args, vec![8, 9, 10, 11, 15, 16, 17, 18]);
+    }
+
+    #[test]
+    fn should_find_all_substrings() {
+        assert_eq!(
+            find_all_substrings("abcABCabc", "abc", false),
+            vec![0, 1, 2, 3, 4, 5, 6, 7, 8]
+        );
+        assert_eq!(
+            find_all_substrings("abcABCabc", "abc", true),
+            vec![0, 1, 2, 6, 7, 8]
+        );
+        assert_eq!(find_all_substrings("aaaa", "aa", false), vec![0, 1, 2, 3]);
+        assert_eq!(
+            find_all_substrings("żółw żółw", "łw", false),
+            vec![2, 3, 7, 8]
+        );
+        assert!(find_all_substrings("abc", "x", false).is_empty());
+    }
+
+    #[test]
+    fn should_split_negations_from_query() {
+        assert_eq!(
+            split_negations("java !jenkins"),
+            (vec!["java".to_string()], vec!["jenkins".to_string()])
+        );
+        assert_eq!(
+            split_negations("!Foo -arg !bar"),
+            (
+                vec!["-arg".to_string()],
+                vec!["foo".to_string(), "bar".to_string()]
+            )
+        );
+        assert_eq!(
+            split_negations("\\!important !"),
+            (vec!["!important".to_string()], vec![])
+        );
+    }
+
+    #[test]
+    fn query_filter_should_exclude_negated_terms() {
+        let filter = QueryFilter::new("java !jenkins").unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::Cmd]);
+        assert_eq!(filter.active_filters(), 2);
+        let mut process = MockProcessInfo {
+            cmd: "java".to_string(),
+            ..Default::default()
+        };
+        process = process.with_args(&["java", "-jar", "app.jar"]);
+        assert!(filter.accept(&process, None));
+
+        process = process.with_args(&["java", "-jar", "/opt/JENKINS/jenkins.war"]);
+        assert!(!filter.accept(&process, None));
+
+        process.cmd = "jenkins-java".to_string();
+        process = process.with_args(&["-jar", "app.jar"]);
+        assert!(!filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_should_exclude_negated_terms_in_port_and_args_search() {
+        let mut process = MockProcessInfo {
+            cmd: "node".to_string(),
+            ..Default::default()
+        };
+        process = process.with_args(&["node", "/opt/server.js", "--inspect"]);
+        for filter in [
+            QueryFilter::new(":3000 !vscode !electron").unwrap(),
+            QueryFilter::with_search_mode("3000 !vscode", SearchBy::Port).unwrap(),
+            QueryFilter::with_search_mode("inspect !vscode", SearchBy::Args).unwrap(),
+        ] {
+            assert!(filter.accept(&process, Some("3000")));
+        }
+
+        process = process.with_args(&["node", "/usr/share/code/vscode-server.js", "--inspect"]);
+        for filter in [
+            QueryFilter::new(":3000 !electron !vscode").unwrap(),
+            QueryFilter::with_search_mode("3000 !vscode", SearchBy::Port).unwrap(),
+            QueryFilter::with_search_mode("inspect !vscode", SearchBy::Args).unwrap(),
+        ] {
+            assert!(!filter.accept(&process, Some("3000")));
+        }
+    }
+
+    #[test]
+    fn query_filter_with_only_negations_accepts_everything_else() {
+        let filter = QueryFilter::new("!chrome !firefox").unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::None]);
+        let mut process = MockProcessInfo {
+            cmd: "bash".to_string(),
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+
+        process.cmd = "chrome".to_string();
+        assert!(!filter.accept(&process, None));
+
+        process.cmd = "firefox-bin".to_string();
+        assert!(!filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_should_allow_escaped_negation() {
+        let filter = QueryFilter::new("\\!bang").unwrap();
+        assert_eq!(filter.search_by(), vec![SearchBy::Cmd]);
+        assert_eq!(filter.criteria[0].query, "!bang");
+        let process = MockProcessInfo {
+            cmd: "!bang".to_string(),
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_should_combine_criteria() {
+        let alice = Uid::from_str("1000").unwrap();
+        let filter = QueryFilter::new(":8080 @alice")
+            .unwrap()
+            .with_users([(&alice, "alice")]);
+        assert_eq!(filter.search_by(), vec![SearchBy::Port, SearchBy::User]);
+        assert_eq!(filter.active_filters(), 2);
+        let mut process = MockProcessInfo {
+            user_id: alice.clone(),
+            ..Default::default()
+        };
+        assert!(filter.accept(&process, Some("8080")));
+        assert!(!filter.accept(&process, Some("9090")));
+
+        process.user_id = Uid::from_str("1001").unwrap();
+        assert!(!filter.accept(&process, Some("8080")));
+    }
+
+    #[test]
+    fn query_filter_should_match_real_or_effective_user() {
+        let alice = Uid::from_str("1000").unwrap();
+        let root = Uid::from_str("0").unwrap();
+        let users = [(&alice, "alice"), (&root, "root")];
+        let process = MockProcessInfo {
+            user_id: alice.clone(),
+            effective_user_id: Some(root.clone()),
+            ..Default::default()
+        };
+        for query in ["@alice", "@root"] {
+            let filter = QueryFilter::new(query).unwrap().with_users(users);
+            assert!(filter.accept(&process, None), "{}", query);
+        }
+        let filter = QueryFilter::new("@bob").unwrap().with_users(users);
+        assert!(!filter.accept(&process, None));
+    }
+
+    #[test]
+    fn query_filter_criteria_order_should_not_matter() {
+        let alice = Uid::from_str("1000").unwrap();
+        let users = [(&alice, "alice")];
+        let filters = [
+            QueryFilter::new(":8080 @alice -config nginx").unwrap(),
+            QueryFilter::new("nginx -config @alice :8080").unwrap(),
+            QueryFilter::new("@alice nginx :8080 -config").unwrap(),
+        ]
+        .map(|f| f.with_users(users));
+        let processes = [
+            MockProcessInfo {
+                cmd: "nginx".to_string(),
+                user_id: alice.clone(),
+                ..Default::default()
+            }
+            .with_args(&["nginx", "--config", "/etc/nginx.conf"]),
+            MockProcessInfo {
+                cmd: "nginx".to_string(),
+                user_id: alice.clone(),
+                ..Default::default()
+            }
+            .with_args(&["nginx"]),
+            MockProcessInfo {
+                cmd: "apache".to_string(),
+                user_id: alice.clone(),
+                ..Default::default()
+            }
+            .with_args(&["apache", "--config"]),
+        ];
+        for ports in [Some("8080"), Some("443")] {
+            for process in processes.iter() {
+                let accepted: Vec<bool> =
+                    filters.iter().map(|f| f.accept(process, ports)).collect();
+                assert!(accepted.iter().all(|a| *a == accepted[0]));
+            }
+        }
+        assert!(filters[0].accept(&processes[0], Some("8080")));
+        assert!(!filters[0].accept(&processes[1], Some("8080")));
+        assert!(!filters[0].accept(&processes[2], Some("8080")));
+    }
+
+    #[test]
+    fn query_filter_should_rank_by_all_name_terms() {
+        let filter = QueryFilter::new("dock :80 erd").unwrap();
+        let process = MockProcessInfo {
+            cmd: "dockerd".to_string(),
+            ..Default::default()
+        };
+        let (score, matches) = filter.rank(&process);
+        assert!(score > MatchScore::default());
+        assert_eq!(matches.cmd, vec![0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn query_filter_search_everywhere() {
+        let mut filter = QueryFilter::new("~test").unwrap();
+        let mut process = MockProcessInfo {
+            cmd: "TEST".into(),
             ..Default::default()
         };
         assert!(filter.accept(&process, None));
@@ -325,7 +2294,7 @@ pub mod tests {
         process = process.with_args(&["-TeSt"]);
         assert!(filter.accept(&process, None));
 
-        filter = QueryFilter::new("~80");
+        filter = QueryFilter::new("~80").unwrap();
         assert!(filter.accept(&process, Some("8080")));
 
         process.cmd = "xxx".into();
@@ -336,7 +2305,7 @@ pub mod tests {
 
     #[test]
     fn query_filter_search_by_none() {
-        let filter = QueryFilter::new("");
+        let filter = QueryFilter::new("").unwrap();
         let mut process = MockProcessInfo::default();
         assert!(filter.accept(&process, None));
 
@@ -361,6 +2330,7 @@ pub mod tests {
                 ..Default::default()
             },
             &current_user_id,
+            None,
         );
         let prc = MockProcessInfo {
             is_thread: true,
@@ -379,6 +2349,7 @@ pub mod tests {
                 ..Default::default()
             },
             &current_user_id,
+            None,
         );
         let prc = MockProcessInfo {
             is_thread: true,
@@ -397,6 +2368,7 @@ pub mod tests {
                 ..Default::default()
             },
             &current_user_id,
+            None,
         );
         let mut prc = MockProcessInfo {
             user_id: current_user_id.clone(),
@@ -417,6 +2389,7 @@ pub mod tests {
                 ..Default::default()
             },
             &current_user_id,
+            None,
         );
         let mut prc = MockProcessInfo {
             user_id: current_user_id.clone(),
@@ -427,4 +2400,26 @@ pub mod tests {
         prc.user_id = Uid::from_str("1001").unwrap();
         assert!(filter.accept(&prc));
     }
+
+    #[test]
+    fn options_filter_should_accept_only_processes_of_given_user() {
+        let current_user_id = Uid::from_str("1000").unwrap();
+        let alice = Uid::from_str("1001").unwrap();
+        let filter = OptionsFilter::new(
+            FilterOptions {
+                include_all_processes: true,
+                ..Default::default()
+            },
+            &current_user_id,
+            Some(&alice),
+        );
+        let mut prc = MockProcessInfo {
+            user_id: alice.clone(),
+            ..Default::default()
+        };
+        assert!(filter.accept(&prc));
+
+        prc.user_id = current_user_id.clone();
+        assert!(!filter.accept(&prc));
+    }
 }
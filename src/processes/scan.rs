@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sysinfo::{Pid, ProcessRefreshKind, RefreshKind, System, Users};
+
+use super::containers::{container_names, process_container_id};
+use super::cpu_time::process_cpu_time;
+use super::disk_io::IoMonitor;
+use super::terminals::process_tty;
+use super::units::process_unit;
+use super::utils::{process_exe_deleted, process_nice};
+use super::{refresh_ports, ProcessPorts};
+
+/// Everything that is refreshed by scanning the system, it is moved to background thread while scan runs
+#[derive(Default)]
+pub(super) struct Scanned {
+    pub(super) sys: System,
+    pub(super) users: Users,
+    pub(super) process_ports: ProcessPorts,
+    /// Names of containers by their id, known only when built with 'docker' feature
+    pub(super) container_names: HashMap<String, String>,
+    pub(super) io_monitor: IoMonitor,
+    /// Values sysinfo does not provide by pid, they are read with the scan so searching does not touch the system
+    pub(super) extras: HashMap<u32, ProcessExtras>,
+    /// Time of the first scan, cpu usage is known only after the second one
+    first_scan: Option<Instant>,
+}
+
+impl Scanned {
+    pub(super) fn new() -> Self {
+        let sys = System::new_with_specifics(
+            RefreshKind::default().with_processes(process_refresh_kind()),
+        );
+        let mut io_monitor = IoMonitor::default();
+        io_monitor.refresh(sampled_processes(&sys), Instant::now());
+        let extras = read_extras(&sys);
+        Self {
+            sys,
+            extras,
+            users: Users::new_with_refreshed_list(),
+            process_ports: refresh_ports(),
+            container_names: container_names(),
+            io_monitor,
+            first_scan: Some(Instant::now()),
+        }
+    }
+
+    pub(super) fn process(&self, pid: u32) -> Option<ScannedProcess<'_>> {
+        let prc = self.sys.process(Pid::from_u32(pid))?;
+        Some(self.scanned_process(prc))
+    }
+
+    pub(super) fn processes(&self) -> impl Iterator<Item = ScannedProcess<'_>> {
+        self.sys
+            .processes()
+            .values()
+            .map(|prc| self.scanned_process(prc))
+    }
+
+    //NOTE: process without extras appeared after they were read, it is seen as one without them
+    fn scanned_process<'a>(&'a self, prc: &'a sysinfo::Process) -> ScannedProcess<'a> {
+        let extras = self.extras.get(&prc.pid().as_u32()).unwrap_or(&NO_EXTRAS);
+        ScannedProcess { prc, extras }
+    }
+
+    /// Scans all processes again, it is much slower than searching them
+    pub(super) fn refresh(&mut self) {
+        //NOTE: cpu usage is computed between two scans, too short interval gives wrong values
+        if let Some(first_scan) = self.first_scan.take() {
+            thread::sleep(
+                sysinfo::MINIMUM_CPU_UPDATE_INTERVAL.saturating_sub(first_scan.elapsed()),
+            );
+        }
+        self.sys
+            .refresh_processes_specifics(sysinfo::ProcessesToUpdate::All, process_refresh_kind());
+        self.io_monitor
+            .refresh(sampled_processes(&self.sys), Instant::now());
+        self.extras = read_extras(&self.sys);
+        // TODO: do we really need to refresh users?
+        self.users.refresh_list();
+        self.process_ports = refresh_ports();
+        self.container_names = container_names();
+    }
+}
+
+/// Scan running in background thread, scanned state comes back once it is done
+pub(super) struct BackgroundScan {
+    receiver: Receiver<Scanned>,
+    /// Refresh was requested again while scanning, state is scanned once more before it is used
+    pub(super) rescan: bool,
+}
+
+impl BackgroundScan {
+    pub(super) fn start(mut scanned: Scanned) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            scanned.refresh();
+            //NOTE: receiver is gone only when pik quits
+            let _ = sender.send(scanned);
+        });
+        Self {
+            receiver,
+            rescan: false,
+        }
+    }
+
+    /// Scanned state when scan is done, None while it runs
+    pub(super) fn try_finish(&self) -> Option<Scanned> {
+        match self.receiver.try_recv() {
+            Ok(scanned) => Some(scanned),
+            Err(TryRecvError::Empty) => None,
+            //NOTE: scan thread panicked, next refresh starts from scratch
+            Err(TryRecvError::Disconnected) => Some(Scanned::new()),
+        }
+    }
+
+    pub(super) fn wait(self) -> Scanned {
+        self.receiver.recv().unwrap_or_else(|_| Scanned::new())
+    }
+}
+
+/// Process details read from /proc (or by syscall) once per scan
+#[derive(Debug, Default)]
+pub(super) struct ProcessExtras {
+    pub(super) nice: Option<i32>,
+    pub(super) exe_deleted: bool,
+    pub(super) container_id: Option<String>,
+    pub(super) unit: Option<String>,
+    pub(super) tty: Option<String>,
+    pub(super) cpu_time: Option<Duration>,
+}
+
+static NO_EXTRAS: ProcessExtras = ProcessExtras {
+    nice: None,
+    exe_deleted: false,
+    container_id: None,
+    unit: None,
+    tty: None,
+    cpu_time: None,
+};
+
+/// Process of the last scan with its extras, searching it reads nothing from the system
+#[derive(Clone, Copy)]
+pub(super) struct ScannedProcess<'a> {
+    pub(super) prc: &'a sysinfo::Process,
+    pub(super) extras: &'a ProcessExtras,
+}
+
+fn read_extras(sys: &System) -> HashMap<u32, ProcessExtras> {
+    sys.processes()
+        .keys()
+        .map(|pid| {
+            let pid = pid.as_u32();
+            let extras = ProcessExtras {
+                nice: process_nice(pid),
+                exe_deleted: process_exe_deleted(pid),
+                container_id: process_container_id(pid),
+                unit: process_unit(pid),
+                tty: process_tty(pid),
+                cpu_time: process_cpu_time(pid),
+            };
+            (pid, extras)
+        })
+        .collect()
+}
+
+/// Pid with start time of every process, start time tells reused pid apart
+pub(super) fn sampled_processes(sys: &System) -> impl Iterator<Item = (u32, u64)> + '_ {
+    sys.processes()
+        .values()
+        .map(|prc| (prc.pid().as_u32(), prc.start_time()))
+}
+
+pub(super) fn process_refresh_kind() -> ProcessRefreshKind {
+    ProcessRefreshKind::default()
+        .with_cpu()
+        .with_memory()
+        .with_cmd(sysinfo::UpdateKind::OnlyIfNotSet)
+        .with_exe(sysinfo::UpdateKind::OnlyIfNotSet)
+        .with_user(sysinfo::UpdateKind::OnlyIfNotSet)
+        //NOTE: working directory can change while process runs
+        .with_cwd(sysinfo::UpdateKind::Always)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processes::ProcessInfo;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn should_read_extras_of_processes_with_scan() {
+        let scanned = Scanned::new();
+        let own = scanned.process(std::process::id()).unwrap();
+        assert!(own.cpu_time().is_some());
+        assert!(own.nice().is_some());
+        assert!(scanned.extras.contains_key(&std::process::id()));
+    }
+}
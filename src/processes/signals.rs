@@ -0,0 +1,218 @@
+use anyhow::{anyhow, Result};
+use sysinfo::Signal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KillSignal {
+    pub signal: Signal,
+    pub name: &'static str,
+    pub number: i32,
+}
+
+impl KillSignal {
+    const fn new(signal: Signal, name: &'static str, number: i32) -> Self {
+        Self {
+            signal,
+            name,
+            number,
+        }
+    }
+}
+
+/// Cannot be ignored, it is offered when process survives other signal
+#[cfg(unix)]
+pub const FORCE_KILL_SIGNAL: KillSignal = KillSignal::new(Signal::Kill, "SIGKILL", libc::SIGKILL);
+
+#[cfg(not(unix))]
+pub const FORCE_KILL_SIGNAL: KillSignal = KillSignal::new(Signal::Kill, "SIGKILL", 9);
+
+#[cfg(unix)]
+const KILL_SIGNALS: &[KillSignal] = &[
+    KillSignal::new(Signal::Term, "SIGTERM", libc::SIGTERM),
+    FORCE_KILL_SIGNAL,
+    KillSignal::new(Signal::Hangup, "SIGHUP", libc::SIGHUP),
+    KillSignal::new(Signal::Interrupt, "SIGINT", libc::SIGINT),
+    KillSignal::new(Signal::Quit, "SIGQUIT", libc::SIGQUIT),
+    KillSignal::new(Signal::User1, "SIGUSR1", libc::SIGUSR1),
+    KillSignal::new(Signal::User2, "SIGUSR2", libc::SIGUSR2),
+];
+
+/// Stops process until it is continued, unlike SIGTSTP it cannot be ignored
+#[cfg(unix)]
+pub const PAUSE_SIGNAL: KillSignal = KillSignal::new(Signal::Stop, "SIGSTOP", libc::SIGSTOP);
+
+#[cfg(unix)]
+pub const RESUME_SIGNAL: KillSignal = KillSignal::new(Signal::Continue, "SIGCONT", libc::SIGCONT);
+
+//NOTE: on windows process can only be terminated
+#[cfg(not(unix))]
+const KILL_SIGNALS: &[KillSignal] = &[FORCE_KILL_SIGNAL];
+
+/// Signals that can be sent to process on current platform, first one is the default
+pub fn kill_signals() -> &'static [KillSignal] {
+    KILL_SIGNALS
+}
+
+/// Signal given by name, with or without 'SIG' prefix in any case, or by its number, i.e. 'SIGTERM', 'kill' or '9'
+pub fn parse_kill_signal(value: &str) -> Result<KillSignal> {
+    let value = value.trim();
+    let name = value.to_uppercase();
+    let name = name.strip_prefix("SIG").unwrap_or(&name);
+    kill_signals()
+        .iter()
+        .find(|s| match value.parse::<i32>() {
+            Ok(number) => s.number == number,
+            Err(_) => s.name.strip_prefix("SIG") == Some(name),
+        })
+        .copied()
+        .ok_or_else(|| {
+            let valid: Vec<String> = kill_signals()
+                .iter()
+                .map(|s| format!("{} ({})", s.name, s.number))
+                .collect();
+            anyhow!(
+                "Invalid signal '{}', valid signals are: {}",
+                value,
+                valid.join(", ")
+            )
+        })
+}
+
+/// Sends signal to the process, error tells why it failed, i.e. process of other user
+#[cfg(unix)]
+pub(super) fn send_signal(pid: u32, signal: KillSignal) -> Result<()> {
+    //NOTE: zero or negative pid would signal whole process group or all processes
+    let pid = libc::pid_t::try_from(pid)
+        .ok()
+        .filter(|pid| *pid > 0)
+        .ok_or_else(|| anyhow!("Invalid PID {}", pid))?;
+    match unsafe { libc::kill(pid, signal.number) } {
+        0 => Ok(()),
+        _ => Err(kill_error(pid as u32, std::io::Error::last_os_error())),
+    }
+}
+
+#[cfg(not(unix))]
+pub(super) fn send_signal(pid: u32, signal: KillSignal) -> Result<()> {
+    let mut sys = sysinfo::System::new();
+    let pid = sysinfo::Pid::from_u32(pid);
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[pid]));
+    let prc = sys
+        .process(pid)
+        .ok_or_else(|| anyhow!("Process {} no longer exists", pid))?;
+    match prc.kill_with(signal.signal) {
+        Some(true) => Ok(()),
+        _ => Err(anyhow!("Failed to kill PID {}, check permissions", pid)),
+    }
+}
+
+/// Process is gone or it is a zombie waiting for its parent, i.e. signal really killed it.
+/// Only this process is scanned, so it is cheap enough to check repeatedly
+pub(super) fn is_terminated(pid: u32) -> bool {
+    let mut sys = sysinfo::System::new();
+    let pid = sysinfo::Pid::from_u32(pid);
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[pid]));
+    let running = sys.process(pid).is_some_and(|prc| {
+        !matches!(
+            prc.status(),
+            sysinfo::ProcessStatus::Zombie | sysinfo::ProcessStatus::Dead
+        )
+    });
+    !running
+}
+
+#[cfg(unix)]
+fn kill_error(pid: u32, err: std::io::Error) -> anyhow::Error {
+    match err.raw_os_error() {
+        Some(libc::EPERM) => anyhow!(
+            "Permission denied killing PID {}: try running with sudo",
+            pid
+        ),
+        Some(libc::ESRCH) => anyhow!("Process {} no longer exists", pid),
+        _ => anyhow!("Failed to kill PID {}: {}", pid, err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_kill_signals_should_be_supported_by_platform() {
+        assert!(kill_signals()
+            .iter()
+            .all(|s| sysinfo::SUPPORTED_SIGNALS.contains(&s.signal)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pause_and_resume_signals_should_be_supported_by_platform() {
+        assert!(sysinfo::SUPPORTED_SIGNALS.contains(&PAUSE_SIGNAL.signal));
+        assert!(sysinfo::SUPPORTED_SIGNALS.contains(&RESUME_SIGNAL.signal));
+        assert_eq!(PAUSE_SIGNAL.number, libc::SIGSTOP);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn default_kill_signal_should_be_sigterm() {
+        assert_eq!(kill_signals()[0].name, "SIGTERM");
+        assert_eq!(kill_signals()[0].number, 15);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_parse_signal_by_name_or_number() {
+        for value in ["9", "SIGKILL", "kill", "sigkill", " KILL "] {
+            assert_eq!(parse_kill_signal(value).unwrap(), FORCE_KILL_SIGNAL);
+        }
+        assert_eq!(parse_kill_signal("15").unwrap().name, "SIGTERM");
+        assert_eq!(parse_kill_signal("SIGUSR1").unwrap().number, libc::SIGUSR1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_list_valid_signals_when_signal_is_invalid() {
+        for value in ["SIGFOO", "999", "SIGSTOP", ""] {
+            let error = parse_kill_signal(value).unwrap_err().to_string();
+            assert!(error.starts_with(&format!("Invalid signal '{}'", value)));
+            assert!(error.contains("SIGTERM (15), SIGKILL (9)"));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_explain_why_kill_failed() {
+        let error = |errno| kill_error(1, std::io::Error::from_raw_os_error(errno)).to_string();
+        assert_eq!(
+            error(libc::EPERM),
+            "Permission denied killing PID 1: try running with sudo"
+        );
+        assert_eq!(error(libc::ESRCH), "Process 1 no longer exists");
+        assert!(error(libc::EINVAL).starts_with("Failed to kill PID 1: "));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_not_signal_process_groups() {
+        let error = send_signal(0, kill_signals()[0]).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid PID 0");
+        let error = send_signal(u32::MAX, kill_signals()[0]).unwrap_err();
+        assert_eq!(error.to_string(), format!("Invalid PID {}", u32::MAX));
+    }
+
+    #[test]
+    fn should_know_that_own_process_is_running() {
+        assert!(!is_terminated(std::process::id()));
+        assert!(is_terminated(i32::MAX as u32));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_fail_to_signal_missing_process() {
+        let pid = i32::MAX as u32;
+        let error = send_signal(pid, kill_signals()[0]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("Process {} no longer exists", pid)
+        );
+    }
+}
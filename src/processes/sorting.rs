@@ -0,0 +1,152 @@
+use std::cmp::Ordering;
+
+use super::Process;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    User,
+    Pid,
+    Parent,
+    StartTime,
+    RunTime,
+    Memory,
+    Cpu,
+    /// Optional column, it can be sorted only when shown
+    Threads,
+    Cmd,
+    CmdPath,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sort {
+    pub column: SortColumn,
+    pub order: SortOrder,
+}
+
+impl SortColumn {
+    /// Columns in table order
+    pub const ALL: [SortColumn; 10] = [
+        SortColumn::User,
+        SortColumn::Pid,
+        SortColumn::Parent,
+        SortColumn::StartTime,
+        SortColumn::RunTime,
+        SortColumn::Memory,
+        SortColumn::Cpu,
+        SortColumn::Threads,
+        SortColumn::Cmd,
+        SortColumn::CmdPath,
+    ];
+
+    /// Next column in table order, None after the last one means no sorting
+    pub fn next(column: Option<SortColumn>) -> Option<SortColumn> {
+        match column {
+            None => Some(Self::ALL[0]),
+            Some(column) => {
+                let index = Self::ALL.iter().position(|c| *c == column).unwrap_or(0);
+                Self::ALL.get(index + 1).copied()
+            }
+        }
+    }
+
+    fn compare(&self, a: &Process, b: &Process) -> Ordering {
+        match self {
+            SortColumn::User => a.user_name.cmp(&b.user_name),
+            SortColumn::Pid => a.pid.cmp(&b.pid),
+            SortColumn::Parent => a.parent_pid.cmp(&b.parent_pid),
+            SortColumn::StartTime => a.start_timestamp.cmp(&b.start_timestamp),
+            //NOTE: process started later runs for shorter time
+            SortColumn::RunTime => b.start_timestamp.cmp(&a.start_timestamp),
+            SortColumn::Memory => a.memory.cmp(&b.memory),
+            SortColumn::Cpu => a.cpu_usage.total_cmp(&b.cpu_usage),
+            //NOTE: unknown thread count is sorted as the lowest one
+            SortColumn::Threads => a.threads.cmp(&b.threads),
+            SortColumn::Cmd => a.cmd.to_lowercase().cmp(&b.cmd.to_lowercase()),
+            SortColumn::CmdPath => a.cmd_path.cmp(&b.cmd_path),
+        }
+    }
+}
+
+impl SortOrder {
+    pub fn toggle(self) -> Self {
+        match self {
+            SortOrder::Ascending => SortOrder::Descending,
+            SortOrder::Descending => SortOrder::Ascending,
+        }
+    }
+}
+
+impl Sort {
+    pub(super) fn compare(&self, a: &Process, b: &Process) -> Ordering {
+        let ordering = self.column.compare(a, b);
+        match self.order {
+            SortOrder::Ascending => ordering,
+            SortOrder::Descending => ordering.reverse(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_cycle_through_all_columns_and_back_to_none() {
+        let mut column = None;
+        let mut visited = vec![];
+        loop {
+            column = SortColumn::next(column);
+            match column {
+                Some(c) => visited.push(c),
+                None => break,
+            }
+        }
+        assert_eq!(visited, SortColumn::ALL);
+    }
+
+    #[test]
+    fn should_sort_unknown_thread_count_first() {
+        let process = |pid: u32, threads: Option<usize>| Process {
+            pid,
+            threads,
+            ..Default::default()
+        };
+        let mut processes = [process(1, Some(8)), process(2, None), process(3, Some(2))];
+        let sort = Sort {
+            column: SortColumn::Threads,
+            order: SortOrder::Descending,
+        };
+        processes.sort_by(|a, b| sort.compare(a, b));
+        let pids: Vec<u32> = processes.iter().map(|prc| prc.pid).collect();
+        assert_eq!(pids, vec![1, 3, 2]);
+    }
+
+    #[test]
+    fn should_sort_by_run_time_from_start_time() {
+        let process = |pid: u32, start_timestamp: u64| Process {
+            pid,
+            start_timestamp,
+            ..Default::default()
+        };
+        let mut processes = [process(1, 200), process(2, 100), process(3, 300)];
+        let sort = Sort {
+            column: SortColumn::RunTime,
+            order: SortOrder::Ascending,
+        };
+        processes.sort_by(|a, b| sort.compare(a, b));
+        let pids: Vec<u32> = processes.iter().map(|prc| prc.pid).collect();
+        assert_eq!(pids, vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn should_toggle_sort_order() {
+        assert_eq!(SortOrder::Ascending.toggle(), SortOrder::Descending);
+        assert_eq!(SortOrder::Descending.toggle(), SortOrder::Ascending);
+    }
+}
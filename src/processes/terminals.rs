@@ -0,0 +1,84 @@
+/// Controlling terminal of the process, i.e. 'pts/3' or 'tty1', read from /proc/<pid>/stat.
+/// None for daemons and on platforms other than linux
+#[cfg(target_os = "linux")]
+pub(super) fn process_tty(pid: u32) -> Option<String> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let tty_nr = parse_tty_nr(&stat)?;
+    tty_name(tty_nr).or_else(|| linux::find_device(tty_nr))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(super) fn process_tty(_pid: u32) -> Option<String> {
+    None
+}
+
+/// Device number of controlling terminal (field 7), 0 means there is none.
+/// Process name (field 2) is in parentheses and may contain spaces, so fields are counted after it
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_tty_nr(stat: &str) -> Option<u64> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    let tty_nr = fields.split_whitespace().nth(4)?.parse().ok()?;
+    (tty_nr != 0).then_some(tty_nr)
+}
+
+/// Device numbers are encoded like in linux kdev_t, minor number is split around major
+fn major_minor(tty_nr: u64) -> (u64, u64) {
+    let major = (tty_nr >> 8) & 0xfff;
+    let minor = (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00);
+    (major, minor)
+}
+
+/// Names of common terminals, other devices are looked up in /dev
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn tty_name(tty_nr: u64) -> Option<String> {
+    match major_minor(tty_nr) {
+        //NOTE: pseudo terminals use 8 majors, 256 minors each
+        (major @ 136..=143, minor) => Some(format!("pts/{}", (major - 136) * 256 + minor)),
+        (4, minor @ 0..=63) => Some(format!("tty{}", minor)),
+        (4, minor) => Some(format!("ttyS{}", minor - 64)),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs;
+    use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+    use super::major_minor;
+
+    /// Character device in /dev with the same device number, i.e. 'ttyUSB0' or 'console'
+    pub(super) fn find_device(tty_nr: u64) -> Option<String> {
+        let expected = major_minor(tty_nr);
+        fs::read_dir("/dev").ok()?.find_map(|entry| {
+            let entry = entry.ok()?;
+            let metadata = entry.metadata().ok()?;
+            let is_device =
+                metadata.file_type().is_char_device() && major_minor(metadata.rdev()) == expected;
+            is_device.then(|| entry.file_name().to_string_lossy().to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_tty_of_process() {
+        let stat = "4321 (bash) S 4320 4321 4321 34819 4400 4194560 1234 0 0 0 1 0 0 0 20 0 1 0";
+        assert_eq!(parse_tty_nr(stat), Some(34819));
+        let stat = "77 (tmux: server (1)) S 1 77 77 0 -1 4194368 300 0 0 0 2 1 0 0 20 0 1 0";
+        assert_eq!(parse_tty_nr(stat), None);
+        assert_eq!(parse_tty_nr(""), None);
+    }
+
+    #[test]
+    fn should_name_common_terminals() {
+        assert_eq!(tty_name(34819), Some("pts/3".to_string()));
+        assert_eq!(tty_name((137 << 8) | 2), Some("pts/258".to_string()));
+        assert_eq!(tty_name((4 << 8) | 1), Some("tty1".to_string()));
+        assert_eq!(tty_name((4 << 8) | 64), Some("ttyS0".to_string()));
+        assert_eq!(tty_name(188 << 8), None);
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::{HashMap, HashSet};
+
+const BRANCH: &str = "├─ ";
+const LAST_BRANCH: &str = "└─ ";
+const PIPE: &str = "│  ";
+const SPACE: &str = "   ";
+const COLLAPSED: &str = "▸ ";
+
+/// Orders (pid, parent pid) pairs depth first so children follow their parents.
+/// Returns index of each node with box drawing prefix that should be rendered before it.
+/// Processes whose parent is not on the list are placed at the top level,
+/// siblings keep their original order. Descendants of collapsed nodes are left out.
+pub(super) fn tree_order(
+    nodes: &[(u32, Option<u32>)],
+    collapsed: &HashSet<u32>,
+) -> Vec<(usize, String)> {
+    let pids: HashSet<u32> = nodes.iter().map(|(pid, _)| *pid).collect();
+    let mut roots = vec![];
+    let mut children: HashMap<u32, Vec<usize>> = HashMap::new();
+    for (index, (pid, parent)) in nodes.iter().enumerate() {
+        match parent {
+            Some(parent) if parent != pid && pids.contains(parent) => {
+                children.entry(*parent).or_default().push(index)
+            }
+            _ => roots.push(index),
+        }
+    }
+
+    let mut ordered = Vec::with_capacity(nodes.len());
+    let mut visited = HashSet::new();
+    //NOTE: stack holds node index, indentation of its parent and whether it is the last child
+    let mut stack: Vec<(usize, String, Option<bool>)> = roots
+        .into_iter()
+        .rev()
+        .map(|index| (index, String::new(), None))
+        .collect();
+    while let Some((index, indent, is_last)) = stack.pop() {
+        if !visited.insert(index) {
+            continue;
+        }
+        let (mut prefix, children_indent) = match is_last {
+            None => (String::new(), String::new()),
+            Some(true) => (format!("{indent}{LAST_BRANCH}"), format!("{indent}{SPACE}")),
+            Some(false) => (format!("{indent}{BRANCH}"), format!("{indent}{PIPE}")),
+        };
+        if let Some(node_children) = children.get(&nodes[index].0) {
+            if collapsed.contains(&nodes[index].0) {
+                prefix.push_str(COLLAPSED);
+                ordered.push((index, prefix));
+                continue;
+            }
+            let last = node_children.len() - 1;
+            for (i, child) in node_children.iter().enumerate().rev() {
+                stack.push((*child, children_indent.clone(), Some(i == last)));
+            }
+        }
+        ordered.push((index, prefix));
+    }
+    ordered
+}
+
+/// Root with all its descendants, every process is listed after its children
+pub(super) fn subtree_children_first(root: u32, nodes: &[(u32, Option<u32>)]) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (pid, parent) in nodes {
+        if let Some(parent) = parent.filter(|parent| parent != pid) {
+            children.entry(parent).or_default().push(*pid);
+        }
+    }
+    let mut ordered = vec![];
+    let mut visited = HashSet::new();
+    //NOTE: node is pushed second time (expanded) when all its children are on the stack above it
+    let mut stack = vec![(root, false)];
+    while let Some((pid, expanded)) = stack.pop() {
+        if expanded {
+            ordered.push(pid);
+            continue;
+        }
+        if !visited.insert(pid) {
+            continue;
+        }
+        stack.push((pid, true));
+        if let Some(node_children) = children.get(&pid) {
+            stack.extend(node_children.iter().rev().map(|child| (*child, false)));
+        }
+    }
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_place_children_below_parents() {
+        let nodes = [
+            (3, Some(1)),
+            (1, None),
+            (4, Some(2)),
+            (2, Some(1)),
+            (5, Some(2)),
+        ];
+        assert_eq!(
+            tree_order(&nodes, &HashSet::new()),
+            vec![
+                (1, "".to_string()),
+                (0, "├─ ".to_string()),
+                (3, "└─ ".to_string()),
+                (2, "   ├─ ".to_string()),
+                (4, "   └─ ".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_draw_pipes_for_unfinished_branches() {
+        let nodes = [(1, None), (2, Some(1)), (3, Some(2)), (4, Some(1))];
+        let prefixes: Vec<String> = tree_order(&nodes, &HashSet::new())
+            .into_iter()
+            .map(|(_, p)| p)
+            .collect();
+        assert_eq!(prefixes, vec!["", "├─ ", "│  └─ ", "└─ "]);
+    }
+
+    #[test]
+    fn should_render_orphans_at_top_level() {
+        let nodes = [(10, Some(7)), (11, Some(10)), (20, Some(8))];
+        assert_eq!(
+            tree_order(&nodes, &HashSet::new()),
+            vec![
+                (0, "".to_string()),
+                (1, "└─ ".to_string()),
+                (2, "".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_not_loop_on_process_being_its_own_parent() {
+        let nodes = [(0, Some(0)), (1, Some(0))];
+        assert_eq!(
+            tree_order(&nodes, &HashSet::new()),
+            vec![(0, "".to_string()), (1, "└─ ".to_string())]
+        );
+    }
+
+    #[test]
+    fn should_leave_out_descendants_of_collapsed_nodes() {
+        let nodes = [
+            (1, None),
+            (2, Some(1)),
+            (3, Some(2)),
+            (4, Some(1)),
+            (5, Some(4)),
+        ];
+        assert_eq!(
+            tree_order(&nodes, &HashSet::from([2, 5])),
+            vec![
+                (0, "".to_string()),
+                (1, "├─ ▸ ".to_string()),
+                (3, "└─ ".to_string()),
+                (4, "   └─ ".to_string()),
+            ]
+        );
+        assert_eq!(
+            tree_order(&nodes, &HashSet::from([1])),
+            vec![(0, "▸ ".to_string())]
+        );
+    }
+
+    #[test]
+    fn should_list_children_before_parents() {
+        let nodes = [
+            (1, None),
+            (2, Some(1)),
+            (3, Some(2)),
+            (4, Some(2)),
+            (5, Some(1)),
+            (6, Some(3)),
+            (7, Some(100)),
+        ];
+        assert_eq!(subtree_children_first(2, &nodes), vec![6, 3, 4, 2]);
+        assert_eq!(subtree_children_first(5, &nodes), vec![5]);
+        assert_eq!(subtree_children_first(1, &nodes), vec![6, 3, 4, 2, 5, 1]);
+    }
+
+    #[test]
+    fn should_not_loop_on_cycles_in_subtree() {
+        let nodes = [(1, Some(2)), (2, Some(1)), (3, Some(3))];
+        assert_eq!(subtree_children_first(1, &nodes), vec![2, 1]);
+        assert_eq!(subtree_children_first(3, &nodes), vec![3]);
+    }
+}
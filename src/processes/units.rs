@@ -0,0 +1,88 @@
+/// Systemd unit the process belongs to, read from /proc/<pid>/cgroup, i.e. 'nginx.service'.
+/// Units of user managers are prefixed with the manager, i.e. 'user@1000/app-firefox.scope'
+#[cfg(target_os = "linux")]
+pub(super) fn process_unit(pid: u32) -> Option<String> {
+    let cgroup = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    parse_unit(&cgroup)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(super) fn process_unit(_pid: u32) -> Option<String> {
+    None
+}
+
+/// Systemd hierarchy is the unified one with cgroup v2 ('0::/system.slice/nginx.service')
+/// or the named one with cgroup v1 ('1:name=systemd:/system.slice/nginx.service')
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_unit(cgroup: &str) -> Option<String> {
+    let path = cgroup.lines().find_map(|line| {
+        let mut fields = line.splitn(3, ':');
+        let controllers = fields.nth(1)?;
+        let path = fields.next()?;
+        (controllers.is_empty() || controllers == "name=systemd").then_some(path)
+    })?;
+    let dirs: Vec<&str> = path.split('/').collect();
+    let user_manager = dirs
+        .iter()
+        .position(|dir| dir.starts_with("user@") && dir.ends_with(".service"));
+    match user_manager {
+        Some(index) => {
+            let manager = dirs[index].trim_end_matches(".service");
+            match last_unit(&dirs[index + 1..]) {
+                Some(unit) => Some(format!("{}/{}", manager, unit)),
+                None => Some(manager.to_string()),
+            }
+        }
+        None => last_unit(&dirs).map(|unit| unit.to_string()),
+    }
+}
+
+/// Slices only group other units, processes run in services and scopes
+fn last_unit<'a>(dirs: &[&'a str]) -> Option<&'a str> {
+    dirs.iter()
+        .rev()
+        .find(|dir| dir.ends_with(".service") || dir.ends_with(".scope"))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_find_system_service() {
+        assert_eq!(
+            parse_unit("0::/system.slice/nginx.service\n"),
+            Some("nginx.service".to_string())
+        );
+        let cgroup_v1 = "12:cpuset:/\n1:name=systemd:/system.slice/sshd.service\n";
+        assert_eq!(parse_unit(cgroup_v1), Some("sshd.service".to_string()));
+    }
+
+    #[test]
+    fn should_prefix_units_of_user_manager() {
+        let cgroup =
+            "0::/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox-1234.scope\n";
+        assert_eq!(
+            parse_unit(cgroup),
+            Some("user@1000/app-firefox-1234.scope".to_string())
+        );
+        let cgroup = "0::/user.slice/user-1000.slice/user@1000.service/init.scope\n";
+        assert_eq!(parse_unit(cgroup), Some("user@1000/init.scope".to_string()));
+        assert_eq!(
+            parse_unit("0::/user.slice/user-1000.slice/session-2.scope\n"),
+            Some("session-2.scope".to_string())
+        );
+    }
+
+    #[test]
+    fn should_not_find_unit_outside_systemd() {
+        assert_eq!(parse_unit("0::/\n"), None);
+        assert_eq!(
+            parse_unit("9:name=systemd:/\n4:memory:/app.service\n0::/\n"),
+            None
+        );
+        assert_eq!(parse_unit("0::/user.slice\n"), None);
+        assert_eq!(parse_unit(""), None);
+    }
+}
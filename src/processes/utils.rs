@@ -1,10 +1,10 @@
+use std::str::FromStr;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::{anyhow, Context, Result};
-use chrono::{DateTime, Utc};
-use sysinfo::{System, Uid};
+use sysinfo::{ProcessStatus, System, Uid};
 
-use super::ProcessInfo;
+use super::{Owner, ProcessInfo};
 
 // NOTE: Some processes have path to binary as first argument, but also some processes has different name than cmd (for exmaple firefox)
 pub(super) fn get_process_args(prc: &impl ProcessInfo) -> Vec<&str> {
@@ -20,19 +20,129 @@ pub(super) fn get_process_args(prc: &impl ProcessInfo) -> Vec<&str> {
     args
 }
 
-pub(super) fn process_run_time(run_duration_since_epoch: u64, now: SystemTime) -> String {
+//NOTE: kernel threads have no arguments, path or name is used instead
+pub(super) fn process_cmd_line(prc: &impl ProcessInfo) -> String {
+    let args = prc.args();
+    match args.is_empty() {
+        true => prc.cmd_path().unwrap_or(prc.cmd()).to_string(),
+        false => args.join(" "),
+    }
+}
+
+/// Time elapsed since process start, start time is given in seconds since epoch
+pub(super) fn process_run_duration(start_time: u64, now: SystemTime) -> Duration {
     let now_since_epoch = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
-    let seconds_diff = now_since_epoch.saturating_sub(run_duration_since_epoch);
-    let hours = seconds_diff / 3600;
-    let minutes = (seconds_diff % 3600) / 60;
-    let seconds = seconds_diff % 60;
-    format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+    Duration::from_secs(now_since_epoch.saturating_sub(start_time))
+}
+
+const DURATION_UNITS: [(&str, u64); 4] = [("d", 86400), ("h", 3600), ("m", 60), ("s", 1)];
+
+/// Two most significant units of the duration, i.e. '1h 23m' or '45s'
+pub(super) fn format_run_time(run_duration: Duration) -> String {
+    let mut seconds = run_duration.as_secs();
+    let largest = DURATION_UNITS
+        .iter()
+        .position(|(_, unit)| seconds >= *unit)
+        .unwrap_or(DURATION_UNITS.len() - 1);
+    DURATION_UNITS[largest..]
+        .iter()
+        .take(2)
+        .map(|(name, unit)| {
+            let value = seconds / unit;
+            seconds %= unit;
+            format!("{}{}", value, name)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Hours are not limited, i.e. '0:00:05' or '123:04:05'
+pub(super) fn format_cpu_time(cpu_time: Duration) -> String {
+    let seconds = cpu_time.as_secs();
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
 }
 
-pub(super) fn process_start_time(seconds_since_epoch: u64) -> String {
-    let system_time = UNIX_EPOCH + Duration::from_secs(seconds_since_epoch);
-    let datetime: DateTime<Utc> = system_time.into();
-    datetime.format("%H:%M:%S").to_string()
+const BYTE_UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+/// Formats bytes in the largest binary unit that keeps value at least 1, i.e. '1.5 MiB'.
+/// Bytes are shown without decimal part, GiB is the largest unit
+pub(super) fn format_bytes(bytes: u64) -> String {
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    //NOTE: value rounded to 1024.0 is shown in next unit, i.e. 1.0 MiB instead of 1024.0 KiB
+    while unit + 1 < BYTE_UNITS.len() && (value * 10.0).round() >= 1024.0 * 10.0 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} {}", bytes, BYTE_UNITS[0]),
+        _ => format!("{:.1} {}", value, BYTE_UNITS[unit]),
+    }
+}
+
+/// Process state codes as shown by ps
+pub(super) const PROCESS_STATES: [char; 7] = ['R', 'S', 'D', 'Z', 'T', 'I', 'X'];
+
+pub(super) const UNKNOWN_STATE: char = '?';
+
+pub(super) fn process_state(status: ProcessStatus) -> char {
+    match status {
+        ProcessStatus::Run => 'R',
+        ProcessStatus::Sleep => 'S',
+        ProcessStatus::UninterruptibleDiskSleep => 'D',
+        ProcessStatus::Zombie => 'Z',
+        ProcessStatus::Stop | ProcessStatus::Tracing => 'T',
+        ProcessStatus::Idle => 'I',
+        ProcessStatus::Dead => 'X',
+        _ => UNKNOWN_STATE,
+    }
+}
+
+/// Suffix linux adds to /proc/<pid>/exe link when binary was deleted or replaced, i.e. by upgrade
+pub(super) const DELETED_EXE_SUFFIX: &str = " (deleted)";
+
+#[cfg(target_os = "linux")]
+pub(super) fn process_exe_deleted(pid: u32) -> bool {
+    std::fs::read_link(format!("/proc/{}/exe", pid))
+        .is_ok_and(|exe| exe.to_string_lossy().ends_with(DELETED_EXE_SUFFIX))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(super) fn process_exe_deleted(_pid: u32) -> bool {
+    false
+}
+
+/// Scheduling priority of the process from -20 (highest) to 19, None when it cannot be read
+#[cfg(unix)]
+pub(super) fn process_nice(pid: u32) -> Option<i32> {
+    let errno = errno_location()?;
+    //NOTE: -1 is a valid nice value, errno must be cleared to tell it from failure
+    unsafe { *errno = 0 };
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, pid as libc::id_t) };
+    match nice == -1 && unsafe { *errno } != 0 {
+        true => None,
+        false => Some(nice),
+    }
+}
+
+#[cfg(not(unix))]
+pub(super) fn process_nice(_pid: u32) -> Option<i32> {
+    None
+}
+
+#[cfg(unix)]
+fn errno_location() -> Option<*mut libc::c_int> {
+    #[cfg(target_os = "linux")]
+    return Some(unsafe { libc::__errno_location() });
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+    return Some(unsafe { libc::__error() });
+    #[allow(unreachable_code)]
+    None
 }
 
 pub(super) fn find_current_process_user(sys: &System) -> Result<Uid> {
@@ -43,10 +153,21 @@ pub(super) fn find_current_process_user(sys: &System) -> Result<Uid> {
         .context("Current process not found!")
 }
 
+/// Root takes precedence, so processes of pik running as root are colored as root ones
+pub(super) fn process_owner(user_id: Option<&Uid>, current_user_id: &Uid) -> Owner {
+    //NOTE: there is no root user on windows, its user ids are not numbers
+    let root = Uid::from_str("0").ok();
+    match user_id {
+        Some(id) if root.as_ref() == Some(id) => Owner::Root,
+        Some(id) if id == current_user_id => Owner::CurrentUser,
+        _ => Owner::Other,
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
 
-    use std::{ops::Mul, str::FromStr, time::Duration};
+    use std::{ops::Mul, time::Duration};
 
     use super::*;
 
@@ -54,13 +175,24 @@ pub mod tests {
         pub pid: u32,
         pub parent_pid: Option<u32>,
         pub user_id: Uid,
+        pub effective_user_id: Option<Uid>,
         pub is_thread: bool,
         pub cmd: String,
         pub cmd_path: Option<String>,
         pub args: Vec<String>,
         pub memory: u64,
+        pub cpu_usage: f32,
         pub start_time: u64,
         pub run_time: u64,
+        pub state: char,
+        pub threads: Option<usize>,
+        pub nice: Option<i32>,
+        pub exe_deleted: bool,
+        pub container_id: Option<String>,
+        pub unit: Option<String>,
+        pub tty: Option<String>,
+        pub cwd: Option<String>,
+        pub cpu_time: Option<Duration>,
     }
 
     impl ProcessInfo for MockProcessInfo {
@@ -72,6 +204,10 @@ pub mod tests {
             Some(&self.user_id)
         }
 
+        fn effective_user_id(&self) -> Option<&Uid> {
+            self.effective_user_id.as_ref().or(Some(&self.user_id))
+        }
+
         fn cmd(&self) -> &str {
             &self.cmd
         }
@@ -92,6 +228,10 @@ pub mod tests {
             self.memory
         }
 
+        fn cpu_usage(&self) -> f32 {
+            self.cpu_usage
+        }
+
         fn start_time(&self) -> u64 {
             self.start_time
         }
@@ -103,6 +243,42 @@ pub mod tests {
         fn args(&self) -> Vec<&str> {
             self.args.iter().map(|a| a.as_str()).collect()
         }
+
+        fn state(&self) -> char {
+            self.state
+        }
+
+        fn threads(&self) -> Option<usize> {
+            self.threads
+        }
+
+        fn nice(&self) -> Option<i32> {
+            self.nice
+        }
+
+        fn exe_deleted(&self) -> bool {
+            self.exe_deleted
+        }
+
+        fn container_id(&self) -> Option<&str> {
+            self.container_id.as_deref()
+        }
+
+        fn unit(&self) -> Option<&str> {
+            self.unit.as_deref()
+        }
+
+        fn tty(&self) -> Option<&str> {
+            self.tty.as_deref()
+        }
+
+        fn cwd(&self) -> Option<&str> {
+            self.cwd.as_deref()
+        }
+
+        fn cpu_time(&self) -> Option<Duration> {
+            self.cpu_time
+        }
     }
 
     impl Default for MockProcessInfo {
@@ -111,13 +287,24 @@ pub mod tests {
                 pid: 1,
                 parent_pid: None,
                 user_id: Uid::from_str("1").unwrap(),
+                effective_user_id: None,
                 is_thread: false,
                 cmd: "xxx".to_string(),
                 cmd_path: Some("xxx".to_string()),
                 args: vec!["xxx".to_string(), "xxx2".to_string()],
                 memory: 0,
+                cpu_usage: 0.0,
                 start_time: 0,
                 run_time: 0,
+                state: 'S',
+                threads: Some(1),
+                nice: Some(0),
+                exe_deleted: false,
+                container_id: None,
+                unit: None,
+                tty: None,
+                cwd: None,
+                cpu_time: None,
             }
         }
     }
@@ -129,6 +316,29 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn should_map_process_status_to_state_code() {
+        assert_eq!(process_state(ProcessStatus::Zombie), 'Z');
+        assert_eq!(process_state(ProcessStatus::Stop), 'T');
+        assert_eq!(process_state(ProcessStatus::Tracing), 'T');
+        assert_eq!(process_state(ProcessStatus::Unknown(42)), UNKNOWN_STATE);
+        assert!(PROCESS_STATES.contains(&process_state(ProcessStatus::Run)));
+    }
+
+    #[test]
+    fn should_create_process_cmd_line() {
+        let prc = MockProcessInfo::default().with_args(&["/bin/java", "-jar", "app.jar"]);
+        assert_eq!(process_cmd_line(&prc), "/bin/java -jar app.jar");
+
+        let prc = MockProcessInfo {
+            cmd: "kthreadd".to_string(),
+            cmd_path: None,
+            ..Default::default()
+        }
+        .with_args(&[]);
+        assert_eq!(process_cmd_line(&prc), "kthreadd");
+    }
+
     #[test]
     fn test_get_process_args() {
         let mut prc = MockProcessInfo {
@@ -147,29 +357,62 @@ pub mod tests {
         assert_eq!(get_process_args(&prc), ["--a1", "-a2"]);
     }
 
+    #[test]
+    fn should_format_cpu_time_as_hours_minutes_seconds() {
+        assert_eq!(format_cpu_time(Duration::ZERO), "0:00:00");
+        assert_eq!(format_cpu_time(Duration::from_millis(5900)), "0:00:05");
+        assert_eq!(format_cpu_time(as_duration(1, 2, 3)), "1:02:03");
+        assert_eq!(format_cpu_time(as_duration(123, 4, 5)), "123:04:05");
+    }
+
     #[test]
     fn test_process_run_time() {
         let run_time = |hours: u64, minutes: u64, seconds: u64| {
             let duration = as_duration(hours, minutes, seconds);
-            process_run_time(duration.as_secs(), UNIX_EPOCH + duration.mul(2))
+            format_run_time(process_run_duration(
+                duration.as_secs(),
+                UNIX_EPOCH + duration.mul(2),
+            ))
         };
-        assert_eq!(run_time(0, 0, 0), "00:00:00");
-        assert_eq!(run_time(0, 30, 5), "00:30:05");
-        assert_eq!(run_time(2, 45, 15), "02:45:15");
+        assert_eq!(run_time(0, 0, 0), "0s");
+        assert_eq!(run_time(0, 0, 45), "45s");
+        assert_eq!(run_time(0, 30, 5), "30m 5s");
+        assert_eq!(run_time(1, 23, 59), "1h 23m");
+        assert_eq!(run_time(2, 0, 15), "2h 0m");
+        assert_eq!(run_time(51, 45, 15), "2d 3h");
     }
 
     #[test]
-    fn test_process_start_time() {
-        let start_time = |hours: u64, minutes: u64, seconds: u64| {
-            let seconds_since_epoch = as_duration(hours, minutes, seconds).as_secs();
-            process_start_time(seconds_since_epoch)
-        };
-        assert_eq!(start_time(0, 0, 0), "00:00:00");
-        assert_eq!(start_time(1, 45, 15), "01:45:15");
-        assert_eq!(start_time(5, 29, 59), "05:29:59");
+    fn should_format_bytes_around_unit_boundaries() {
+        const KIB: u64 = 1024;
+        const MIB: u64 = 1024 * KIB;
+        const GIB: u64 = 1024 * MIB;
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(KIB), "1.0 KiB");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(MIB - 1), "1.0 MiB");
+        assert_eq!(format_bytes(MIB), "1.0 MiB");
+        assert_eq!(format_bytes(300 * MIB), "300.0 MiB");
+        assert_eq!(format_bytes(GIB - MIB), "1023.0 MiB");
+        assert_eq!(format_bytes(GIB), "1.0 GiB");
+        assert_eq!(format_bytes(5 * GIB + GIB / 2), "5.5 GiB");
+        assert_eq!(format_bytes(2048 * GIB), "2048.0 GiB");
     }
 
     fn as_duration(hours: u64, minutes: u64, seconds: u64) -> Duration {
         Duration::from_secs(hours * 3600 + minutes * 60 + seconds)
     }
+
+    #[test]
+    fn should_tell_owner_of_process() {
+        let root = Uid::from_str("0").unwrap();
+        let user = Uid::from_str("1000").unwrap();
+        let other = Uid::from_str("1001").unwrap();
+        assert_eq!(process_owner(Some(&root), &user), Owner::Root);
+        assert_eq!(process_owner(Some(&root), &root), Owner::Root);
+        assert_eq!(process_owner(Some(&user), &user), Owner::CurrentUser);
+        assert_eq!(process_owner(Some(&other), &user), Owner::Other);
+        assert_eq!(process_owner(None, &user), Owner::Other);
+    }
 }
@@ -1,27 +1,159 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
 use ratatui::Viewport;
 
 use crate::{
-    args::{CliArgs, ScreenSizeOptions},
-    config::{AppConfig, ScreenSize},
-    processes::FilterOptions,
+    args::{CliArgs, ScreenSizeOptions, SortArg},
+    config::{
+        AppConfig, ColumnsConfig, EnterAction, KeyConfig, ScreenSize, ThemeConfig, ThemePreset,
+    },
+    export::{Export, DEFAULT_EXPORT_PATH},
+    processes::{FilterOptions, KillSignal, Sort, SortColumn, SortOrder},
 };
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct AppSettings {
     pub viewport: Viewport,
     pub filter_opions: FilterOptions,
+    pub print_mode: Option<PrintMode>,
+    /// Print when pik prints selected process, otherwise as configured
+    pub enter_action: EnterAction,
+    /// Process is printed without showing ui when it is the only one matching --filter
+    pub print_single_match: bool,
+    pub bookmarks: BTreeMap<String, String>,
+    pub keys: KeyConfig,
+    pub theme: ThemeConfig,
+    /// Searching is delayed until user stops typing for this long
+    pub search_debounce: Duration,
+    pub columns: ColumnsConfig,
+    /// Open files listed for a process, the rest is only counted
+    pub open_files_limit: usize,
+    pub wrap_selection: bool,
+    /// Ascii symbols are drawn instead of box drawing ones
+    pub ascii: bool,
+    pub hide_details: bool,
+    /// Time killed processes are waited for, None when it is not checked whether they exited
+    pub verify_kill: Option<Duration>,
+    /// Signal sent by kill action, None lets user pick it every time
+    pub signal: Option<KillSignal>,
+    /// Only processes of this user are listed, 'me' is the current user
+    pub user: Option<String>,
+    /// Initial sorting of the table, None keeps processes ordered by match score
+    pub sort: Option<Sort>,
+    pub export: Export,
+}
+
+/// What should be printed to stdout when process is selected with ENTER
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrintMode {
+    Pid,
+    PidAndCmd,
 }
 
 impl AppSettings {
     pub fn from(config: AppConfig, cli_args: &CliArgs) -> Self {
+        let ascii = cli_args.ascii || config.ascii;
         Self {
             viewport: prefer_override(config.screen_size, cli_args.screen_size),
             filter_opions: FilterOptions {
                 ignore_threads: !cli_args.include_threads_processes,
                 include_all_processes: cli_args.include_other_users_processes,
+                fuzzy_search: !cli_args.exact,
+                hide_self: config.hide_self,
+                ..Default::default()
+            },
+            //NOTE: printing with Enter needs ui on stderr like --print
+            print_mode: print_mode(cli_args)
+                .or((config.enter_action == EnterAction::Print).then_some(PrintMode::Pid)),
+            enter_action: match print_mode(cli_args) {
+                Some(_) => EnterAction::Print,
+                None => config.enter_action,
             },
+            print_single_match: cli_args.filter.is_some() && print_mode(cli_args).is_some(),
+            bookmarks: config.bookmarks,
+            keys: config.keys,
+            theme: theme(config.theme, ascii),
+            search_debounce: Duration::from_millis(config.search_debounce_ms),
+            columns: ColumnsConfig {
+                visible: cli_args.columns.clone().or(config.columns.visible),
+                ..config.columns
+            },
+            open_files_limit: config.open_files_limit,
+            wrap_selection: config.wrap_selection,
+            ascii,
+            hide_details: config.hide_details,
+            verify_kill: (cli_args.verify_kill || config.verify_kill)
+                .then(|| Duration::from_millis(config.verify_kill_timeout_ms)),
+            signal: cli_args.signal,
+            user: cli_args.user.clone(),
+            sort: initial_sort(cli_args),
+            export: Export::new(
+                cli_args
+                    .export
+                    .clone()
+                    .unwrap_or(PathBuf::from(DEFAULT_EXPORT_PATH)),
+                cli_args.export_format,
+            ),
         }
     }
+
+    /// Query from cli args, prefixed with bookmarked query when bookmark is given
+    pub fn initial_query(&self, cli_args: &CliArgs) -> Result<String> {
+        let query = cli_args.filter.as_ref().unwrap_or(&cli_args.query);
+        let Some(name) = cli_args.bookmark.as_deref() else {
+            return Ok(query.clone());
+        };
+        let bookmark = self.bookmarks.get(name).ok_or_else(|| {
+            let names: Vec<&str> = self.bookmarks.keys().map(|k| k.as_str()).collect();
+            match names.is_empty() {
+                true => anyhow!("Bookmark '{}' not found, no bookmarks are defined", name),
+                false => anyhow!(
+                    "Bookmark '{}' not found, available bookmarks: {}",
+                    name,
+                    names.join(", ")
+                ),
+            }
+        })?;
+        Ok(format!("{} {}", bookmark, query).trim().to_string())
+    }
+}
+
+//NOTE: terminals without unicode support often lack 24-bit colors too
+fn theme(theme: ThemeConfig, ascii: bool) -> ThemeConfig {
+    match ascii {
+        true => ThemeConfig {
+            preset: ThemePreset::Basic,
+            ..theme
+        },
+        false => theme,
+    }
+}
+
+fn print_mode(cli_args: &CliArgs) -> Option<PrintMode> {
+    match (cli_args.print, cli_args.print_cmd) {
+        (_, true) => Some(PrintMode::PidAndCmd),
+        (true, false) => Some(PrintMode::Pid),
+        (false, false) => None,
+    }
+}
+
+fn initial_sort(cli_args: &CliArgs) -> Option<Sort> {
+    let column = match cli_args.sort? {
+        SortArg::Pid => SortColumn::Pid,
+        SortArg::Cpu => SortColumn::Cpu,
+        SortArg::Mem => SortColumn::Memory,
+        SortArg::Time => SortColumn::RunTime,
+        SortArg::User => SortColumn::User,
+        SortArg::Cmd => SortColumn::Cmd,
+    };
+    let order = match cli_args.reverse {
+        true => SortOrder::Descending,
+        false => SortOrder::Ascending,
+    };
+    Some(Sort { column, order })
 }
 
 fn prefer_override<V, C, A>(config_value: C, override_opt: Option<A>) -> V
@@ -56,7 +188,10 @@ impl From<ScreenSizeOptions> for Viewport {
 #[cfg(test)]
 mod tests {
 
+    use clap::Parser;
+
     use super::*;
+    use crate::config::Column;
 
     #[test]
     fn should_convert_screen_size_to_viewport() {
@@ -87,8 +222,22 @@ mod tests {
         let config = AppConfig::default();
         let cli_args = CliArgs {
             query: "".to_string(),
+            filter: None,
             include_threads_processes: true,
             include_other_users_processes: true,
+            exact: false,
+            print: false,
+            print_cmd: false,
+            bookmark: None,
+            user: None,
+            sort: None,
+            reverse: false,
+            columns: None,
+            export: None,
+            export_format: None,
+            ascii: false,
+            verify_kill: false,
+            signal: None,
             screen_size: None,
         };
         let settings = AppSettings::from(config, &cli_args);
@@ -98,16 +247,142 @@ mod tests {
                 viewport: Viewport::Inline(25),
                 filter_opions: FilterOptions {
                     ignore_threads: false,
-                    include_all_processes: true
-                }
+                    include_all_processes: true,
+                    fuzzy_search: true,
+                    hide_self: true,
+                    ..Default::default()
+                },
+                print_mode: None,
+                enter_action: EnterAction::Details,
+                print_single_match: false,
+                bookmarks: BTreeMap::new(),
+                keys: KeyConfig::default(),
+                theme: ThemeConfig::default(),
+                search_debounce: Duration::from_millis(100),
+                columns: ColumnsConfig::default(),
+                open_files_limit: 500,
+                wrap_selection: true,
+                ascii: false,
+                hide_details: false,
+                verify_kill: None,
+                signal: None,
+                user: None,
+                sort: None,
+                export: Export::default(),
             }
         );
     }
 
+    #[test]
+    fn should_disable_fuzzy_search_with_exact_flag() {
+        let cli_args = CliArgs {
+            exact: true,
+            ..some_cli_args()
+        };
+        let settings = AppSettings::from(AppConfig::default(), &cli_args);
+        assert!(!settings.filter_opions.fuzzy_search);
+    }
+
+    #[test]
+    fn should_set_print_mode() {
+        let print_mode = |print, print_cmd| {
+            let cli_args = CliArgs {
+                print,
+                print_cmd,
+                ..some_cli_args()
+            };
+            AppSettings::from(AppConfig::default(), &cli_args).print_mode
+        };
+        assert_eq!(print_mode(false, false), None);
+        assert_eq!(print_mode(true, false), Some(PrintMode::Pid));
+        assert_eq!(print_mode(false, true), Some(PrintMode::PidAndCmd));
+        assert_eq!(print_mode(true, true), Some(PrintMode::PidAndCmd));
+    }
+
+    #[test]
+    fn should_print_with_enter_when_printing_or_configured() {
+        let settings = AppSettings::from(AppConfig::default(), &some_cli_args());
+        assert_eq!(settings.enter_action, EnterAction::Details);
+        let config = AppConfig {
+            enter_action: EnterAction::Kill,
+            ..Default::default()
+        };
+        let cli_args = CliArgs::try_parse_from(["pik", "--print-cmd"]).unwrap();
+        let settings = AppSettings::from(config, &cli_args);
+        assert_eq!(settings.enter_action, EnterAction::Print);
+        assert_eq!(settings.print_mode, Some(PrintMode::PidAndCmd));
+
+        let config = AppConfig {
+            enter_action: EnterAction::Print,
+            ..Default::default()
+        };
+        let settings = AppSettings::from(config, &some_cli_args());
+        assert_eq!(settings.enter_action, EnterAction::Print);
+        assert_eq!(settings.print_mode, Some(PrintMode::Pid));
+        assert!(!settings.print_single_match);
+    }
+
+    #[test]
+    fn should_use_bookmarked_query() {
+        let config = AppConfig {
+            bookmarks: BTreeMap::from([("web".to_string(), ":80,443".to_string())]),
+            ..Default::default()
+        };
+        let settings = AppSettings::from(config, &some_cli_args());
+        let initial_query = |bookmark: Option<&str>, query: &str| {
+            let cli_args = CliArgs {
+                bookmark: bookmark.map(|b| b.to_string()),
+                query: query.to_string(),
+                ..some_cli_args()
+            };
+            settings.initial_query(&cli_args).map_err(|e| e.to_string())
+        };
+        assert_eq!(initial_query(None, "java"), Ok("java".to_string()));
+        assert_eq!(initial_query(Some("web"), ""), Ok(":80,443".to_string()));
+        assert_eq!(
+            initial_query(Some("web"), "nginx"),
+            Ok(":80,443 nginx".to_string())
+        );
+        assert_eq!(
+            initial_query(Some("db"), ""),
+            Err("Bookmark 'db' not found, available bookmarks: web".to_string())
+        );
+    }
+
+    #[test]
+    fn should_start_with_filter_query() {
+        let cli_args = CliArgs::try_parse_from(["pik", "--filter", ":8080 !java"]).unwrap();
+        let settings = AppSettings::from(AppConfig::default(), &cli_args);
+        assert_eq!(settings.initial_query(&cli_args).unwrap(), ":8080 !java");
+        assert!(!settings.print_single_match);
+
+        let cli_args = CliArgs::try_parse_from(["pik", "-f", "nginx", "--print"]).unwrap();
+        let settings = AppSettings::from(AppConfig::default(), &cli_args);
+        assert!(settings.print_single_match);
+        let cli_args = CliArgs::try_parse_from(["pik", "nginx", "--print"]).unwrap();
+        assert!(!AppSettings::from(AppConfig::default(), &cli_args).print_single_match);
+
+        assert!(CliArgs::try_parse_from(["pik", "nginx", "--filter", "java"]).is_err());
+    }
+
+    #[test]
+    fn should_fail_on_bookmark_when_none_defined() {
+        let cli_args = CliArgs {
+            bookmark: Some("web".to_string()),
+            ..some_cli_args()
+        };
+        let settings = AppSettings::from(AppConfig::default(), &cli_args);
+        assert_eq!(
+            settings.initial_query(&cli_args).unwrap_err().to_string(),
+            "Bookmark 'web' not found, no bookmarks are defined"
+        );
+    }
+
     #[test]
     fn should_prefer_cli_args_screen_size() {
         let config = AppConfig {
             screen_size: ScreenSize::Height(40),
+            ..Default::default()
         };
         let cli_args = CliArgs {
             screen_size: Some(ScreenSizeOptions {
@@ -120,11 +395,151 @@ mod tests {
         assert_eq!(settings.viewport, Viewport::Fullscreen);
     }
 
+    #[test]
+    fn should_set_initial_sort() {
+        let sort = |sort, reverse| {
+            let cli_args = CliArgs {
+                sort,
+                reverse,
+                ..some_cli_args()
+            };
+            AppSettings::from(AppConfig::default(), &cli_args).sort
+        };
+        assert_eq!(sort(None, false), None);
+        assert_eq!(
+            sort(Some(SortArg::Mem), true),
+            Some(Sort {
+                column: SortColumn::Memory,
+                order: SortOrder::Descending
+            })
+        );
+        assert_eq!(
+            sort(Some(SortArg::Time), false),
+            Some(Sort {
+                column: SortColumn::RunTime,
+                order: SortOrder::Ascending
+            })
+        );
+    }
+
+    #[test]
+    fn should_reject_unknown_sort_column() {
+        let error = CliArgs::try_parse_from(["pik", "--sort", "size"]).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("[possible values: pid, cpu, mem, time, user, cmd]"));
+        assert!(CliArgs::try_parse_from(["pik", "--reverse"]).is_err());
+        let args = CliArgs::try_parse_from(["pik", "-s", "cpu", "-r"]).unwrap();
+        assert_eq!((args.sort, args.reverse), (Some(SortArg::Cpu), true));
+    }
+
+    #[test]
+    fn should_prefer_columns_given_as_cli_arg() {
+        let config = AppConfig {
+            columns: ColumnsConfig {
+                visible: Some(vec![Column::Pid, Column::Cmd]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let cli_args = CliArgs::try_parse_from(["pik", "--columns", "user,mem,cmd_path"]).unwrap();
+        let settings = AppSettings::from(config, &cli_args);
+        assert_eq!(
+            settings.columns.visible,
+            Some(vec![Column::User, Column::Mem, Column::CmdPath])
+        );
+        let settings = AppSettings::from(AppConfig::default(), &some_cli_args());
+        assert_eq!(settings.columns.visible, None);
+
+        let error = CliArgs::try_parse_from(["pik", "--columns", "pid,size"]).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("[possible values: user, pid, parent"));
+    }
+
+    #[test]
+    fn should_use_basic_colors_in_ascii_mode() {
+        let config = AppConfig {
+            theme: ThemeConfig {
+                row_fg: Some("white".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let cli_args = CliArgs::try_parse_from(["pik", "--ascii"]).unwrap();
+        let settings = AppSettings::from(config, &cli_args);
+        assert!(settings.ascii);
+        assert_eq!(settings.theme.preset, ThemePreset::Basic);
+        assert_eq!(settings.theme.row_fg, Some("white".to_string()));
+
+        let config = AppConfig {
+            ascii: true,
+            ..Default::default()
+        };
+        assert!(AppSettings::from(config, &some_cli_args()).ascii);
+        let settings = AppSettings::from(AppConfig::default(), &some_cli_args());
+        assert!(!settings.ascii);
+        assert_eq!(settings.theme.preset, ThemePreset::Dark);
+    }
+
+    #[test]
+    fn should_verify_kill_with_configured_timeout() {
+        let config = AppConfig {
+            verify_kill_timeout_ms: 2500,
+            ..Default::default()
+        };
+        let cli_args = CliArgs::try_parse_from(["pik", "--verify-kill"]).unwrap();
+        let settings = AppSettings::from(config, &cli_args);
+        assert_eq!(settings.verify_kill, Some(Duration::from_millis(2500)));
+
+        let config = AppConfig {
+            verify_kill: true,
+            ..Default::default()
+        };
+        let settings = AppSettings::from(config, &some_cli_args());
+        assert_eq!(settings.verify_kill, Some(Duration::from_millis(1000)));
+        let settings = AppSettings::from(AppConfig::default(), &some_cli_args());
+        assert_eq!(settings.verify_kill, None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_take_signal_from_cli_args() {
+        let cli_args = CliArgs::try_parse_from(["pik", "--signal", "9"]).unwrap();
+        let settings = AppSettings::from(AppConfig::default(), &cli_args);
+        assert_eq!(settings.signal.map(|s| s.name), Some("SIGKILL"));
+        let cli_args = CliArgs::try_parse_from(["pik", "--signal", "SIGHUP"]).unwrap();
+        assert_eq!(cli_args.signal.map(|s| s.number), Some(1));
+
+        let error = CliArgs::try_parse_from(["pik", "--signal", "SIGFOO"]).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("valid signals are: SIGTERM (15)"));
+        assert_eq!(
+            AppSettings::from(AppConfig::default(), &some_cli_args()).signal,
+            None
+        );
+    }
+
     fn some_cli_args() -> CliArgs {
         CliArgs {
             query: "".to_string(),
+            filter: None,
             include_threads_processes: true,
             include_other_users_processes: true,
+            exact: false,
+            print: false,
+            print_cmd: false,
+            bookmark: None,
+            user: None,
+            sort: None,
+            reverse: false,
+            columns: None,
+            export: None,
+            export_format: None,
+            ascii: false,
+            verify_kill: false,
+            signal: None,
             screen_size: None,
         }
     }
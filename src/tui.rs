@@ -1,149 +1,1060 @@
-use std::io;
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    cursor::Show,
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers,
+    },
+    execute,
     terminal::{disable_raw_mode, enable_raw_mode},
 };
-use ratatui::{prelude::*, TerminalOptions};
+use ratatui::{prelude::*, TerminalOptions, Viewport};
 
+mod clipboard;
+mod debounce;
+mod kill_check;
+mod popups;
 mod rendering;
+mod start_time;
+mod symbols;
+mod theme;
+mod usage_history;
 
 use crate::{
-    processes::{FilterOptions, ProcessManager, ProcessSearchResults},
-    settings::AppSettings,
+    config::EnterAction,
+    export::Export,
+    history::SearchHistory,
+    processes::{
+        process_environment, process_open_files, FilterOptions, KillSignal, Process,
+        ProcessManager, ProcessSearchResults, SortColumn, FORCE_KILL_SIGNAL,
+    },
+    settings::{AppSettings, PrintMode},
 };
 
-use self::rendering::Tui;
+use self::{
+    debounce::Debouncer,
+    kill_check::{KillCheck, KillCheckResult},
+    popups::PopupEvent,
+    rendering::Tui,
+    theme::Theme,
+};
+#[cfg(unix)]
+use crate::processes::{PAUSE_SIGNAL, RESUME_SIGNAL};
 
 struct App {
     process_manager: ProcessManager,
     search_results: ProcessSearchResults,
     filter_options: FilterOptions,
+    print_mode: Option<PrintMode>,
+    enter_action: EnterAction,
+    /// Delays searching while user types, searching thousands of processes on every key is slow
+    search_debouncer: Debouncer,
+    history: SearchHistory,
+    /// Processes whose descendants are hidden in tree view, kept between searches
+    collapsed: HashSet<u32>,
+    /// Executables whose groups show all their processes, kept between searches
+    expanded_groups: HashSet<String>,
+    /// Killed processes may still be found by refresh that follows, they are removed from its results
+    killed: Vec<u32>,
+    /// Time killed processes are waited for, None when it is not checked whether they exited
+    verify_kill: Option<Duration>,
+    kill_check: Option<KillCheck>,
+    /// Signal given with --signal is sent without asking for it
+    signal: Option<KillSignal>,
+    export: Export,
     tui: Tui,
 }
 
 impl App {
     fn new(search_criteria: String, app_settings: AppSettings) -> Result<App> {
+        let (theme, theme_warnings) = Theme::from_config(&app_settings.theme);
+        let mut process_manager = ProcessManager::new()?;
+        if let Some(user) = app_settings.user.as_deref() {
+            process_manager.filter_by_user(user)?;
+        }
         let mut app = App {
-            process_manager: ProcessManager::new()?,
+            process_manager,
             search_results: ProcessSearchResults::empty(),
             filter_options: app_settings.filter_opions,
-            tui: Tui::new(search_criteria),
+            print_mode: app_settings.print_mode,
+            enter_action: app_settings.enter_action,
+            search_debouncer: Debouncer::new(app_settings.search_debounce),
+            history: SearchHistory::load(),
+            collapsed: HashSet::new(),
+            expanded_groups: HashSet::new(),
+            killed: vec![],
+            verify_kill: app_settings.verify_kill,
+            kill_check: None,
+            signal: app_settings.signal,
+            export: app_settings.export,
+            tui: Tui::new(
+                search_criteria,
+                app_settings.bookmarks,
+                app_settings.keys,
+                app_settings.columns,
+                theme,
+            )
+            .with_sort(app_settings.sort)
+            .with_wrap_selection(app_settings.wrap_selection)
+            .with_ascii(app_settings.ascii)
+            .with_details_hidden(app_settings.hide_details)
+            .with_open_files_limit(app_settings.open_files_limit)
+            .with_enter_action(app_settings.enter_action)
+            .with_fuzzy_search(app_settings.filter_opions.fuzzy_search),
         };
+        //NOTE: processes from the first scan are shown right away, cpu usage is known after refresh
         app.search_for_processess();
+        app.refresh_processes();
+        //NOTE: set after first search, it would reset the message
+        if !theme_warnings.is_empty() {
+            app.tui.set_error_message(theme_warnings.join(", "));
+        }
         Ok(app)
     }
 
     fn enter_char(&mut self, new_char: char) {
         self.tui.enter_char(new_char);
-        self.search_for_processess();
+        self.search_after_typing();
+    }
+
+    fn handle_input(&mut self, key: KeyEvent) {
+        let text = self.tui.search_input_text().to_string();
+        self.tui.handle_input(key);
+        if text != self.tui.search_input_text() {
+            self.search_after_typing();
+        }
+    }
+
+    /// Search runs once user stops typing, previous results are shown meanwhile.
+    /// Without delay it runs right away
+    fn search_after_typing(&mut self) {
+        let now = Instant::now();
+        self.search_debouncer.request(now);
+        if self.search_debouncer.fire(now) {
+            self.search_for_processess();
+        }
     }
 
+    fn search_if_typing_stopped(&mut self) {
+        if self.search_debouncer.fire(Instant::now()) {
+            self.search_for_processess();
+        }
+    }
+
+    /// Filters processes scanned by the last refresh, it is cheap enough to run while user types
     fn search_for_processess(&mut self) {
+        self.search_debouncer.cancel();
+        //NOTE: processes are searched again once refresh is done, with query typed meanwhile
+        if self.process_manager.is_refreshing() {
+            return;
+        }
         self.tui.reset_error_message();
-        self.process_manager.refresh();
-        self.search_results = self
-            .process_manager
-            .find_processes(self.tui.search_input_text(), self.filter_options);
+        //NOTE: marks are kept by pid through refresh and sorting, only processes that are gone lose them
+        let process_manager = &self.process_manager;
+        self.tui.retain_marks(|pid| process_manager.is_running(pid));
+        let query = self.tui.search_input_text().to_string();
+        let options = FilterOptions {
+            regex_search: self.tui.regex_search(),
+            fuzzy_search: self.tui.fuzzy_search(),
+            case_sensitivity: self.tui.case_sensitivity(),
+            search_mode: self.tui.search_mode(),
+            tree_view: self.tui.tree_view(),
+            ..self.filter_options
+        };
+        let results = match self.process_manager.find_processes(&query, options) {
+            //NOTE: invalid regex falls back to literal search, so user still sees some results
+            Err(err) if options.regex_search => {
+                self.tui
+                    .set_error_message(format!("{}, searching literally", err));
+                self.process_manager
+                    .find_processes(&regex::escape(&query), options)
+            }
+            results => results,
+        };
+        match results {
+            Ok(search_results) => {
+                self.search_results = search_results;
+                self.search_results.collapse(&self.collapsed);
+                if self.tui.grouped() {
+                    self.search_results.group_by_exe(&self.expanded_groups);
+                }
+                if let Some(sort) = self.tui.sort() {
+                    self.search_results.sort(sort);
+                }
+                self.tui
+                    .update_process_table_number_of_items(self.search_results.len());
+                self.update_details_popup();
+            }
+            //NOTE: previous results are kept so table does not blink while typing
+            Err(err) => self.tui.set_error_message(err.to_string()),
+        }
+    }
+
+    /// Scans processes again in background, ui stays responsive while it runs
+    fn refresh_processes(&mut self) {
+        self.process_manager.start_refresh();
+        self.tui.set_refreshing(true);
+    }
+
+    fn search_if_refresh_finished(&mut self) {
+        if self.process_manager.finish_refresh() {
+            self.search_refreshed_processes();
+        }
+    }
+
+    /// Some actions need current processes, i.e. killing whole subtree
+    fn wait_for_refresh(&mut self) {
+        if self.process_manager.is_refreshing() {
+            self.process_manager.wait_for_refresh();
+            self.search_refreshed_processes();
+        }
+    }
+
+    /// Searches with the same query, selection stays on the same process
+    fn search_refreshed_processes(&mut self) {
+        self.tui.set_refreshing(false);
+        let selected_index = self.tui.get_selected_row_index();
+        let selected_pid = self.search_results.nth(selected_index).map(|prc| prc.pid);
+        let details_tab = self.tui.details_tab();
+        self.search_for_processess();
+        for pid in std::mem::take(&mut self.killed) {
+            self.search_results.remove(pid);
+        }
         self.tui
             .update_process_table_number_of_items(self.search_results.len());
+        if let (Some(pid), Some(index)) = (selected_pid, selected_index) {
+            let index = self.search_results.selection_after_refresh(pid, index);
+            self.tui.select_row_by_index(index);
+            if self.search_results.position(pid).is_some() {
+                self.tui.set_details_tab(details_tab);
+            }
+        }
+        if let Some(prc) = self.search_results.nth(self.tui.get_selected_row_index()) {
+            self.tui.sample_usage(prc);
+        }
+    }
+
+    fn toggle_regex_search(&mut self) {
+        self.tui.toggle_regex_search();
+        self.search_for_processess();
+    }
+
+    fn toggle_fuzzy_search(&mut self) {
+        self.tui.toggle_fuzzy_search();
+        self.search_for_processess();
+    }
+
+    fn cycle_search_mode(&mut self) {
+        self.tui.cycle_search_mode();
+        self.search_for_processess();
+    }
+
+    fn cycle_case_sensitivity(&mut self) {
+        self.tui.cycle_case_sensitivity();
+        self.search_for_processess();
+    }
+
+    fn toggle_hide_self(&mut self) {
+        self.filter_options.hide_self = !self.filter_options.hide_self;
+        self.search_for_processess();
+    }
+
+    fn toggle_tree_view(&mut self) {
+        self.tui.toggle_tree_view();
+        self.search_for_processess();
+    }
+
+    /// Hides descendants of selected process, on process without children its parent is collapsed
+    fn collapse_selected_subtree(&mut self) {
+        let Some(prc) = self.search_results.nth(self.tui.get_selected_row_index()) else {
+            return;
+        };
+        let pid = match (self.search_results.has_children(prc.pid), prc.parent_pid) {
+            (true, _) => prc.pid,
+            (false, Some(parent)) if self.search_results.position(parent).is_some() => parent,
+            _ => return,
+        };
+        self.collapsed.insert(pid);
+        self.update_collapsed(pid);
+    }
+
+    fn expand_selected_subtree(&mut self) {
+        let Some(prc) = self.search_results.nth(self.tui.get_selected_row_index()) else {
+            return;
+        };
+        let pid = prc.pid;
+        if self.collapsed.remove(&pid) {
+            self.update_collapsed(pid);
+        }
+    }
+
+    /// Selection moves to the process that was collapsed or expanded
+    fn update_collapsed(&mut self, selected_pid: u32) {
+        self.search_results.collapse(&self.collapsed);
+        self.tui
+            .update_process_table_number_of_items(self.search_results.len());
+        self.tui
+            .select_row_by_index(self.search_results.position(selected_pid));
+    }
+
+    fn toggle_grouping(&mut self) {
+        self.tui.toggle_grouped();
+        self.search_for_processess();
+    }
+
+    fn selected_group(&self) -> Option<&Process> {
+        self.search_results
+            .nth(self.tui.get_selected_row_index())
+            .filter(|prc| prc.group.is_some())
+    }
+
+    fn toggle_selected_group(&mut self) {
+        let Some(exe) = self.selected_group().map(|prc| prc.exe().to_string()) else {
+            return;
+        };
+        if !self.expanded_groups.remove(&exe) {
+            self.expanded_groups.insert(exe.clone());
+        }
+        self.update_groups(&exe);
+    }
+
+    fn expand_selected_group(&mut self) {
+        let Some(exe) = self.selected_group().map(|prc| prc.exe().to_string()) else {
+            return;
+        };
+        if self.expanded_groups.insert(exe.clone()) {
+            self.update_groups(&exe);
+        }
+    }
+
+    /// Works on group row and on any process of expanded group
+    fn collapse_selected_group(&mut self) {
+        let Some(prc) = self.search_results.nth(self.tui.get_selected_row_index()) else {
+            return;
+        };
+        let exe = prc.exe().to_string();
+        if self.expanded_groups.remove(&exe) {
+            self.update_groups(&exe);
+        }
+    }
+
+    /// Selection moves to the row of the group that was collapsed or expanded
+    fn update_groups(&mut self, selected_exe: &str) {
+        self.search_results.group_by_exe(&self.expanded_groups);
+        self.tui
+            .update_process_table_number_of_items(self.search_results.len());
+        let index = self
+            .search_results
+            .iter()
+            .position(|prc| prc.group.is_some() && prc.exe() == selected_exe);
+        if index.is_some() {
+            self.tui.select_row_by_index(index);
+        }
+    }
+
+    fn cycle_sort_column(&mut self) {
+        self.tui.cycle_sort_column();
+        self.apply_sort();
+    }
+
+    fn sort_by(&mut self, column: SortColumn) {
+        self.tui.sort_by(column);
+        self.apply_sort();
+    }
+
+    fn toggle_sort_order(&mut self) {
+        self.tui.toggle_sort_order();
+        self.apply_sort();
+    }
+
+    fn apply_sort(&mut self) {
+        let selected_pid = self
+            .search_results
+            .nth(self.tui.get_selected_row_index())
+            .map(|prc| prc.pid);
+        match self.tui.sort() {
+            Some(sort) => self.search_results.sort(sort),
+            //NOTE: searching again restores order by match score
+            None => self.search_for_processess(),
+        }
+        if let Some(index) = selected_pid.and_then(|pid| self.search_results.position(pid)) {
+            self.tui.select_row_by_index(Some(index));
+        }
+    }
+
+    fn search_bookmark(&mut self, query: &str) {
+        self.tui.set_search_text(query);
+        self.search_for_processess();
     }
 
     fn delete_char(&mut self) {
         self.tui.delete_char();
-        self.search_for_processess();
+        self.search_after_typing();
     }
 
-    fn kill_selected_process(&mut self) {
-        self.tui.reset_error_message();
+    /// Shows older query from history, false when there is none to show
+    fn search_previous_query(&mut self) -> bool {
+        let Some(query) = self.history.older() else {
+            return false;
+        };
+        let query = query.to_string();
+        self.tui.set_search_text(&query);
+        self.search_after_typing();
+        true
+    }
+
+    /// Browsing history starts only from empty search like in shell, without history Up moves selection
+    fn search_previous_query_or_select_previous_row(&mut self) {
+        let browse = self.history.is_browsing()
+            || (self.tui.search_input_text().is_empty() && !self.history.is_empty());
+        if !browse || !self.search_previous_query() {
+            self.tui.select_previous_row(1);
+        }
+    }
+
+    fn search_next_query(&mut self) {
+        let query = self.history.newer().unwrap_or_default().to_string();
+        self.tui.set_search_text(&query);
+        self.search_after_typing();
+    }
+
+    /// Query is remembered when it was used, i.e. to kill or print process, and on quit
+    fn remember_query(&mut self) {
+        let query = self.tui.search_input_text().to_string();
+        self.history.add(&query);
+    }
+
+    fn save_history(&mut self) {
+        self.remember_query();
+        //NOTE: pik should quit even when history cannot be saved
+        let _ = self.history.save();
+    }
+
+    fn toggle_mark_of_selected_process(&mut self) {
         let prc_index = self.tui.get_selected_row_index();
         if let Some(prc) = self.search_results.nth(prc_index) {
-            let pid = prc.pid;
-            if self.process_manager.kill_process(pid) {
-                self.search_for_processess();
-                //NOTE: cache refresh takes time and process may reappear in list!
-                self.search_results.remove(pid);
-                //TODO: this must be here because details will show 1/0 when removed!
-                // seems like this can only be fixed by autorefresh!
-                self.tui
-                    .update_process_table_number_of_items(self.search_results.len());
-            } else {
+            let pids = self.search_results.group_members(prc);
+            self.tui.toggle_marks(&pids);
+        }
+    }
+
+    /// Pinned process stays at the top of results whatever is searched, selection follows it
+    fn toggle_pin_of_selected_process(&mut self) {
+        let Some(pid) = self
+            .search_results
+            .nth(self.tui.get_selected_row_index())
+            .map(|prc| prc.pid)
+        else {
+            return;
+        };
+        let action = match self.process_manager.toggle_pin(pid) {
+            true => "Pinned",
+            false => "Unpinned",
+        };
+        self.search_for_processess();
+        self.tui
+            .select_row_by_index(self.search_results.position(pid));
+        self.tui.set_info_message(format!("{} PID {}", action, pid));
+    }
+
+    /// Parent that does not match the query is added above its child until next search
+    fn select_parent_of_selected_process(&mut self) {
+        let prc_index = self.tui.get_selected_row_index();
+        let Some(prc) = self.search_results.nth(prc_index) else {
+            return;
+        };
+        let Some(parent_pid) = prc.parent_pid else {
+            self.tui
+                .set_error_message(format!("Process {} has no parent", prc.pid));
+            return;
+        };
+        if let Some(index) = self.search_results.position(parent_pid) {
+            self.tui.select_row_by_index(Some(index));
+            return;
+        }
+        //NOTE: last scan may be old, parent is checked right now
+        let parent = match self.process_manager.is_terminated(parent_pid) {
+            true => None,
+            false => self.process_manager.process(parent_pid),
+        };
+        let Some(parent) = parent else {
+            self.tui
+                .set_error_message(format!("Parent process {} no longer exists", parent_pid));
+            return;
+        };
+        let index = prc_index.unwrap_or(0);
+        self.search_results.insert_context(index, parent);
+        self.tui
+            .update_process_table_number_of_items(self.search_results.len());
+        self.tui.select_row_by_index(Some(index));
+    }
+
+    /// Query is replaced with parent filter, previous one can be found in history
+    fn show_children_of_selected_process(&mut self) {
+        let prc_index = self.tui.get_selected_row_index();
+        let Some(pid) = self.search_results.nth(prc_index).map(|prc| prc.pid) else {
+            return;
+        };
+        self.remember_query();
+        self.tui.set_search_text(&format!("^{}", pid));
+        self.search_for_processess();
+    }
+
+    /// Processes that only give context in tree view don't match the query, they are not marked.
+    /// Collapsed groups mark all their processes
+    fn mark_all_processes(&mut self) {
+        let pids: Vec<u32> = self
+            .search_results
+            .iter()
+            .filter(|prc| !prc.context_only)
+            .flat_map(|prc| self.search_results.group_members(prc))
+            .collect();
+        self.tui.mark_all(pids);
+    }
+
+    /// Marked processes take precedence over the selected one
+    fn select_signal_for_processes(&mut self) {
+        let mut pids = self.tui.marked_pids();
+        if pids.is_empty() {
+            if let Some(group) = self.selected_group() {
+                self.select_signal_for_group(
+                    group.cmd.clone(),
+                    self.search_results.group_members(group),
+                );
+                return;
+            }
+            let prc_index = self.tui.get_selected_row_index();
+            pids.extend(self.search_results.nth(prc_index).map(|prc| prc.pid));
+        }
+        if pids.is_empty() {
+            return;
+        }
+        //NOTE: pik can be killed only when it is not hidden, so user is asked first
+        match self.own_processes_warning(&pids) {
+            Some(warning) => self.confirm_kill(pids, format!("{} Continue?", warning)),
+            None => match self.signal {
+                Some(signal) => self.kill_processes(pids, signal),
+                None => self.tui.show_signal_popup(pids),
+            },
+        }
+    }
+
+    /// Signal is picked after confirmation unless it was given with --signal
+    fn confirm_kill(&mut self, pids: Vec<u32>, msg: String) {
+        match self.signal {
+            Some(signal) => self.tui.show_confirm_signal_popup(pids, signal, msg),
+            None => self.tui.show_confirm_kill_popup(pids, msg),
+        }
+    }
+
+    /// User is asked first, as group row can stand for dozens of processes
+    fn select_signal_for_group(&mut self, cmd: String, pids: Vec<u32>) {
+        let mut msg = format!("Kill all {} instances of {}?", pids.len(), cmd);
+        if let Some(warning) = self.own_processes_warning(&pids) {
+            msg = format!("{} {}", msg, warning);
+        }
+        self.confirm_kill(pids, format!("{} Continue?", msg));
+    }
+
+    /// Selected process and its descendants are killed children first, user is always asked first
+    fn select_signal_for_process_subtree(&mut self) {
+        self.wait_for_refresh();
+        let prc_index = self.tui.get_selected_row_index();
+        let Some(prc) = self.search_results.nth(prc_index) else {
+            return;
+        };
+        let pids = self.process_manager.process_subtree(prc.pid);
+        let mut msg = match pids.len().saturating_sub(1) {
+            0 => format!(
+                "Process {} has no descendants, only it will be killed.",
+                prc.pid
+            ),
+            1 => format!("Process {} and 1 descendant will be killed.", prc.pid),
+            n => format!(
+                "Process {} and {} descendants will be killed ({} processes).",
+                prc.pid,
+                n,
+                n + 1
+            ),
+        };
+        if let Some(warning) = self.own_processes_warning(&pids) {
+            msg = format!("{} {}", msg, warning);
+        }
+        self.confirm_kill(pids, format!("{} Continue?", msg));
+    }
+
+    fn own_processes_warning(&self, pids: &[u32]) -> Option<String> {
+        let own_pids: Vec<String> = pids
+            .iter()
+            .filter(|pid| self.process_manager.is_self_or_ancestor(**pid))
+            .map(|pid| pid.to_string())
+            .collect();
+        let own = match own_pids.as_slice() {
+            [] => return None,
+            [pid] => format!("Process {} is", pid),
+            pids => format!("Processes {} are", pids.join(", ")),
+        };
+        Some(format!(
+            "{} pik or the shell/terminal it runs in, killing it will close pik.",
+            own
+        ))
+    }
+
+    fn copy_selected_pid(&mut self) {
+        let Some(prc) = self.search_results.nth(self.tui.get_selected_row_index()) else {
+            return;
+        };
+        let pid = prc.pid;
+        match clipboard::copy_to_clipboard(&pid.to_string()) {
+            Ok(()) => self.tui.set_info_message(format!("Copied PID {}", pid)),
+            Err(err) => self.tui.set_error_message(err.to_string()),
+        }
+    }
+
+    fn copy_selected_cmd_line(&mut self) {
+        let Some(prc) = self.search_results.nth(self.tui.get_selected_row_index()) else {
+            return;
+        };
+        let pid = prc.pid;
+        match clipboard::copy_to_clipboard(&prc.cmd_line) {
+            Ok(()) => self
+                .tui
+                .set_info_message(format!("Copied command of PID {}", pid)),
+            Err(err) => self.tui.set_error_message(err.to_string()),
+        }
+    }
+
+    /// Sends SIGSTOP or SIGCONT to selected process, pik itself and its ancestors are never paused
+    #[cfg(unix)]
+    fn send_job_control_signal(&mut self, signal: KillSignal, action: &str) {
+        let Some(prc) = self.search_results.nth(self.tui.get_selected_row_index()) else {
+            return;
+        };
+        let pid = prc.pid;
+        if prc.is_self_or_ancestor && signal == PAUSE_SIGNAL {
+            self.tui
+                .set_error_message(format!("Pausing PID {} would freeze pik", pid));
+            return;
+        }
+        match self.process_manager.kill_process(pid, signal) {
+            Ok(()) => {
+                self.refresh_processes();
                 self.tui
-                    .set_error_message("Failed to kill process, check permissions");
+                    .set_info_message(format!("{} PID {} ({})", action, pid, signal.name));
+            }
+            Err(err) => self.tui.set_error_message(err.to_string()),
+        }
+    }
+
+    fn show_environment_of_selected_process(&mut self) {
+        let Some(prc) = self.search_results.nth(self.tui.get_selected_row_index()) else {
+            return;
+        };
+        let pid = prc.pid;
+        self.tui
+            .show_environment_popup(pid, process_environment(pid));
+    }
+
+    /// Exports processes as shown, i.e. filtered, sorted and without collapsed subtrees
+    fn export_processes(&mut self) {
+        match self.export.write(self.search_results.iter()) {
+            Ok(path) => self.tui.set_info_message(format!(
+                "Exported {} processes to {}",
+                self.search_results.len(),
+                path.display()
+            )),
+            Err(err) => self.tui.set_error_message(err.to_string()),
+        }
+    }
+
+    fn show_details_of_selected_process(&mut self) {
+        if let Some(prc) = self.search_results.nth(self.tui.get_selected_row_index()) {
+            let args = self.process_manager.process_args(prc.pid);
+            self.tui.show_details_popup(prc, args);
+        }
+    }
+
+    /// Open details popup follows refresh, it keeps the last content when the process is not found anymore
+    fn update_details_popup(&mut self) {
+        let Some(pid) = self.tui.details_popup_pid() else {
+            return;
+        };
+        if let Some(index) = self.search_results.position(pid) {
+            let args = self.process_manager.process_args(pid);
+            let prc = self
+                .search_results
+                .nth(Some(index))
+                .expect("process was found");
+            self.tui.update_details_popup(prc, args);
+        }
+    }
+
+    fn copy_text(&mut self, text: &str) {
+        match clipboard::copy_to_clipboard(text) {
+            Ok(()) => self.tui.set_info_message("Copied to clipboard"),
+            Err(err) => self.tui.set_error_message(err.to_string()),
+        }
+    }
+
+    fn show_open_files_of_selected_process(&mut self) {
+        let Some(prc) = self.search_results.nth(self.tui.get_selected_row_index()) else {
+            return;
+        };
+        let pid = prc.pid;
+        self.tui.show_open_files_popup(pid, process_open_files(pid));
+    }
+
+    /// Text printed to stdout after quitting in print mode
+    fn selected_process_output(&self) -> Option<String> {
+        let prc = self.search_results.nth(self.tui.get_selected_row_index())?;
+        match self.print_mode? {
+            PrintMode::Pid => Some(prc.pid.to_string()),
+            PrintMode::PidAndCmd => Some(format!("{}\t{}", prc.pid, prc.cmd_line)),
+        }
+    }
+
+    fn kill_processes(&mut self, pids: Vec<u32>, signal: KillSignal) {
+        self.remember_query();
+        self.tui.reset_error_message();
+        let mut killed = vec![];
+        let mut errors = vec![];
+        for pid in pids {
+            match self.process_manager.kill_process(pid, signal) {
+                Ok(()) => killed.push(pid),
+                Err(err) => errors.push(err),
+            }
+        }
+        self.tui.clear_marks();
+        if !killed.is_empty() {
+            //NOTE: process may take a while to exit, so it is removed from results of refresh too
+            for pid in killed.iter() {
+                self.search_results.remove(*pid);
+            }
+            self.tui
+                .update_process_table_number_of_items(self.search_results.len());
+            self.killed.extend(&killed);
+            self.refresh_processes();
+            if let Some(timeout) = self.verify_kill {
+                self.kill_check = Some(KillCheck::new(
+                    killed.clone(),
+                    signal,
+                    timeout,
+                    Instant::now(),
+                ));
+            }
+        }
+        if killed.len() + errors.len() > 1 {
+            let errors: Vec<String> = errors.iter().map(|err| err.to_string()).collect();
+            self.tui
+                .show_kill_summary_popup(signal.name, &killed, &errors);
+            return;
+        }
+        //NOTE: failed process stays in the table
+        if let [error] = errors.as_slice() {
+            self.tui.set_error_message(error.to_string());
+        }
+    }
+
+    /// Open fds are counted lazily, only for processes whose count is shown
+    fn load_open_fds(&mut self) -> bool {
+        let rows = self.tui.rows_showing_open_fds(self.search_results.len());
+        self.search_results.load_open_fds(rows)
+    }
+
+    /// Reports whether killed processes exited, SIGKILL is offered for the ones that survived
+    fn check_killed_processes(&mut self) {
+        let Some(kill_check) = &self.kill_check else {
+            return;
+        };
+        let signal = kill_check.signal;
+        let process_manager = &self.process_manager;
+        match kill_check.check(Instant::now(), |pid| process_manager.is_terminated(pid)) {
+            KillCheckResult::Pending => return,
+            KillCheckResult::Terminated(msg) => self.tui.set_info_message(msg),
+            KillCheckResult::StillRunning(pids, msg) => {
+                //NOTE: processes were removed from results as killed, refresh shows them again
+                self.killed.retain(|pid| !pids.contains(pid));
+                self.refresh_processes();
+                if signal != FORCE_KILL_SIGNAL {
+                    self.tui.show_confirm_signal_popup(
+                        pids,
+                        FORCE_KILL_SIGNAL,
+                        format!(
+                            "{} after {}. Send {}?",
+                            msg, signal.name, FORCE_KILL_SIGNAL.name
+                        ),
+                    );
+                }
+                self.tui.set_error_message(msg);
             }
         }
+        self.kill_check = None;
     }
 }
 
 pub fn start_app(search_criteria: String, app_settings: AppSettings) -> Result<()> {
-    // setup terminal
-    enable_raw_mode()?;
-    let backend = CrosstermBackend::new(io::stdout());
     let viewport = app_settings.viewport.clone();
-    let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport })?;
-
-    // create app and run it
+    let print_single_match = app_settings.print_single_match;
+    //NOTE: app is created first, so errors (i.e. unknown user) are printed to normal terminal
     let app = App::new(search_criteria, app_settings)?;
-    let res = run_app(&mut terminal, app);
+    if print_single_match && app.search_results.len() == 1 {
+        if let Some(selected) = app.selected_process_output() {
+            println!("{selected}");
+            return Ok(());
+        }
+    }
+    let ui_on_stderr = app.print_mode.is_some();
+    // setup terminal
+    enable_raw_mode()?;
+    set_panic_hook(ui_on_stderr);
+    let res = run_in_terminal(app, viewport, ui_output(ui_on_stderr));
 
-    // restore terminal
-    disable_raw_mode()?;
-    terminal.clear()?;
+    // restore terminal, errors of setup and main loop end up here too
+    restore_terminal(&mut ui_output(ui_on_stderr))?;
 
     //FIXME: add error handling, for exaple some error page should be shown
-    if let Err(err) = res {
-        println!("{err:?}");
+    match res {
+        Ok(Some(selected)) => println!("{selected}"),
+        Ok(None) => {}
+        Err(err) => eprintln!("{err:?}"),
     }
 
     Ok(())
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<()> {
+//NOTE: in print mode stdout is usually captured, i.e. $(pik --print), so ui is drawn to stderr
+fn ui_output(ui_on_stderr: bool) -> Box<dyn Write> {
+    match ui_on_stderr {
+        true => Box::new(io::stderr()),
+        false => Box::new(io::stdout()),
+    }
+}
+
+fn run_in_terminal(
+    app: App,
+    viewport: Viewport,
+    output: Box<dyn Write>,
+) -> io::Result<Option<String>> {
+    let backend = CrosstermBackend::new(output);
+    let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport })?;
+    execute!(terminal.backend_mut(), EnableMouseCapture)?;
+    let res = run_app(&mut terminal, app);
+    terminal.clear()?;
+    res
+}
+
+//NOTE: mouse capture is disabled even when app failed, otherwise terminal keeps reporting mouse
+fn restore_terminal(output: &mut impl Write) -> io::Result<()> {
+    execute!(output, DisableMouseCapture, Show)?;
+    disable_raw_mode()
+}
+
+/// Terminal is restored before panic message is printed, so shell is usable without typing 'reset'
+fn set_panic_hook(ui_on_stderr: bool) {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal(&mut ui_output(ui_on_stderr));
+        hook(info);
+    }));
+}
+
+const REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+const REFRESH_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Returns text that should be printed after terminal is restored
+fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<Option<String>> {
     loop {
+        app.search_if_refresh_finished();
+        app.check_killed_processes();
         terminal.draw(|f| app.tui.render_ui(&app.search_results, f))?;
+        //NOTE: rows on screen are known only after render
+        if app.load_open_fds() {
+            terminal.draw(|f| app.tui.render_ui(&app.search_results, f))?;
+        }
 
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                use KeyCode::*;
-                match key.code {
-                    Esc => return Ok(()),
-                    Up if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        app.tui.select_first_row()
-                    }
-                    Down if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        app.tui.select_last_row()
-                    }
-                    Up | BackTab => app.tui.select_previous_row(1),
-                    Tab | Down => app.tui.select_next_row(1),
-                    Char('j') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        app.tui.select_next_row(1);
-                    }
-                    Char('k') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        app.tui.select_previous_row(1);
-                    }
-                    PageUp => app.tui.select_previous_row(10),
-                    PageDown => app.tui.select_next_row(10),
-                    Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        return Ok(());
-                    }
-                    Char('x') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        app.kill_selected_process()
-                    }
-                    Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        app.search_for_processess()
-                    }
-                    Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        app.tui.process_details_down(&mut terminal.get_frame())
-                    }
-                    Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        app.tui.process_details_up()
+        //NOTE: pending search waits for next key only until it is due, without key
+        // the table is redrawn so run time of processes stays current
+        let timeout = match app.search_debouncer.time_left(Instant::now()) {
+            Some(timeout) => timeout.min(REDRAW_INTERVAL),
+            None => REDRAW_INTERVAL,
+        };
+        //NOTE: scan running in background and killed processes are checked often,
+        // so their results are shown soon
+        let timeout = match app.process_manager.is_refreshing() || app.kill_check.is_some() {
+            true => timeout.min(REFRESH_POLL_INTERVAL),
+            false => timeout,
+        };
+        if !event::poll(timeout)? {
+            app.search_if_typing_stopped();
+            continue;
+        }
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            Event::Mouse(mouse) => {
+                if app.tui.handle_mouse(mouse, &mut terminal.get_frame()) {
+                    app.show_details_of_selected_process();
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        app.tui.reset_info_message();
+        match app.tui.handle_popup_input(key) {
+            PopupEvent::NotHandled => {}
+            PopupEvent::Handled => continue,
+            PopupEvent::BookmarkSelected(query) => {
+                app.search_bookmark(&query);
+                continue;
+            }
+            PopupEvent::SortSelected(column) => {
+                app.sort_by(column);
+                continue;
+            }
+            PopupEvent::KillConfirmed(pids) => {
+                app.tui.show_signal_popup(pids);
+                continue;
+            }
+            PopupEvent::SignalSelected(signal, pids) => {
+                app.kill_processes(pids, signal);
+                continue;
+            }
+            PopupEvent::CopyText(text) => {
+                app.copy_text(&text);
+                continue;
+            }
+        }
+        //NOTE: any other key ends browsing, i.e. after editing query Up selects processes again
+        if !key.modifiers.is_empty() || !matches!(key.code, KeyCode::Up | KeyCode::Down) {
+            app.history.stop_browsing();
+        }
+        //NOTE: in normal mode 'o' opens details whatever Enter does
+        if app.tui.is_normal_mode() && key.code == KeyCode::Char('o') && key.modifiers.is_empty() {
+            app.show_details_of_selected_process();
+            continue;
+        }
+        if app.tui.handle_normal_mode_key(key) {
+            continue;
+        }
+        let keys = app.tui.keys();
+        use KeyCode::*;
+        match key.code {
+            Esc if key.modifiers.is_empty() && app.tui.has_marks() => app.tui.clear_marks(),
+            _ if keys.quit.matches(&key) => {
+                app.save_history();
+                return Ok(None);
+            }
+            _ if keys.kill.matches(&key) => app.select_signal_for_processes(),
+            _ if keys.kill_tree.matches(&key) => app.select_signal_for_process_subtree(),
+            _ if keys.refresh.matches(&key) => app.refresh_processes(),
+            _ if keys.details_forward.matches(&key) => {
+                app.tui.process_details_down(&mut terminal.get_frame())
+            }
+            _ if keys.details_backward.matches(&key) => app.tui.process_details_up(),
+            _ if keys.search_mode.matches(&key) => app.cycle_search_mode(),
+            _ if keys.copy_pid.matches(&key) => app.copy_selected_pid(),
+            _ if keys.copy_cmd.matches(&key) => app.copy_selected_cmd_line(),
+            _ if keys.environment.matches(&key) => app.show_environment_of_selected_process(),
+            _ if keys.details_tab.matches(&key) => app.tui.cycle_details_tab(),
+            _ if keys.toggle_details.matches(&key) => app.tui.toggle_details(),
+            _ if keys.start_time_format.matches(&key) => app.tui.cycle_start_time_format(),
+            _ if keys.open_files.matches(&key) => app.show_open_files_of_selected_process(),
+            _ if keys.export.matches(&key) => app.export_processes(),
+            _ if keys.sort_picker.matches(&key) => app.tui.show_sort_popup(),
+            _ if keys.normal_mode.matches(&key) => app.tui.toggle_normal_mode(),
+            _ if keys.pin.matches(&key) => app.toggle_pin_of_selected_process(),
+            _ if keys.group.matches(&key) => app.toggle_grouping(),
+            _ if keys.fuzzy_search.matches(&key) => app.toggle_fuzzy_search(),
+            _ if keys.select_parent.matches(&key) => app.select_parent_of_selected_process(),
+            _ if keys.show_children.matches(&key) => app.show_children_of_selected_process(),
+            _ if keys.mark.matches(&key) => app.toggle_mark_of_selected_process(),
+            _ if keys.mark_all.matches(&key) => app.mark_all_processes(),
+            _ if keys.sort_column.matches(&key) => app.cycle_sort_column(),
+            _ if keys.sort_order.matches(&key) => app.toggle_sort_order(),
+            _ if keys.tree_view.matches(&key) => app.toggle_tree_view(),
+            _ if keys.bookmarks.matches(&key) => app.tui.show_bookmarks_popup(),
+            _ if keys.regex_search.matches(&key) => app.toggle_regex_search(),
+            _ if keys.case_sensitivity.matches(&key) => app.cycle_case_sensitivity(),
+            _ if keys.help.matches(&key) => app.tui.show_keybindings_help(),
+            _ if keys.hide_self.matches(&key) => app.toggle_hide_self(),
+            _ if keys.collapse.matches(&key) && app.tui.tree_view() => {
+                app.collapse_selected_subtree()
+            }
+            _ if keys.expand.matches(&key) && app.tui.tree_view() => app.expand_selected_subtree(),
+            _ if keys.collapse.matches(&key) && app.tui.grouped() => app.collapse_selected_group(),
+            _ if keys.expand.matches(&key) && app.tui.grouped() => app.expand_selected_group(),
+            #[cfg(unix)]
+            _ if keys.pause.matches(&key) => app.send_job_control_signal(PAUSE_SIGNAL, "Paused"),
+            #[cfg(unix)]
+            _ if keys.resume.matches(&key) => app.send_job_control_signal(RESUME_SIGNAL, "Resumed"),
+            //NOTE: results being typed are shown first, so user does not pick from stale ones
+            _ if keys.enter.matches(&key) && app.search_debouncer.is_pending() => {
+                app.search_for_processess()
+            }
+            _ if keys.enter.matches(&key) && app.selected_group().is_some() => {
+                app.toggle_selected_group()
+            }
+            _ if keys.enter.matches(&key) => match app.enter_action {
+                EnterAction::Print => {
+                    if let Some(selected) = app.selected_process_output() {
+                        app.save_history();
+                        return Ok(Some(selected));
                     }
-                    Char(to_insert) => app.enter_char(to_insert),
-                    Backspace => app.delete_char(),
-                    _ => app.tui.handle_input(key),
                 }
+                EnterAction::Kill => app.select_signal_for_processes(),
+                EnterAction::Details => app.show_details_of_selected_process(),
+            },
+            Up if key.modifiers.contains(KeyModifiers::CONTROL) => app.tui.select_first_row(),
+            Down if key.modifiers.contains(KeyModifiers::CONTROL) => app.tui.select_last_row(),
+            Up if key.modifiers.is_empty() => app.search_previous_query_or_select_previous_row(),
+            Down if key.modifiers.is_empty() && app.history.is_browsing() => {
+                app.search_next_query()
+            }
+            Up | BackTab => app.tui.select_previous_row(1),
+            Tab | Down => app.tui.select_next_row(1),
+            Char('j') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.tui.select_next_row(1);
+            }
+            Char('k') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.tui.select_previous_row(1);
             }
+            PageUp => app.tui.select_previous_row(10),
+            PageDown => app.tui.select_next_row(10),
+            Right
+                if key.modifiers.is_empty()
+                    && app.tui.search_cursor_at_end()
+                    && app.selected_group().is_some() =>
+            {
+                app.expand_selected_group()
+            }
+            Char(to_insert) => app.enter_char(to_insert),
+            Backspace => app.delete_char(),
+            _ => app.handle_input(key),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::{args::CliArgs, config::AppConfig};
+
+    #[test]
+    fn should_select_previous_row_on_up_without_history() {
+        let settings = AppSettings::from(AppConfig::default(), &CliArgs::parse_from(["pik"]));
+        let mut app = App::new("".to_string(), settings).unwrap();
+        app.history = SearchHistory::new(vec![], None);
+        app.tui.select_row_by_index(Some(1));
+
+        app.search_previous_query_or_select_previous_row();
+
+        assert_eq!(app.tui.get_selected_row_index(), Some(0));
+        assert_eq!(app.tui.search_input_text(), "");
+        assert!(!app.history.is_browsing());
+    }
+}
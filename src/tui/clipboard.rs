@@ -0,0 +1,110 @@
+use std::{
+    io::{ErrorKind, Write},
+    process::{Command, Stdio},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+/// Programs that can put text to the system clipboard, first one found is used
+const CLIPBOARD_COMMANDS: [ClipboardCommand; 5] = [
+    ClipboardCommand {
+        program: "pbcopy",
+        args: &[],
+        env: None,
+    },
+    ClipboardCommand {
+        program: "wl-copy",
+        args: &[],
+        env: Some("WAYLAND_DISPLAY"),
+    },
+    ClipboardCommand {
+        program: "xclip",
+        args: &["-selection", "clipboard"],
+        env: Some("DISPLAY"),
+    },
+    ClipboardCommand {
+        program: "xsel",
+        args: &["--clipboard", "--input"],
+        env: Some("DISPLAY"),
+    },
+    ClipboardCommand {
+        program: "clip",
+        args: &[],
+        env: None,
+    },
+];
+
+#[derive(Debug, PartialEq, Eq)]
+struct ClipboardCommand {
+    program: &'static str,
+    args: &'static [&'static str],
+    /// Environment variable that must be set, i.e. there is no X11 clipboard without display
+    env: Option<&'static str>,
+}
+
+/// Copies text using clipboard program of the platform, fails when there is none (i.e. over ssh)
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    let commands = available_commands(|name| std::env::var_os(name).is_some());
+    for command in commands {
+        match command.run(text) {
+            //NOTE: program is not installed, next one is tried
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            result => {
+                return result.with_context(|| format!("Failed to copy with {}", command.program));
+            }
+        }
+    }
+    Err(anyhow!(
+        "No clipboard available, install wl-copy, xclip or xsel"
+    ))
+}
+
+fn available_commands(is_env_set: impl Fn(&str) -> bool) -> Vec<&'static ClipboardCommand> {
+    CLIPBOARD_COMMANDS
+        .iter()
+        .filter(|command| command.env.is_none_or(&is_env_set))
+        .collect()
+}
+
+impl ClipboardCommand {
+    fn run(&self, text: &str) -> std::io::Result<()> {
+        let mut child = Command::new(self.program)
+            .args(self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        let status = child.wait()?;
+        match status.success() {
+            true => Ok(()),
+            false => Err(std::io::Error::other(format!("exited with {}", status))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn programs(commands: Vec<&ClipboardCommand>) -> Vec<&str> {
+        commands.iter().map(|command| command.program).collect()
+    }
+
+    #[test]
+    fn should_skip_display_clipboards_without_display() {
+        let commands = available_commands(|_| false);
+        assert_eq!(programs(commands), vec!["pbcopy", "clip"]);
+    }
+
+    #[test]
+    fn should_prefer_wayland_clipboard() {
+        let commands = available_commands(|name| name == "WAYLAND_DISPLAY" || name == "DISPLAY");
+        assert_eq!(
+            programs(commands),
+            vec!["pbcopy", "wl-copy", "xclip", "xsel", "clip"]
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use std::time::{Duration, Instant};
+
+/// Delays an action until there was no new request for given time, i.e. searching while user types.
+/// Current time is always passed in, so it can be tested without sleeping
+pub struct Debouncer {
+    delay: Duration,
+    deadline: Option<Instant>,
+}
+
+impl Debouncer {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            deadline: None,
+        }
+    }
+
+    /// Action is postponed by the delay with every request
+    pub fn request(&mut self, now: Instant) {
+        self.deadline = Some(now + self.delay);
+    }
+
+    pub fn cancel(&mut self) {
+        self.deadline = None;
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.deadline.is_some()
+    }
+
+    /// Time left until action is due, None when nothing is pending
+    pub fn time_left(&self, now: Instant) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(now))
+    }
+
+    /// Returns true once when action is due, pending request is cleared
+    pub fn fire(&mut self, now: Instant) -> bool {
+        match self.deadline {
+            Some(deadline) if deadline <= now => {
+                self.deadline = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELAY: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn should_fire_after_delay() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(DELAY);
+        assert!(!debouncer.fire(start + DELAY));
+
+        debouncer.request(start);
+        assert!(debouncer.is_pending());
+        assert_eq!(debouncer.time_left(start), Some(DELAY));
+        assert!(!debouncer.fire(start + Duration::from_millis(99)));
+        assert!(debouncer.fire(start + DELAY));
+        assert!(!debouncer.is_pending());
+        assert!(!debouncer.fire(start + DELAY * 2));
+    }
+
+    #[test]
+    fn should_postpone_on_every_request() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(DELAY);
+        debouncer.request(start);
+        debouncer.request(start + Duration::from_millis(80));
+        assert!(!debouncer.fire(start + DELAY));
+        assert_eq!(
+            debouncer.time_left(start + DELAY),
+            Some(Duration::from_millis(80))
+        );
+        assert!(debouncer.fire(start + Duration::from_millis(180)));
+    }
+
+    #[test]
+    fn should_not_fire_when_cancelled() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(DELAY);
+        debouncer.request(start);
+        debouncer.cancel();
+        assert_eq!(debouncer.time_left(start), None);
+        assert!(!debouncer.fire(start + DELAY));
+    }
+
+    #[test]
+    fn should_report_no_time_left_when_overdue() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(DELAY);
+        debouncer.request(start);
+        assert_eq!(debouncer.time_left(start + DELAY * 2), Some(Duration::ZERO));
+    }
+}
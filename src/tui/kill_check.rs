@@ -0,0 +1,114 @@
+use std::time::{Duration, Instant};
+
+use crate::processes::KillSignal;
+
+/// Killed processes are checked until they exit or the timeout passes, so ignored signal is noticed.
+/// Current time is always passed in, so it can be tested without sleeping
+pub struct KillCheck {
+    pids: Vec<u32>,
+    pub signal: KillSignal,
+    deadline: Instant,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum KillCheckResult {
+    /// Some processes are still running, but there is time left
+    Pending,
+    Terminated(String),
+    /// Timeout passed, pids of processes that survived the signal are returned
+    StillRunning(Vec<u32>, String),
+}
+
+impl KillCheck {
+    pub fn new(pids: Vec<u32>, signal: KillSignal, timeout: Duration, now: Instant) -> Self {
+        Self {
+            pids,
+            signal,
+            deadline: now + timeout,
+        }
+    }
+
+    pub fn check(&self, now: Instant, is_terminated: impl Fn(u32) -> bool) -> KillCheckResult {
+        let running: Vec<u32> = self
+            .pids
+            .iter()
+            .copied()
+            .filter(|pid| !is_terminated(*pid))
+            .collect();
+        if running.is_empty() {
+            let msg = match self.pids.as_slice() {
+                [pid] => format!("PID {} terminated", pid),
+                pids => format!("{} processes terminated", pids.len()),
+            };
+            return KillCheckResult::Terminated(msg);
+        }
+        if now < self.deadline {
+            return KillCheckResult::Pending;
+        }
+        let msg = match (running.as_slice(), self.pids.len()) {
+            ([pid], 1) => format!("PID {} still running", pid),
+            (running, all) => format!(
+                "{} of {} processes still running: {}",
+                running.len(),
+                all,
+                join_pids(running)
+            ),
+        };
+        KillCheckResult::StillRunning(running, msg)
+    }
+}
+
+fn join_pids(pids: &[u32]) -> String {
+    let pids: Vec<String> = pids.iter().map(|pid| pid.to_string()).collect();
+    pids.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processes::kill_signals;
+
+    const TIMEOUT: Duration = Duration::from_millis(1000);
+
+    #[test]
+    fn should_report_terminated_process_right_away() {
+        let start = Instant::now();
+        let check = KillCheck::new(vec![1234], kill_signals()[0], TIMEOUT, start);
+        assert_eq!(
+            check.check(start, |_| true),
+            KillCheckResult::Terminated("PID 1234 terminated".to_string())
+        );
+        let check = KillCheck::new(vec![1, 2, 3], kill_signals()[0], TIMEOUT, start);
+        assert_eq!(
+            check.check(start, |_| true),
+            KillCheckResult::Terminated("3 processes terminated".to_string())
+        );
+    }
+
+    #[test]
+    fn should_wait_for_process_until_timeout() {
+        let start = Instant::now();
+        let check = KillCheck::new(vec![1234], kill_signals()[0], TIMEOUT, start);
+        assert_eq!(
+            check.check(start + Duration::from_millis(999), |_| false),
+            KillCheckResult::Pending
+        );
+        assert_eq!(
+            check.check(start + TIMEOUT, |_| false),
+            KillCheckResult::StillRunning(vec![1234], "PID 1234 still running".to_string())
+        );
+    }
+
+    #[test]
+    fn should_list_processes_that_survived_signal() {
+        let start = Instant::now();
+        let check = KillCheck::new(vec![1, 2, 3], kill_signals()[0], TIMEOUT, start);
+        assert_eq!(
+            check.check(start + TIMEOUT, |pid| pid == 2),
+            KillCheckResult::StillRunning(
+                vec![1, 3],
+                "2 of 3 processes still running: 1, 3".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+
+use crate::processes::{KillSignal, SortColumn};
+
+mod bookmarks;
+mod confirm;
+mod msg;
+mod scroll;
+mod signal;
+mod sort;
+
+pub use bookmarks::BookmarksPopup;
+pub use confirm::ConfirmKillPopup;
+pub use msg::MsgPopup;
+pub use scroll::{ScrollType, VerticalScroll};
+pub use signal::SignalPopup;
+pub use sort::SortPopup;
+
+pub enum PopupEvent {
+    /// No popup is visible, event should be handled by main view
+    NotHandled,
+    Handled,
+    SignalSelected(KillSignal, Vec<u32>),
+    BookmarkSelected(String),
+    /// Table should be sorted by the column, order is toggled when it is sorted by it already
+    SortSelected(SortColumn),
+    /// Killing pik or its ancestors was confirmed, signal should be chosen next
+    KillConfirmed(Vec<u32>),
+    /// Text of the popup should be copied to clipboard
+    CopyText(String),
+}
+
+fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
+
+fn centered_area(area: Rect, width: u16, height: u16) -> Rect {
+    let vertical = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(width)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
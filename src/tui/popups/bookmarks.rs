@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Clear, HighlightSpacing, List, ListState,
+    },
+    Frame,
+};
+
+use crate::{config::KeyConfig, tui::symbols::Symbols};
+
+use super::{popup_area, PopupEvent};
+
+pub struct BookmarksPopup {
+    /// Bookmark names with their queries, sorted by name
+    bookmarks: Vec<(String, String)>,
+    state: ListState,
+    visible: bool,
+}
+
+impl BookmarksPopup {
+    pub fn new(bookmarks: BTreeMap<String, String>) -> Self {
+        Self {
+            bookmarks: bookmarks.into_iter().collect(),
+            state: ListState::default(),
+            visible: false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bookmarks.is_empty()
+    }
+
+    pub fn show(&mut self) {
+        self.state.select(Some(0));
+        self.visible = true;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn event(&mut self, key: KeyEvent, keys: &KeyConfig) -> PopupEvent {
+        if !self.visible {
+            return PopupEvent::NotHandled;
+        }
+        match key.code {
+            KeyCode::Esc => self.hide(),
+            _ if keys.popup_up.matches(&key) => self.select(|i| i.saturating_sub(1)),
+            _ if keys.popup_down.matches(&key) => self.select(|i| i.saturating_add(1)),
+            KeyCode::PageUp => self.select(|i| i.saturating_sub(10)),
+            KeyCode::PageDown => self.select(|i| i.saturating_add(10)),
+            KeyCode::Home => self.select(|_| 0),
+            KeyCode::End => self.select(|_| usize::MAX),
+            _ if keys.enter.matches(&key) => {
+                let selected = self.state.selected().and_then(|i| self.bookmarks.get(i));
+                if let Some((_, query)) = selected {
+                    let query = query.clone();
+                    self.hide();
+                    return PopupEvent::BookmarkSelected(query);
+                }
+            }
+            _ => {}
+        }
+        PopupEvent::Handled
+    }
+
+    //NOTE: list state clamps selection only on render so we do it ourselves
+    fn select(&mut self, index: impl Fn(usize) -> usize) {
+        let last = self.bookmarks.len().saturating_sub(1);
+        let current = self.state.selected().unwrap_or(0);
+        self.state.select(Some(index(current).min(last)));
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect, symbols: &Symbols) {
+        if !self.visible {
+            return;
+        }
+        let area = popup_area(area, 60, 60);
+        let name_width = self
+            .bookmarks
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .max()
+            .unwrap_or(0);
+        let items = self
+            .bookmarks
+            .iter()
+            .map(|(name, query)| format!("{:<width$}  {}", name, query, width = name_width));
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(
+                        Title::from(" Bookmarks ")
+                            .alignment(Alignment::Left)
+                            .position(Position::Top),
+                    )
+                    .title(
+                        Title::from(" <ENTER> search ")
+                            .alignment(Alignment::Right)
+                            .position(Position::Bottom),
+                    )
+                    .borders(Borders::ALL)
+                    .border_set(symbols.thick_border),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ")
+            .highlight_spacing(HighlightSpacing::Always);
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut self.state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn should_select_bookmarked_query() {
+        let mut popup = BookmarksPopup::new(BTreeMap::from([
+            ("web".to_string(), ":80,443".to_string()),
+            ("noisy".to_string(), "chrome".to_string()),
+        ]));
+        assert!(matches!(
+            popup.event(key(KeyCode::Enter), &KeyConfig::default()),
+            PopupEvent::NotHandled
+        ));
+
+        popup.show();
+        popup.event(key(KeyCode::End), &KeyConfig::default());
+        popup.event(key(KeyCode::Down), &KeyConfig::default());
+        match popup.event(key(KeyCode::Enter), &KeyConfig::default()) {
+            PopupEvent::BookmarkSelected(query) => assert_eq!(query, ":80,443"),
+            _ => panic!("bookmark should be selected"),
+        }
+        assert!(matches!(
+            popup.event(key(KeyCode::Enter), &KeyConfig::default()),
+            PopupEvent::NotHandled
+        ));
+    }
+}
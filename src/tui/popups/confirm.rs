@@ -0,0 +1,155 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Clear, Paragraph, Wrap,
+    },
+    Frame,
+};
+
+use crate::{config::KeyConfig, processes::KillSignal, tui::symbols::Symbols};
+
+use super::{centered_area, PopupEvent};
+
+/// Asks before killing a whole process tree or pik itself
+pub struct ConfirmKillPopup {
+    /// Processes that will be killed after confirmation, popup is visible when not empty
+    pids: Vec<u32>,
+    /// Sent right after confirmation, otherwise signal is chosen next
+    signal: Option<KillSignal>,
+    msg: String,
+}
+
+impl ConfirmKillPopup {
+    pub fn new() -> Self {
+        Self {
+            pids: vec![],
+            signal: None,
+            msg: String::new(),
+        }
+    }
+
+    pub fn show(&mut self, pids: Vec<u32>, msg: String) {
+        self.pids = pids;
+        self.signal = None;
+        self.msg = msg;
+    }
+
+    /// Used to escalate, i.e. to SIGKILL when process survived SIGTERM
+    pub fn show_with_signal(&mut self, pids: Vec<u32>, signal: KillSignal, msg: String) {
+        self.show(pids, msg);
+        self.signal = Some(signal);
+    }
+
+    pub fn is_visible(&self) -> bool {
+        !self.pids.is_empty()
+    }
+
+    pub fn hide(&mut self) {
+        self.pids.clear();
+    }
+
+    pub fn event(&mut self, key: KeyEvent, keys: &KeyConfig) -> PopupEvent {
+        if self.pids.is_empty() {
+            return PopupEvent::NotHandled;
+        }
+        match key.code {
+            KeyCode::Esc => self.hide(),
+            _ if keys.enter.matches(&key) => {
+                let pids = std::mem::take(&mut self.pids);
+                self.hide();
+                return match self.signal.take() {
+                    Some(signal) => PopupEvent::SignalSelected(signal, pids),
+                    None => PopupEvent::KillConfirmed(pids),
+                };
+            }
+            _ => {}
+        }
+        PopupEvent::Handled
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect, symbols: &Symbols) {
+        if self.pids.is_empty() {
+            return;
+        }
+        let area = centered_area(area, 60, 7);
+        let paragraph = Paragraph::new(self.msg.as_str())
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .title(
+                        Title::from(" Warning ")
+                            .alignment(Alignment::Left)
+                            .position(Position::Top),
+                    )
+                    .title(
+                        Title::from(" <ENTER> continue | <ESC> cancel ")
+                            .alignment(Alignment::Right)
+                            .position(Position::Bottom),
+                    )
+                    .borders(Borders::ALL)
+                    .border_set(symbols.thick_border),
+            );
+        f.render_widget(Clear, area);
+        f.render_widget(paragraph, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+    use crate::processes::FORCE_KILL_SIGNAL;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn should_confirm_killing_all_processes() {
+        let mut popup = ConfirmKillPopup::new();
+        popup.show(vec![10, 20], "Kill?".to_string());
+        match popup.event(key(KeyCode::Enter), &KeyConfig::default()) {
+            PopupEvent::KillConfirmed(pids) => assert_eq!(pids, vec![10, 20]),
+            _ => panic!("kill should be confirmed"),
+        }
+        assert!(matches!(
+            popup.event(key(KeyCode::Enter), &KeyConfig::default()),
+            PopupEvent::NotHandled
+        ));
+    }
+
+    #[test]
+    fn should_send_given_signal_after_confirmation() {
+        let mut popup = ConfirmKillPopup::new();
+        popup.show_with_signal(vec![10], FORCE_KILL_SIGNAL, "Kill?".to_string());
+        match popup.event(key(KeyCode::Enter), &KeyConfig::default()) {
+            PopupEvent::SignalSelected(signal, pids) => {
+                assert_eq!(signal, FORCE_KILL_SIGNAL);
+                assert_eq!(pids, vec![10]);
+            }
+            _ => panic!("signal should be sent"),
+        }
+        popup.show(vec![10], "Kill?".to_string());
+        assert!(matches!(
+            popup.event(key(KeyCode::Enter), &KeyConfig::default()),
+            PopupEvent::KillConfirmed(_)
+        ));
+    }
+
+    #[test]
+    fn should_cancel_kill() {
+        let mut popup = ConfirmKillPopup::new();
+        popup.show(vec![10], "Kill?".to_string());
+        assert!(matches!(
+            popup.event(key(KeyCode::Esc), &KeyConfig::default()),
+            PopupEvent::Handled
+        ));
+        assert!(matches!(
+            popup.event(key(KeyCode::Enter), &KeyConfig::default()),
+            PopupEvent::NotHandled
+        ));
+    }
+}
@@ -0,0 +1,177 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    text::Text,
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Clear, Paragraph, Wrap,
+    },
+    Frame,
+};
+
+use crate::{config::KeyConfig, tui::symbols::Symbols};
+
+use super::{popup_area, PopupEvent, ScrollType, VerticalScroll};
+
+pub struct MsgPopup {
+    title: String,
+    msg: String,
+    visible: bool,
+    /// Popup takes whole screen instead of its center, i.e. for process details
+    full_screen: bool,
+    scroll: VerticalScroll,
+}
+
+impl MsgPopup {
+    pub fn new() -> Self {
+        Self {
+            title: String::new(),
+            msg: String::new(),
+            visible: false,
+            full_screen: false,
+            scroll: VerticalScroll::new(),
+        }
+    }
+
+    pub fn show(&mut self, title: impl Into<String>, msg: impl Into<String>) {
+        self.title = title.into();
+        self.msg = msg.into();
+        self.scroll.reset();
+        self.visible = true;
+        self.full_screen = false;
+    }
+
+    pub fn show_full_screen(&mut self, title: impl Into<String>, msg: impl Into<String>) {
+        self.show(title, msg);
+        self.full_screen = true;
+    }
+
+    /// Replaces the message of visible popup, scroll position is kept
+    pub fn update(&mut self, title: impl Into<String>, msg: impl Into<String>) {
+        self.title = title.into();
+        self.msg = msg.into();
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn event(&mut self, key: KeyEvent, keys: &KeyConfig) -> PopupEvent {
+        if !self.visible {
+            return PopupEvent::NotHandled;
+        }
+        match key.code {
+            KeyCode::Esc => self.hide(),
+            _ if keys.enter.matches(&key) => self.hide(),
+            _ if keys.popup_copy.matches(&key) => return PopupEvent::CopyText(self.msg.clone()),
+            _ if keys.popup_up.matches(&key) => {
+                self.scroll.move_top(ScrollType::Up);
+            }
+            _ if keys.popup_down.matches(&key) => {
+                self.scroll.move_top(ScrollType::Down);
+            }
+            KeyCode::PageUp => {
+                self.scroll.move_top(ScrollType::PageUp);
+            }
+            KeyCode::PageDown => {
+                self.scroll.move_top(ScrollType::PageDown);
+            }
+            KeyCode::Home => {
+                self.scroll.move_top(ScrollType::Home);
+            }
+            KeyCode::End => {
+                self.scroll.move_top(ScrollType::End);
+            }
+            _ => {}
+        }
+        PopupEvent::Handled
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect, symbols: &Symbols, keys: &KeyConfig) {
+        if !self.visible {
+            return;
+        }
+        //NOTE: bottom line is left for messages, i.e. that text was copied
+        let area = match self.full_screen {
+            true => Rect {
+                height: area.height.saturating_sub(1),
+                ..area
+            },
+            false => popup_area(area, 80, 80),
+        };
+        let paragraph = Paragraph::new(Text::from(self.msg.as_str())).wrap(Wrap { trim: false });
+        let content_width = area.width.saturating_sub(2);
+        let content_height = area.height.saturating_sub(2) as usize;
+        self.scroll
+            .update(paragraph.line_count(content_width), content_height);
+
+        let paragraph = paragraph
+            .block(
+                Block::default()
+                    .title(
+                        Title::from(format!(" {} ", self.title))
+                            .alignment(Alignment::Left)
+                            .position(Position::Top),
+                    )
+                    .title(
+                        Title::from(format!(" {} copy | <ESC> close ", keys.popup_copy.short()))
+                            .alignment(Alignment::Right)
+                            .position(Position::Bottom),
+                    )
+                    .borders(Borders::ALL)
+                    .border_set(symbols.thick_border),
+            )
+            .scroll((self.scroll.get_top() as u16, 0));
+        f.render_widget(Clear, area);
+        f.render_widget(paragraph, area);
+        self.scroll.draw(f, area, symbols);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn should_copy_message_of_visible_popup() {
+        let mut popup = MsgPopup::new();
+        let keys = KeyConfig::default();
+        assert!(matches!(
+            popup.event(key(KeyCode::Char('y')), &keys),
+            PopupEvent::NotHandled
+        ));
+        popup.show_full_screen("Details", "PID: 1234");
+        match popup.event(key(KeyCode::Char('y')), &keys) {
+            PopupEvent::CopyText(text) => assert_eq!(text, "PID: 1234"),
+            _ => panic!("message should be copied"),
+        }
+        assert!(popup.is_visible());
+    }
+
+    #[test]
+    fn should_keep_scroll_when_message_is_updated() {
+        let mut popup = MsgPopup::new();
+        let keys = KeyConfig::default();
+        popup.show_full_screen("Details", "line\n".repeat(100));
+        popup.scroll.update(100, 10);
+        popup.event(key(KeyCode::Down), &keys);
+        popup.event(key(KeyCode::Down), &keys);
+        popup.update("Details", "changed\n".repeat(100));
+        assert_eq!(popup.scroll.get_top(), 2);
+        assert!(popup.msg.starts_with("changed"));
+
+        popup.show("Other", "text");
+        assert_eq!(popup.scroll.get_top(), 0);
+        assert!(!popup.full_screen);
+    }
+}
@@ -0,0 +1,144 @@
+use ratatui::{
+    layout::{Margin, Rect},
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState},
+    Frame,
+};
+
+use crate::tui::symbols::Symbols;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollType {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+}
+
+#[derive(Debug, Default)]
+pub struct VerticalScroll {
+    top: usize,
+    max_top: usize,
+    visible_height: usize,
+}
+
+impl VerticalScroll {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_top(&self) -> usize {
+        self.top
+    }
+
+    pub fn reset(&mut self) {
+        self.top = 0;
+    }
+
+    /// Moves top line of the viewport, returns true if position changed
+    pub fn move_top(&mut self, move_type: ScrollType) -> bool {
+        let old = self.top;
+        //NOTE: page is never smaller than single line so we always make progress
+        let page = self.visible_height.max(1);
+        let new_top = match move_type {
+            ScrollType::Up => old.saturating_sub(1),
+            ScrollType::Down => old.saturating_add(1),
+            ScrollType::PageUp => old.saturating_sub(page),
+            ScrollType::PageDown => old.saturating_add(page),
+            ScrollType::Home => 0,
+            ScrollType::End => self.max_top,
+        };
+        self.top = new_top.min(self.max_top);
+        self.top != old
+    }
+
+    /// Must be called on each render so paging knows current viewport size
+    pub fn update(&mut self, content_height: usize, visible_height: usize) {
+        self.visible_height = visible_height;
+        self.max_top = content_height.saturating_sub(visible_height);
+        self.top = self.top.min(self.max_top);
+    }
+
+    pub fn draw(&self, f: &mut Frame, area: Rect, symbols: &Symbols) {
+        if self.max_top == 0 {
+            return;
+        }
+        let mut state = ScrollbarState::new(self.max_top).position(self.top);
+        f.render_stateful_widget(
+            Scrollbar::default()
+                .orientation(ScrollbarOrientation::VerticalRight)
+                .symbols(symbols.scrollbar.clone())
+                .begin_symbol(None)
+                .end_symbol(None),
+            area.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut state,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scroll(content_height: usize, visible_height: usize) -> VerticalScroll {
+        let mut scroll = VerticalScroll::new();
+        scroll.update(content_height, visible_height);
+        scroll
+    }
+
+    #[test]
+    fn should_scroll_by_single_line() {
+        let mut scroll = scroll(20, 5);
+        assert!(scroll.move_top(ScrollType::Down));
+        assert_eq!(scroll.get_top(), 1);
+        assert!(scroll.move_top(ScrollType::Up));
+        assert_eq!(scroll.get_top(), 0);
+        assert!(!scroll.move_top(ScrollType::Up));
+        assert_eq!(scroll.get_top(), 0);
+    }
+
+    #[test]
+    fn should_scroll_by_visible_page() {
+        let mut scroll = scroll(20, 5);
+        scroll.move_top(ScrollType::PageDown);
+        assert_eq!(scroll.get_top(), 5);
+        scroll.move_top(ScrollType::PageDown);
+        scroll.move_top(ScrollType::PageDown);
+        assert_eq!(scroll.get_top(), 15);
+        // cannot scroll past last page
+        assert!(!scroll.move_top(ScrollType::PageDown));
+        assert_eq!(scroll.get_top(), 15);
+        scroll.move_top(ScrollType::PageUp);
+        assert_eq!(scroll.get_top(), 10);
+    }
+
+    #[test]
+    fn should_jump_to_home_and_end() {
+        let mut scroll = scroll(20, 5);
+        scroll.move_top(ScrollType::End);
+        assert_eq!(scroll.get_top(), 15);
+        scroll.move_top(ScrollType::Home);
+        assert_eq!(scroll.get_top(), 0);
+    }
+
+    #[test]
+    fn should_not_scroll_when_content_fits() {
+        let mut scroll = scroll(3, 5);
+        assert!(!scroll.move_top(ScrollType::Down));
+        assert!(!scroll.move_top(ScrollType::PageDown));
+        assert!(!scroll.move_top(ScrollType::End));
+        assert_eq!(scroll.get_top(), 0);
+    }
+
+    #[test]
+    fn should_clamp_top_when_viewport_grows() {
+        let mut scroll = scroll(20, 5);
+        scroll.move_top(ScrollType::End);
+        scroll.update(20, 10);
+        assert_eq!(scroll.get_top(), 10);
+    }
+}
@@ -0,0 +1,159 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Clear, HighlightSpacing, List, ListState,
+    },
+    Frame,
+};
+
+use crate::{
+    config::KeyConfig,
+    processes::{kill_signals, KillSignal},
+    tui::symbols::Symbols,
+};
+
+use super::{centered_area, PopupEvent};
+
+pub struct SignalPopup {
+    signals: &'static [KillSignal],
+    state: ListState,
+    /// Processes that will receive selected signal, popup is visible when not empty
+    pids: Vec<u32>,
+}
+
+impl SignalPopup {
+    pub fn new() -> Self {
+        Self {
+            signals: kill_signals(),
+            state: ListState::default(),
+            pids: vec![],
+        }
+    }
+
+    pub fn show(&mut self, pids: Vec<u32>) {
+        self.pids = pids;
+        self.state.select(Some(0));
+    }
+
+    pub fn is_visible(&self) -> bool {
+        !self.pids.is_empty()
+    }
+
+    pub fn hide(&mut self) {
+        self.pids.clear();
+    }
+
+    pub fn event(&mut self, key: KeyEvent, keys: &KeyConfig) -> PopupEvent {
+        if self.pids.is_empty() {
+            return PopupEvent::NotHandled;
+        }
+        match key.code {
+            KeyCode::Esc => self.hide(),
+            _ if keys.popup_up.matches(&key) => self.select(|i| i.saturating_sub(1)),
+            _ if keys.popup_down.matches(&key) => self.select(|i| i.saturating_add(1)),
+            KeyCode::Home => self.select(|_| 0),
+            KeyCode::End => self.select(|_| usize::MAX),
+            _ if keys.enter.matches(&key) => {
+                let selected = self.state.selected().and_then(|i| self.signals.get(i));
+                if let Some(signal) = selected.copied() {
+                    let pids = std::mem::take(&mut self.pids);
+                    return PopupEvent::SignalSelected(signal, pids);
+                }
+            }
+            _ => {}
+        }
+        PopupEvent::Handled
+    }
+
+    //NOTE: list state clamps selection only on render so we do it ourselves
+    fn select(&mut self, index: impl Fn(usize) -> usize) {
+        let last = self.signals.len().saturating_sub(1);
+        let current = self.state.selected().unwrap_or(0);
+        self.state.select(Some(index(current).min(last)));
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect, symbols: &Symbols) {
+        let target = match self.pids.as_slice() {
+            [] => return,
+            [pid] => pid.to_string(),
+            pids => format!("{} processes", pids.len()),
+        };
+        let area = centered_area(area, 34, self.signals.len() as u16 + 2);
+        let items = self
+            .signals
+            .iter()
+            .map(|s| format!("{:<10}{:>3}", s.name, s.number));
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(
+                        Title::from(format!(" Send signal to {} ", target))
+                            .alignment(Alignment::Left)
+                            .position(Position::Top),
+                    )
+                    .title(
+                        Title::from(" <ENTER> send ")
+                            .alignment(Alignment::Right)
+                            .position(Position::Bottom),
+                    )
+                    .borders(Borders::ALL)
+                    .border_set(symbols.thick_border),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ")
+            .highlight_spacing(HighlightSpacing::Always);
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut self.state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn should_send_selected_signal_to_all_processes() {
+        let mut popup = SignalPopup::new();
+        assert!(matches!(
+            popup.event(key(KeyCode::Enter), &KeyConfig::default()),
+            PopupEvent::NotHandled
+        ));
+
+        popup.show(vec![10, 20]);
+        popup.event(key(KeyCode::Down), &KeyConfig::default());
+        match popup.event(key(KeyCode::Enter), &KeyConfig::default()) {
+            PopupEvent::SignalSelected(signal, pids) => {
+                assert_eq!(signal.name, kill_signals()[1].name);
+                assert_eq!(pids, vec![10, 20]);
+            }
+            _ => panic!("signal should be selected"),
+        }
+        assert!(matches!(
+            popup.event(key(KeyCode::Enter), &KeyConfig::default()),
+            PopupEvent::NotHandled
+        ));
+    }
+
+    #[test]
+    fn should_close_without_sending_signal() {
+        let mut popup = SignalPopup::new();
+        popup.show(vec![10]);
+        assert!(matches!(
+            popup.event(key(KeyCode::Esc), &KeyConfig::default()),
+            PopupEvent::Handled
+        ));
+        assert!(matches!(
+            popup.event(key(KeyCode::Enter), &KeyConfig::default()),
+            PopupEvent::NotHandled
+        ));
+    }
+}
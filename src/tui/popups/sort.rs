@@ -0,0 +1,179 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Clear, HighlightSpacing, List, ListState,
+    },
+    Frame,
+};
+
+use crate::{
+    config::KeyConfig,
+    processes::{Sort, SortColumn, SortOrder},
+    tui::symbols::Symbols,
+};
+
+use super::{centered_area, PopupEvent};
+
+pub struct SortPopup {
+    /// Columns that can be picked, popup is visible when not empty
+    columns: Vec<SortColumn>,
+    current: Option<Sort>,
+    state: ListState,
+}
+
+impl SortPopup {
+    pub fn new() -> Self {
+        Self {
+            columns: vec![],
+            current: None,
+            state: ListState::default(),
+        }
+    }
+
+    /// Column the table is sorted by is selected, so Enter toggles its order
+    pub fn show(&mut self, columns: Vec<SortColumn>, current: Option<Sort>) {
+        let selected = current
+            .and_then(|sort| columns.iter().position(|c| *c == sort.column))
+            .unwrap_or(0);
+        self.columns = columns;
+        self.current = current;
+        self.state.select(Some(selected));
+    }
+
+    pub fn is_visible(&self) -> bool {
+        !self.columns.is_empty()
+    }
+
+    pub fn hide(&mut self) {
+        self.columns.clear();
+    }
+
+    pub fn event(&mut self, key: KeyEvent, keys: &KeyConfig) -> PopupEvent {
+        if self.columns.is_empty() {
+            return PopupEvent::NotHandled;
+        }
+        match key.code {
+            KeyCode::Esc => self.hide(),
+            _ if keys.popup_up.matches(&key) => self.select(|i| i.saturating_sub(1)),
+            _ if keys.popup_down.matches(&key) => self.select(|i| i.saturating_add(1)),
+            KeyCode::Home => self.select(|_| 0),
+            KeyCode::End => self.select(|_| usize::MAX),
+            _ if keys.enter.matches(&key) => {
+                let selected = self.state.selected().and_then(|i| self.columns.get(i));
+                if let Some(column) = selected.copied() {
+                    self.hide();
+                    return PopupEvent::SortSelected(column);
+                }
+            }
+            _ => {}
+        }
+        PopupEvent::Handled
+    }
+
+    //NOTE: list state clamps selection only on render so we do it ourselves
+    fn select(&mut self, index: impl Fn(usize) -> usize) {
+        let last = self.columns.len().saturating_sub(1);
+        let current = self.state.selected().unwrap_or(0);
+        self.state.select(Some(index(current).min(last)));
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect, symbols: &Symbols) {
+        if self.columns.is_empty() {
+            return;
+        }
+        let area = centered_area(area, 24, self.columns.len() as u16 + 2);
+        let items = self.columns.iter().map(|column| {
+            let arrow = match self.current {
+                Some(sort) if sort.column == *column => match sort.order {
+                    SortOrder::Ascending => format!(" {}", symbols.ascending),
+                    SortOrder::Descending => format!(" {}", symbols.descending),
+                },
+                _ => String::new(),
+            };
+            format!("{}{}", column_title(*column), arrow)
+        });
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(
+                        Title::from(" Sort by ")
+                            .alignment(Alignment::Left)
+                            .position(Position::Top),
+                    )
+                    .title(
+                        Title::from(" <ENTER> sort ")
+                            .alignment(Alignment::Right)
+                            .position(Position::Bottom),
+                    )
+                    .borders(Borders::ALL)
+                    .border_set(symbols.thick_border),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ")
+            .highlight_spacing(HighlightSpacing::Always);
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut self.state);
+    }
+}
+
+/// Same titles as in table header
+fn column_title(column: SortColumn) -> &'static str {
+    match column {
+        SortColumn::User => "USER",
+        SortColumn::Pid => "PID",
+        SortColumn::Parent => "PARENT",
+        SortColumn::StartTime => "STARTED",
+        SortColumn::RunTime => "TIME",
+        SortColumn::Memory => "MEM",
+        SortColumn::Cpu => "CPU%",
+        SortColumn::Threads => "THREADS",
+        SortColumn::Cmd => "CMD",
+        SortColumn::CmdPath => "CMD_PATH",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn should_select_sort_column_starting_from_current_one() {
+        let mut popup = SortPopup::new();
+        let keys = KeyConfig::default();
+        assert!(matches!(
+            popup.event(key(KeyCode::Enter), &keys),
+            PopupEvent::NotHandled
+        ));
+
+        let current = Sort {
+            column: SortColumn::Memory,
+            order: SortOrder::Descending,
+        };
+        popup.show(
+            vec![SortColumn::Pid, SortColumn::Memory, SortColumn::Cpu],
+            Some(current),
+        );
+        match popup.event(key(KeyCode::Enter), &keys) {
+            PopupEvent::SortSelected(column) => assert_eq!(column, SortColumn::Memory),
+            _ => panic!("column should be selected"),
+        }
+        assert!(!popup.is_visible());
+
+        popup.show(vec![SortColumn::Pid, SortColumn::Memory], None);
+        popup.event(key(KeyCode::Down), &keys);
+        popup.event(key(KeyCode::Down), &keys);
+        match popup.event(key(KeyCode::Enter), &keys) {
+            PopupEvent::SortSelected(column) => assert_eq!(column, SortColumn::Memory),
+            _ => panic!("column should be selected"),
+        }
+    }
+}
@@ -1,69 +1,294 @@
-use std::{borrow::Cow, rc::Rc};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
+    ops::Range,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
-use crossterm::event::KeyEvent;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
     layout::{Alignment, Constraint, Layout, Margin, Rect},
-    style::{palette::tailwind, Color, Modifier, Style, Stylize},
+    style::{Color, Modifier, Style, Stylize},
     text::{Line, Span, Text},
     widgets::{
         block::{Position, Title},
-        Block, BorderType, Borders, HighlightSpacing, Paragraph, Row, Scrollbar,
-        ScrollbarOrientation, ScrollbarState, Table, TableState, Wrap,
+        Block, Borders, Cell, HighlightSpacing, Paragraph, Row, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Sparkline, Table, TableState, Wrap,
     },
     Frame,
 };
 use tui_textarea::TextArea;
 
-use crate::processes::{Process, ProcessSearchResults, SearchBy};
-
-pub struct Theme {
-    row_fg: Color,
-    selected_style_fg: Color,
-    normal_row_color: Color,
-    alt_row_color: Color,
-    process_table_border_color: Color,
-}
+use crate::{
+    config::{Column, ColumnsConfig, EnterAction, KeyConfig, StartTimeFormat},
+    processes::{
+        process_connections, process_environment, process_open_files, CaseSensitivity, Connection,
+        KillSignal, OpenFile, Owner, Process, ProcessSearchResults, ResultsTotals, SearchBy, Sort,
+        SortColumn, SortOrder,
+    },
+};
 
-impl Theme {
-    pub fn new() -> Self {
-        Self {
-            row_fg: tailwind::SLATE.c200,
-            selected_style_fg: tailwind::BLUE.c400,
-            normal_row_color: tailwind::SLATE.c950,
-            alt_row_color: tailwind::SLATE.c900,
-            process_table_border_color: tailwind::BLUE.c400,
-        }
-    }
-}
+use super::{
+    popups::{BookmarksPopup, ConfirmKillPopup, MsgPopup, PopupEvent, SignalPopup, SortPopup},
+    start_time::format_start_time,
+    symbols::Symbols,
+    theme::Theme,
+    usage_history::{UsageHistory, UsageSamples},
+};
 
 pub struct Tui {
     theme: Theme,
     process_table: TableState,
     process_table_scroll_state: ScrollbarState,
     process_table_number_of_items: usize,
-    process_details_scroll_state: ScrollbarState,
-    process_details_scroll_offset: u16,
-    process_details_number_of_lines: u16,
+    /// Rows visible in the table at last render, selection keeps its relative position when it changes
+    process_table_rows: u16,
+    details_scroll: [DetailsScroll; DetailsTab::ALL.len()],
+    //NOTE: connections are read only for selected process, they are cached until selection or results change
+    connections: Option<(u32, Vec<Connection>)>,
+    details_tab: DetailsTab,
+    //NOTE: environment is read only when its tab is shown, cached the same way as connections
+    environment: Option<(u32, Result<Vec<String>>)>,
+    open_files: Option<(u32, Result<Vec<OpenFile>>)>,
+    /// Open files listed in popup and details tab, the rest is only counted
+    open_files_limit: usize,
     search_area: TextArea<'static>,
-    error_message: Option<&'static str>,
+    error_message: Option<String>,
+    /// Confirmation of finished action, i.e. copying, it is cleared with next key
+    info_message: Option<String>,
+    msg_popup: MsgPopup,
+    /// Message popup shows details of this process, they are updated on refresh
+    details_popup_pid: Option<u32>,
+    signal_popup: SignalPopup,
+    confirm_kill_popup: ConfirmKillPopup,
+    bookmarks_popup: BookmarksPopup,
+    sort_popup: SortPopup,
+    sort: Option<Sort>,
+    regex_search: bool,
+    /// Query chars may be apart in the name, otherwise name has to contain the query
+    fuzzy_search: bool,
+    case_sensitivity: CaseSensitivity,
+    search_mode: SearchBy,
+    tree_view: bool,
+    /// Processes running the same executable are shown as one row
+    grouped: bool,
+    //NOTE: pids are stored so marks survive sorting and refresh
+    marked: HashSet<u32>,
+    keys: KeyConfig,
+    help_text: String,
+    normal_mode_help_text: String,
+    columns: ColumnsConfig,
+    table_columns: Vec<Column>,
+    details_hidden: bool,
+    wrap_selection: bool,
+    symbols: Symbols,
+    /// Processes are scanned in background
+    refreshing: bool,
+    /// Results of the first scan were searched, until then empty table is still waiting for them
+    searched: bool,
+    /// Plain keys navigate the table like in vim instead of typing into search
+    normal_mode: bool,
+    /// Row and time of the last click, used to detect double click
+    last_click: Option<(usize, Instant)>,
+    usage_history: UsageHistory,
+    /// Chars of CMD and CMD_PATH cells scrolled out to the left, so truncated paths can be read in the table
+    cmd_scroll: usize,
+}
+
+/// Chars scrolled by one press of h/l in normal mode
+const CMD_SCROLL_STEP: usize = 8;
+
+/// Usage history is shown next to info only when details are at least this wide
+const MIN_WIDTH_WITH_USAGE_HISTORY: u16 = 100;
+const USAGE_HISTORY_WIDTH: u16 = 32;
+const USAGE_LABEL_WIDTH: u16 = 4;
+
+/// Content shown in process details panel
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DetailsTab {
+    #[default]
+    Info,
+    Environment,
+    Files,
+    Network,
+}
+
+impl DetailsTab {
+    const ALL: [DetailsTab; 4] = [Self::Info, Self::Environment, Self::Files, Self::Network];
+
+    fn next(self) -> Self {
+        Self::ALL[(self as usize + 1) % Self::ALL.len()]
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Info => "Info",
+            Self::Environment => "Environment",
+            Self::Files => "Files",
+            Self::Network => "Network",
+        }
+    }
+}
+
+/// Every details tab keeps its own scroll, line count is the one of its last render
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct DetailsScroll {
+    offset: u16,
+    number_of_lines: u16,
 }
 
 impl Tui {
-    pub fn new(search_text: String) -> Self {
-        let mut search_area = TextArea::from(search_text.lines());
-        search_area.move_cursor(tui_textarea::CursorMove::End);
+    pub fn new(
+        search_text: String,
+        bookmarks: BTreeMap<String, String>,
+        keys: KeyConfig,
+        columns: ColumnsConfig,
+        theme: Theme,
+    ) -> Self {
         Self {
             process_table: TableState::default(),
             process_table_scroll_state: ScrollbarState::new(0),
-            theme: Theme::new(),
+            theme,
             process_table_number_of_items: 0,
-            process_details_scroll_offset: 0,
-            process_details_number_of_lines: 0,
-            //NOTE: we don't update this, value 1 means that this should be rendered
-            process_details_scroll_state: ScrollbarState::new(1),
-            search_area,
+            process_table_rows: 0,
+            details_scroll: Default::default(),
+            connections: None,
+            details_tab: DetailsTab::default(),
+            environment: None,
+            open_files: None,
+            open_files_limit: usize::MAX,
+            search_area: search_area(&search_text),
             error_message: None,
+            info_message: None,
+            msg_popup: MsgPopup::new(),
+            details_popup_pid: None,
+            signal_popup: SignalPopup::new(),
+            confirm_kill_popup: ConfirmKillPopup::new(),
+            bookmarks_popup: BookmarksPopup::new(bookmarks),
+            sort_popup: SortPopup::new(),
+            sort: None,
+            regex_search: false,
+            fuzzy_search: true,
+            case_sensitivity: CaseSensitivity::default(),
+            search_mode: SearchBy::Cmd,
+            tree_view: false,
+            grouped: false,
+            marked: HashSet::new(),
+            help_text: help_text(&keys, EnterAction::default()),
+            normal_mode_help_text: normal_mode_help_text(&keys),
+            keys,
+            table_columns: columns.table_columns(),
+            details_hidden: false,
+            wrap_selection: true,
+            symbols: Symbols::unicode(),
+            refreshing: false,
+            searched: false,
+            normal_mode: false,
+            last_click: None,
+            cmd_scroll: 0,
+            usage_history: UsageHistory::default(),
+            columns,
+        }
+    }
+
+    /// Moving selection past the last or the first row continues from the other end
+    pub fn with_wrap_selection(mut self, wrap_selection: bool) -> Self {
+        self.wrap_selection = wrap_selection;
+        self
+    }
+
+    /// Substring search is used from the start with --exact
+    pub fn with_fuzzy_search(mut self, fuzzy_search: bool) -> Self {
+        self.fuzzy_search = fuzzy_search;
+        self
+    }
+
+    /// Open files listed for a process, the rest is only counted
+    pub fn with_open_files_limit(mut self, open_files_limit: usize) -> Self {
+        self.open_files_limit = open_files_limit;
+        self
+    }
+
+    /// Help bar tells what Enter does
+    pub fn with_enter_action(mut self, enter_action: EnterAction) -> Self {
+        self.help_text = help_text(&self.keys, enter_action);
+        self
+    }
+
+    /// Details panel can start hidden, i.e. with 'hide_details' in config file
+    pub fn with_details_hidden(mut self, details_hidden: bool) -> Self {
+        self.details_hidden = details_hidden;
+        self
+    }
+
+    /// Borders, scrollbars and arrows are drawn with ascii chars, i.e. with --ascii
+    pub fn with_ascii(mut self, ascii: bool) -> Self {
+        if ascii {
+            self.symbols = Symbols::ascii();
+        }
+        self
+    }
+
+    /// Sorting the table starts with, i.e. given with --sort
+    pub fn with_sort(mut self, sort: Option<Sort>) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Cpu usage and memory of selected process are sampled after every refresh
+    pub fn sample_usage(&mut self, prc: &Process) {
+        self.usage_history
+            .sample(prc.pid, prc.cpu_usage, prc.memory);
+    }
+
+    pub fn set_refreshing(&mut self, refreshing: bool) {
+        self.refreshing = refreshing;
+    }
+
+    pub fn toggle_normal_mode(&mut self) {
+        self.set_normal_mode(!self.normal_mode);
+    }
+
+    //NOTE: cursor is hidden in normal mode, so it is clear that keys are not typed
+    pub fn is_normal_mode(&self) -> bool {
+        self.normal_mode
+    }
+
+    fn set_normal_mode(&mut self, normal_mode: bool) {
+        self.normal_mode = normal_mode;
+        let cursor_style = match normal_mode {
+            true => Style::default(),
+            false => Style::default().add_modifier(Modifier::REVERSED),
+        };
+        self.search_area.set_cursor_style(cursor_style);
+    }
+
+    /// In normal mode j/k move selection, h/l scroll commands, g/G jump to the first/last row and '/' or 'i' go back to search.
+    /// Returns false for keys that are handled as usual, i.e. with ctrl or alt
+    pub fn handle_normal_mode_key(&mut self, key: KeyEvent) -> bool {
+        //NOTE: shift is part of 'G'
+        if !self.normal_mode || !(key.modifiers - KeyModifiers::SHIFT).is_empty() {
+            return false;
+        }
+        match key.code {
+            KeyCode::Char('j') => self.select_next_row(1),
+            KeyCode::Char('k') => self.select_previous_row(1),
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.cmd_scroll = self.cmd_scroll.saturating_sub(CMD_SCROLL_STEP)
+            }
+            KeyCode::Char('l') | KeyCode::Right => {
+                self.cmd_scroll = self.cmd_scroll.saturating_add(CMD_SCROLL_STEP)
+            }
+            KeyCode::Char('g') => self.select_first_row(),
+            KeyCode::Char('G') => self.select_last_row(),
+            KeyCode::Char('/') | KeyCode::Char('i') => self.set_normal_mode(false),
+            //NOTE: other characters would change the query unnoticed
+            KeyCode::Char(_) | KeyCode::Backspace | KeyCode::Delete => {}
+            _ => return false,
         }
+        true
     }
 
     pub fn select_first_row(&mut self) {
@@ -76,29 +301,44 @@ impl Tui {
         self.select_row_by_index(index);
     }
 
+    /// Moves selection down, going past the last row wraps to the first one or stops at the last one
     pub fn select_next_row(&mut self, step_size: usize) {
-        let next_row_index = self.process_table.selected().map(|i| {
-            let mut i = i + step_size;
-            if i >= self.process_table_number_of_items {
-                i = 0
-            }
-            i
-        });
+        let last = self.process_table_number_of_items.checked_sub(1);
+        let next_row_index = match (self.process_table.selected(), last) {
+            (_, None) => None,
+            (None, Some(_)) => Some(0),
+            (Some(i), Some(last)) => match i.saturating_add(step_size) {
+                i if i <= last => Some(i),
+                _ if self.wrap_selection => Some(0),
+                _ => Some(last),
+            },
+        };
         self.select_row_by_index(next_row_index);
     }
 
+    /// Details of newly selected process start with the info tab
     pub fn select_row_by_index(&mut self, index: Option<usize>) {
+        if index != self.process_table.selected() {
+            self.details_tab = DetailsTab::Info;
+        }
         self.process_table.select(index);
         self.process_table_scroll_state =
             self.process_table_scroll_state.position(index.unwrap_or(0));
         self.reset_process_detals_scroll();
     }
 
+    /// Moves selection up, going past the first row wraps to the last one or stops at the first one
     pub fn select_previous_row(&mut self, step_size: usize) {
-        let previous_index = self.process_table.selected().map(|i| {
-            let i = i.wrapping_sub(step_size);
-            i.clamp(0, self.process_table_number_of_items.saturating_sub(1))
-        });
+        let last = self.process_table_number_of_items.checked_sub(1);
+        let previous_index = match (self.process_table.selected(), last) {
+            (_, None) => None,
+            (None, Some(last)) => Some(last),
+            (Some(i), Some(last)) => match i.checked_sub(step_size) {
+                Some(i) => Some(i),
+                None if self.wrap_selection => Some(last),
+                None => Some(0),
+            },
+        };
         self.select_row_by_index(previous_index);
     }
 
@@ -106,39 +346,304 @@ impl Tui {
         self.search_area.input(input);
     }
 
+    /// Click selects process, wheel scrolls table or details under the cursor.
+    /// Returns true when row was double clicked. Mouse is ignored while popup is open
+    pub fn handle_mouse(&mut self, mouse: MouseEvent, frame: &mut Frame) -> bool {
+        if self.is_popup_visible() {
+            return false;
+        }
+        let rects = layout_rects(frame.area(), self.details_hidden);
+        let (table_area, details_area) = (rects[1], rects[2]);
+        let position = (mouse.column, mouse.row).into();
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if table_area.contains(position) => {
+                let index = table_row_at(
+                    table_area,
+                    self.process_table.offset(),
+                    self.process_table_number_of_items,
+                    mouse.row,
+                );
+                let Some(index) = index else {
+                    return false;
+                };
+                self.select_row_by_index(Some(index));
+                let now = Instant::now();
+                if is_double_click(self.last_click, index, now) {
+                    //NOTE: third click starts a new double click
+                    self.last_click = None;
+                    return true;
+                }
+                self.last_click = Some((index, now));
+            }
+            MouseEventKind::ScrollDown if table_area.contains(position) => self.scroll_table(true),
+            MouseEventKind::ScrollUp if table_area.contains(position) => self.scroll_table(false),
+            MouseEventKind::ScrollDown if details_area.contains(position) => {
+                self.process_details_down(frame)
+            }
+            MouseEventKind::ScrollUp if details_area.contains(position) => {
+                self.process_details_up()
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Moves selection by one row, unlike keys it does not wrap around
+    fn scroll_table(&mut self, down: bool) {
+        let last = self.process_table_number_of_items.checked_sub(1);
+        let index = match (self.process_table.selected(), last) {
+            (_, None) => None,
+            (None, Some(_)) => Some(0),
+            (Some(i), Some(last)) if down => Some((i + 1).min(last)),
+            (Some(i), Some(_)) => Some(i.saturating_sub(1)),
+        };
+        self.select_row_by_index(index);
+    }
+
+    fn is_popup_visible(&self) -> bool {
+        self.msg_popup.is_visible()
+            || self.confirm_kill_popup.is_visible()
+            || self.signal_popup.is_visible()
+            || self.bookmarks_popup.is_visible()
+            || self.sort_popup.is_visible()
+    }
+
+    pub fn handle_popup_input(&mut self, input: KeyEvent) -> PopupEvent {
+        match self.msg_popup.event(input, &self.keys) {
+            PopupEvent::NotHandled => match self.confirm_kill_popup.event(input, &self.keys) {
+                PopupEvent::NotHandled => match self.signal_popup.event(input, &self.keys) {
+                    PopupEvent::NotHandled => match self.bookmarks_popup.event(input, &self.keys) {
+                        PopupEvent::NotHandled => self.sort_popup.event(input, &self.keys),
+                        handled => handled,
+                    },
+                    handled => handled,
+                },
+                handled => handled,
+            },
+            handled => handled,
+        }
+    }
+
+    pub fn keys(&self) -> &KeyConfig {
+        &self.keys
+    }
+
+    pub fn show_signal_popup(&mut self, pids: Vec<u32>) {
+        self.signal_popup.show(pids);
+    }
+
+    pub fn show_confirm_kill_popup(&mut self, pids: Vec<u32>, msg: String) {
+        self.confirm_kill_popup.show(pids, msg);
+    }
+
+    pub fn show_confirm_signal_popup(&mut self, pids: Vec<u32>, signal: KillSignal, msg: String) {
+        self.confirm_kill_popup.show_with_signal(pids, signal, msg);
+    }
+
+    pub fn show_bookmarks_popup(&mut self) {
+        match self.bookmarks_popup.is_empty() {
+            true => self.set_error_message("No bookmarks defined in config"),
+            false => self.bookmarks_popup.show(),
+        }
+    }
+
+    pub fn show_sort_popup(&mut self) {
+        self.sort_popup.show(self.sortable_columns(), self.sort);
+    }
+
+    /// Hidden columns cannot be sorted, order would change without visible reason
+    fn sortable_columns(&self) -> Vec<SortColumn> {
+        SortColumn::ALL
+            .into_iter()
+            .filter(|column| self.is_column_shown(*column))
+            .collect()
+    }
+
+    fn is_column_shown(&self, column: SortColumn) -> bool {
+        self.table_columns
+            .iter()
+            .any(|shown| column_sort(*shown) == Some(column))
+    }
+
+    /// Sorts by the column in ascending order, picking the same column again toggles the order
+    pub fn sort_by(&mut self, column: SortColumn) {
+        self.sort = match self.sort {
+            Some(sort) if sort.column == column => Some(Sort {
+                column,
+                order: sort.order.toggle(),
+            }),
+            _ => Some(Sort {
+                column,
+                order: SortOrder::Ascending,
+            }),
+        };
+    }
+
+    pub fn set_search_text(&mut self, search_text: &str) {
+        self.search_area = search_area(search_text);
+    }
+
+    /// Variables are listed one per line, error is shown instead when they cannot be read
+    pub fn show_environment_popup(&mut self, pid: u32, variables: Result<Vec<String>>) {
+        let msg = match variables {
+            Ok(variables) if variables.is_empty() => {
+                "Process has no environment variables".to_string()
+            }
+            Ok(variables) => variables.join("\n"),
+            Err(err) => err.to_string(),
+        };
+        self.msg_popup
+            .show(format!("Environment of PID {}", pid), msg);
+    }
+
+    /// Only first files up to the limit are listed, processes can have thousands of them
+    pub fn show_open_files_popup(&mut self, pid: u32, files: Result<Vec<OpenFile>>) {
+        let msg = match files {
+            Ok(files) => open_files_text(&files, self.open_files_limit),
+            Err(err) => err.to_string(),
+        };
+        self.msg_popup
+            .show(format!("Open files of PID {}", pid), msg);
+    }
+
+    /// Result of signal sent to several processes, every failure is listed with its reason
+    pub fn show_kill_summary_popup(&mut self, signal: &str, killed: &[u32], errors: &[String]) {
+        self.msg_popup.show(
+            format!(
+                "{} sent to {} of {} processes",
+                signal,
+                killed.len(),
+                killed.len() + errors.len()
+            ),
+            kill_summary_text(killed, errors),
+        );
+    }
+
+    /// All known fields of the process, one per line, popup takes whole screen for long command lines
+    pub fn show_details_popup(&mut self, prc: &Process, args: Option<Vec<String>>) {
+        let text = self.details_popup_text(prc, args);
+        self.msg_popup
+            .show_full_screen(format!("Details of PID {}", prc.pid), text);
+        self.details_popup_pid = Some(prc.pid);
+    }
+
+    /// Content of open details popup is replaced, i.e. after refresh
+    pub fn update_details_popup(&mut self, prc: &Process, args: Option<Vec<String>>) {
+        let text = self.details_popup_text(prc, args);
+        self.msg_popup
+            .update(format!("Details of PID {}", prc.pid), text);
+    }
+
+    /// Process whose details popup is open
+    pub fn details_popup_pid(&self) -> Option<u32> {
+        self.details_popup_pid
+            .filter(|_| self.msg_popup.is_visible())
+    }
+
+    fn details_popup_text(&mut self, prc: &Process, args: Option<Vec<String>>) -> String {
+        let connections = match self.connections_of(Some(prc.pid)) {
+            [] => prc.connections.clone(),
+            connections => connections.to_vec(),
+        };
+        process_details_text(prc, args.as_deref(), &connections, self.columns.start_time)
+    }
+
+    pub fn show_keybindings_help(&mut self) {
+        self.msg_popup
+            .show("Keybindings", keybindings_help(&self.keys));
+    }
+
     pub fn enter_char(&mut self, new_char: char) {
         self.search_area.insert_char(new_char);
     }
 
     pub fn process_details_down(&mut self, frame: &mut Frame) {
-        let rects = layout_rects(frame);
+        if self.details_hidden {
+            return;
+        }
+        let rects = layout_rects(frame.area(), self.details_hidden);
         let process_details_area = rects[2];
-        let area_content_height = process_details_area.height - 2;
-        let content_scrolled =
-            self.process_details_number_of_lines - self.process_details_scroll_offset;
+        let area_content_height = process_details_area.height.saturating_sub(2);
+        let scroll = self.details_scroll();
+        let content_scrolled = scroll.number_of_lines.saturating_sub(scroll.offset);
 
         if content_scrolled > area_content_height {
-            self.process_details_scroll_offset =
-                self.process_details_scroll_offset.saturating_add(1);
+            scroll.offset = scroll.offset.saturating_add(1);
         }
     }
 
     pub fn process_details_up(&mut self) {
-        self.process_details_scroll_offset = self.process_details_scroll_offset.saturating_sub(1);
+        if self.details_hidden {
+            return;
+        }
+        let scroll = self.details_scroll();
+        scroll.offset = scroll.offset.saturating_sub(1);
+    }
+
+    pub fn cycle_start_time_format(&mut self) {
+        self.columns.start_time = self.columns.start_time.next();
+    }
+
+    /// Collapses details panel, so the table gets more rows on small screens.
+    /// Panel shown again starts from the top
+    pub fn toggle_details(&mut self) {
+        self.details_hidden = !self.details_hidden;
+        if !self.details_hidden {
+            self.reset_process_detals_scroll();
+        }
+    }
+
+    /// Rows whose open fds are shown, selected one in details and the ones on screen when FDS column is visible.
+    /// Rows on screen are known from the last render
+    pub fn rows_showing_open_fds(&self, number_of_rows: usize) -> Vec<usize> {
+        let mut rows: Vec<usize> = match self.table_columns.contains(&Column::Fds) {
+            true => {
+                let offset = self.process_table.offset();
+                (offset..offset + self.process_table_rows as usize).collect()
+            }
+            false => vec![],
+        };
+        if !self.details_hidden {
+            rows.extend(self.process_table.selected());
+        }
+        rows.retain(|row| *row < number_of_rows);
+        rows
+    }
+
+    /// Switches details panel between process info, environment variables, open files and connections,
+    /// every tab keeps its scroll
+    pub fn cycle_details_tab(&mut self) {
+        self.details_tab = self.details_tab.next();
+    }
+
+    fn details_scroll(&mut self) -> &mut DetailsScroll {
+        &mut self.details_scroll[self.details_tab as usize]
     }
 
     fn reset_process_detals_scroll(&mut self) {
-        self.process_details_scroll_offset = 0;
+        for scroll in self.details_scroll.iter_mut() {
+            scroll.offset = 0;
+        }
     }
 
-    pub fn set_error_message(&mut self, message: &'static str) {
-        self.error_message = Some(message);
+    pub fn set_error_message(&mut self, message: impl Into<String>) {
+        self.info_message = None;
+        self.error_message = Some(message.into());
     }
 
     pub fn reset_error_message(&mut self) {
         self.error_message = None;
     }
 
+    pub fn set_info_message(&mut self, message: impl Into<String>) {
+        self.error_message = None;
+        self.info_message = Some(message.into());
+    }
+
+    pub fn reset_info_message(&mut self) {
+        self.info_message = None;
+    }
+
     pub fn delete_char(&mut self) {
         self.search_area.delete_char();
     }
@@ -149,33 +654,236 @@ impl Tui {
 
     pub fn update_process_table_number_of_items(&mut self, number_of_items: usize) {
         self.process_table_number_of_items = number_of_items;
+        self.searched = true;
+        self.connections = None;
+        self.environment = None;
+        self.open_files = None;
         self.process_table_scroll_state = self
             .process_table_scroll_state
             .content_length(number_of_items.saturating_sub(1));
-        if number_of_items == 0 {
-            self.process_table.select(None);
+        //NOTE: first row of new results is another process, so its details start with info tab too
+        self.process_table.select(None);
+        self.select_row_by_index((number_of_items > 0).then_some(0));
+    }
+
+    pub fn details_tab(&self) -> DetailsTab {
+        self.details_tab
+    }
+
+    /// Process selected again after refresh keeps the tab it had
+    pub fn set_details_tab(&mut self, details_tab: DetailsTab) {
+        self.details_tab = details_tab;
+    }
+
+    pub fn sort(&self) -> Option<Sort> {
+        self.sort
+    }
+
+    pub fn cycle_sort_column(&mut self) {
+        let mut column = SortColumn::next(self.sort.map(|s| s.column));
+        //NOTE: hidden columns are skipped, order would change without visible reason
+        while column.is_some_and(|column| !self.is_column_shown(column)) {
+            column = SortColumn::next(column);
+        }
+        self.sort = column.map(|column| Sort {
+            column,
+            order: SortOrder::Ascending,
+        });
+    }
+
+    pub fn toggle_sort_order(&mut self) {
+        if let Some(sort) = self.sort.as_mut() {
+            sort.order = sort.order.toggle();
+        }
+    }
+
+    fn sortable_header(&self, name: &str, column: SortColumn) -> String {
+        match self.sort {
+            Some(sort) if sort.column == column => {
+                let arrow = match sort.order {
+                    SortOrder::Ascending => self.symbols.ascending,
+                    SortOrder::Descending => self.symbols.descending,
+                };
+                format!("{} {}", name, arrow)
+            }
+            _ => name.to_string(),
+        }
+    }
+
+    pub fn regex_search(&self) -> bool {
+        self.regex_search
+    }
+
+    pub fn toggle_regex_search(&mut self) {
+        self.regex_search = !self.regex_search;
+    }
+
+    pub fn fuzzy_search(&self) -> bool {
+        self.fuzzy_search
+    }
+
+    pub fn toggle_fuzzy_search(&mut self) {
+        self.fuzzy_search = !self.fuzzy_search;
+    }
+
+    /// Unmarks processes when all of them are marked, otherwise marks all of them
+    pub fn toggle_marks(&mut self, pids: &[u32]) {
+        if pids.iter().all(|pid| self.marked.contains(pid)) {
+            pids.iter().for_each(|pid| {
+                self.marked.remove(pid);
+            });
         } else {
-            self.process_table.select(Some(0));
+            self.marked.extend(pids);
         }
     }
 
+    pub fn mark_all(&mut self, pids: impl IntoIterator<Item = u32>) {
+        self.marked.extend(pids);
+    }
+
+    pub fn has_marks(&self) -> bool {
+        !self.marked.is_empty()
+    }
+
+    /// Marks of processes that are gone are dropped, so they are not killed by accident
+    pub fn retain_marks(&mut self, keep: impl Fn(u32) -> bool) {
+        self.marked.retain(|pid| keep(*pid));
+    }
+
+    pub fn marked_pids(&self) -> Vec<u32> {
+        let mut pids: Vec<u32> = self.marked.iter().copied().collect();
+        pids.sort_unstable();
+        pids
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+
+    pub fn tree_view(&self) -> bool {
+        self.tree_view
+    }
+
+    pub fn toggle_tree_view(&mut self) {
+        self.tree_view = !self.tree_view;
+    }
+
+    pub fn grouped(&self) -> bool {
+        self.grouped
+    }
+
+    pub fn toggle_grouped(&mut self) {
+        self.grouped = !self.grouped;
+    }
+
+    pub fn case_sensitivity(&self) -> CaseSensitivity {
+        self.case_sensitivity
+    }
+
+    pub fn cycle_case_sensitivity(&mut self) {
+        self.case_sensitivity = self.case_sensitivity.next();
+    }
+
+    pub fn search_mode(&self) -> SearchBy {
+        self.search_mode
+    }
+
+    pub fn cycle_search_mode(&mut self) {
+        self.search_mode = self.search_mode.next_mode();
+    }
+
     pub fn search_input_text(&self) -> &str {
         &self.search_area.lines()[0]
     }
 
+    /// Right arrow moves cursor while there is text after it, so it can expand group only at the end
+    pub fn search_cursor_at_end(&self) -> bool {
+        self.search_area.cursor().1 >= self.search_input_text().chars().count()
+    }
+
     pub fn render_ui(&mut self, search_results: &ProcessSearchResults, frame: &mut Frame) {
-        let rects = layout_rects(frame);
+        let rects = layout_rects(frame.area(), self.details_hidden);
+        self.keep_selection_position(table_viewport_rows(rects[1]));
 
         self.render_search_input(frame, rects[0]);
         self.render_process_table(frame, search_results, rects[1]);
-        self.render_process_details(frame, search_results, rects[2]);
+        if !self.details_hidden {
+            self.render_process_details(frame, search_results, rects[2]);
+        }
+
+        let message = match (&self.error_message, &self.info_message) {
+            (Some(error), _) => Span::from(error.as_str()).fg(Color::Red),
+            (None, Some(info)) => Span::from(info.as_str()).fg(self.theme.row_fg),
+            (None, None) => Span::raw(""),
+        };
+        let help_text = match self.normal_mode {
+            true => &self.normal_mode_help_text,
+            false => &self.help_text,
+        };
+        render_help(frame, message, help_text, rects[3]);
+        self.signal_popup.render(frame, frame.area(), &self.symbols);
+        self.confirm_kill_popup
+            .render(frame, frame.area(), &self.symbols);
+        self.bookmarks_popup
+            .render(frame, frame.area(), &self.symbols);
+        self.sort_popup.render(frame, frame.area(), &self.symbols);
+        self.msg_popup
+            .render(frame, frame.area(), &self.symbols, &self.keys);
+    }
 
-        render_help(frame, self.error_message, rects[3]);
+    /// Table viewport changes with terminal size, without adjusting the offset
+    /// the table would show different rows or scroll selection out of view
+    fn keep_selection_position(&mut self, rows: u16) {
+        let previous_rows = std::mem::replace(&mut self.process_table_rows, rows);
+        let Some(selected) = self.process_table.selected() else {
+            return;
+        };
+        if previous_rows == 0 || previous_rows == rows {
+            return;
+        }
+        *self.process_table.offset_mut() = offset_after_resize(
+            selected,
+            self.process_table.offset(),
+            previous_rows,
+            rows,
+            self.process_table_number_of_items,
+        );
     }
 
     fn render_search_input(&self, f: &mut Frame, area: Rect) {
-        let rects = Layout::horizontal([Constraint::Length(2), Constraint::Min(2)]).split(area);
-        f.render_widget(Paragraph::new("> "), rects[0]);
+        let case_label = Span::styled(
+            case_sensitivity_label(self.case_sensitivity),
+            Style::new().add_modifier(Modifier::DIM),
+        );
+        let mut prompt = match self.regex_search {
+            true => Line::from(vec![case_label, Span::raw("re> ")]),
+            //NOTE: search mode does not apply to regex search
+            false => Line::from(vec![
+                case_label,
+                Span::raw(match self.fuzzy_search {
+                    true => "fuzzy> ",
+                    false => "exact> ",
+                }),
+                Span::styled(
+                    format!(" {} ", search_mode_badge(self.search_mode)),
+                    Style::new()
+                        .fg(self.theme.normal_row_color)
+                        .bg(self.theme.selected_style_fg)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" "),
+            ]),
+        };
+        if self.normal_mode {
+            let label = Span::styled("NORMAL ", Style::new().add_modifier(Modifier::BOLD));
+            prompt.spans.insert(0, label);
+        }
+        let rects = Layout::horizontal([
+            Constraint::Length(prompt.width() as u16),
+            Constraint::Min(2),
+        ])
+        .split(area);
+        f.render_widget(Paragraph::new(prompt), rects[0]);
         f.render_widget(&self.search_area, rects[1]);
     }
 
@@ -185,73 +893,100 @@ impl Tui {
         search_results: &ProcessSearchResults,
         area: Rect,
     ) {
-        let (dynamic_header, value_getter) = dynamic_search_column(search_results);
+        let cmd_path_shown = self.table_columns.contains(&Column::CmdPath);
+        let (dynamic_header, value_getter) = dynamic_search_column(search_results, cmd_path_shown);
+        let highlight = Style::new()
+            .fg(self.theme.match_highlight)
+            .add_modifier(Modifier::BOLD);
+        let constraints = match self.columns.fixed_widths {
+            true => process_table_columns(&self.table_columns),
+            false => self.fitted_table_columns(search_results, area),
+        };
+        let widths = process_table_column_widths(&constraints, area);
+        //NOTE: commands are not scrolled past the end of the longest one, so scrolling back starts right away
+        if self.cmd_scroll > 0 {
+            let longest = search_results
+                .iter()
+                .map(|prc| {
+                    let path = prc.cmd_path.as_deref().unwrap_or("");
+                    prc.cmd.chars().count().max(path.chars().count())
+                })
+                .max()
+                .unwrap_or(0);
+            self.cmd_scroll = self.cmd_scroll.min(longest);
+        }
         let rows = search_results.iter().enumerate().map(|(i, data)| {
             let color = match i % 2 {
+                _ if self.marked.contains(&data.pid) => self.theme.marked_row_color,
+                _ if data.pinned => self.theme.pinned_row_color,
                 0 => self.theme.normal_row_color,
                 _ => self.theme.alt_row_color,
             };
-            Row::new(vec![
-                Cow::Borrowed(data.user_name.as_str()),
-                Cow::Owned(format!("{}", data.pid)),
-                Cow::Owned(data.parent_as_string()),
-                Cow::Borrowed(&data.start_time),
-                Cow::Borrowed(&data.run_time),
-                Cow::Borrowed(&data.cmd),
-                Cow::Borrowed(data.cmd_path.as_deref().unwrap_or("")),
-                Cow::Borrowed(value_getter(data)),
-            ])
-            .style(Style::new().fg(self.theme.row_fg).bg(color))
+            let fg = match data.owner {
+                _ if data.is_self_or_ancestor => self.theme.self_row_fg,
+                _ if data.is_zombie() => self.theme.zombie_row_fg,
+                _ if data.is_stopped() => self.theme.stopped_row_fg,
+                Owner::Root => self.theme.root_row_fg,
+                Owner::CurrentUser => self.theme.own_row_fg,
+                Owner::Other => self.theme.row_fg,
+            };
+            let mut cells: Vec<Cell> = self
+                .table_columns
+                .iter()
+                .zip(&widths)
+                .map(|(column, width)| self.process_cell(*column, data, highlight, *width))
+                .collect();
+            //NOTE: value matched by search is always the last column
+            let dynamic_width = widths.last().copied().unwrap_or(0);
+            cells.push(Cell::from(value_getter(data, highlight, dynamic_width)));
+            Row::new(cells).style(row_style(Style::new().fg(fg).bg(color), data.context_only))
         });
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Percentage(5),
-                Constraint::Percentage(5),
-                Constraint::Percentage(5),
-                Constraint::Percentage(5),
-                Constraint::Percentage(5),
-                Constraint::Percentage(10),
-                Constraint::Percentage(25),
-                Constraint::Percentage(40),
-            ],
-        )
-        .header(Row::new(vec![
-            "USER",
-            "PID",
-            "PARENT",
-            "STARTED",
-            "TIME",
-            "CMD",
-            "CMD_PATH",
-            dynamic_header,
-        ]))
-        .block(
-            Block::default()
-                .title(
-                    Title::from(format!(
-                        " {} / {} ",
-                        self.process_table.selected().map(|i| i + 1).unwrap_or(0),
-                        search_results.len()
-                    ))
-                    .position(Position::Top)
-                    .alignment(Alignment::Left),
-                )
-                .borders(Borders::ALL)
-                .border_style(Style::new().fg(self.theme.process_table_border_color))
-                .border_type(BorderType::Plain),
-        )
-        .highlight_style(
-            Style::default()
-                .add_modifier(Modifier::REVERSED)
-                .fg(self.theme.selected_style_fg),
-        )
-        .highlight_symbol(Text::from(vec![" ".into()]))
-        .highlight_spacing(HighlightSpacing::Always);
+        let mut header: Vec<Cell> = self
+            .table_columns
+            .iter()
+            .map(|column| self.column_header(*column))
+            .collect();
+        header.push(Cell::from(dynamic_header.as_str()));
+        let table = Table::new(rows, constraints)
+            .header(Row::new(header))
+            .block(
+                Block::default()
+                    .title(
+                        Title::from(format!(
+                            " {} / {}{}{}{}{}{} ",
+                            self.process_table.selected().map(|i| i + 1).unwrap_or(0),
+                            search_results.len(),
+                            hidden_label(search_results.hidden),
+                            user_filter_label(search_results.user_filter.as_deref()),
+                            active_filters_label(search_results.active_filters),
+                            marked_label(self.marked.len()),
+                            refreshing_label(self.refreshing),
+                        ))
+                        .position(Position::Top)
+                        .alignment(Alignment::Left),
+                    )
+                    .title(
+                        Title::from(totals_label(&search_results.totals()))
+                            .position(Position::Bottom)
+                            .alignment(Alignment::Left),
+                    )
+                    .borders(Borders::ALL)
+                    .border_style(Style::new().fg(self.theme.process_table_border_color))
+                    .border_set(self.symbols.plain_border),
+            )
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(self.theme.selected_style_fg),
+            )
+            .highlight_symbol(Text::from(vec![" ".into()]))
+            .highlight_spacing(HighlightSpacing::Always);
         f.render_stateful_widget(table, area, &mut self.process_table);
+        self.render_marks(f, search_results, area);
         f.render_stateful_widget(
             Scrollbar::default()
                 .orientation(ScrollbarOrientation::VerticalRight)
+                .symbols(self.symbols.scrollbar.clone())
                 .begin_symbol(None)
                 .end_symbol(None),
             area.inner(Margin {
@@ -260,6 +995,170 @@ impl Tui {
             }),
             &mut self.process_table_scroll_state,
         );
+        if let Some(msg) = empty_table_message(search_results.len(), self.searched) {
+            render_empty_table_message(f, area, msg);
+        }
+    }
+
+    /// Marked rows get '*' in the gutter of highlight symbol, table widget draws symbol only for selected row
+    fn render_marks(&self, f: &mut Frame, search_results: &ProcessSearchResults, area: Rect) {
+        if self.marked.is_empty() {
+            return;
+        }
+        let offset = self.process_table.offset();
+        for row in area.top()..area.bottom() {
+            let index = table_row_at(area, offset, search_results.len(), row);
+            let is_marked = search_results
+                .nth(index)
+                .is_some_and(|prc| self.marked.contains(&prc.pid));
+            if is_marked {
+                f.buffer_mut()[(area.x + 1, row)].set_symbol("*");
+            }
+        }
+    }
+
+    fn process_cell<'a>(
+        &self,
+        column: Column,
+        data: &'a Process,
+        highlight: Style,
+        width: u16,
+    ) -> Cell<'a> {
+        let right_aligned = |value: String| Cell::from(Line::from(value).right_aligned());
+        match column {
+            Column::User => Cell::from(data.user_name.as_str()),
+            Column::Pid => Cell::from(highlight_prefix(
+                data.pid.to_string(),
+                data.matches.pid,
+                highlight,
+            )),
+            Column::Parent => Cell::from(data.parent_as_string()),
+            Column::Started => Cell::from(format_start_time(
+                self.columns.start_time,
+                data.start_timestamp,
+            )),
+            Column::Time => Cell::from(data.run_time_as_string()),
+            Column::Mem => right_aligned(data.memory_as_string()),
+            Column::Cpu => right_aligned(data.cpu_usage_as_string()),
+            Column::Threads => right_aligned(data.threads_as_string()),
+            Column::Read => right_aligned(data.io_read_as_string()),
+            Column::Write => right_aligned(data.io_write_as_string()),
+            Column::Tty => right_aligned(data.tty_as_string().to_string()),
+            Column::Fds => right_aligned(data.open_fds_as_string()),
+            Column::Cmd => {
+                let count = data.group_count_as_string();
+                let mut node = tree_node(
+                    self.symbols.tree_prefix(&data.tree_prefix),
+                    highlight_scrolled_matches(
+                        &data.cmd,
+                        &data.matches.cmd,
+                        highlight,
+                        width.saturating_sub(
+                            (data.tree_prefix.chars().count() + count.len()) as u16,
+                        ),
+                        self.cmd_scroll,
+                    ),
+                );
+                if !count.is_empty() {
+                    node.spans.push(Span::raw(count));
+                }
+                Cell::from(node)
+            }
+            Column::CmdPath => Cell::from(highlight_scrolled_matches(
+                data.cmd_path.as_deref().unwrap_or(""),
+                &data.matches.cmd_path,
+                highlight,
+                width,
+                self.cmd_scroll,
+            ))
+            .style(match data.exe_deleted {
+                true => Style::new().fg(self.theme.deleted_exe_fg),
+                false => Style::new(),
+            }),
+        }
+    }
+
+    fn column_header(&self, column: Column) -> Cell<'static> {
+        let title = self.column_header_title(column);
+        match column {
+            Column::Mem
+            | Column::Cpu
+            | Column::Threads
+            | Column::Read
+            | Column::Write
+            | Column::Tty
+            | Column::Fds => Cell::from(Line::from(title).right_aligned()),
+            _ => Cell::from(title),
+        }
+    }
+
+    fn column_header_title(&self, column: Column) -> String {
+        match column_sort(column) {
+            Some(sort_column) => self.sortable_header(column_title(column), sort_column),
+            None => column_title(column).to_string(),
+        }
+    }
+
+    /// Columns fit the longest value of rows on screen (up to a cap), so widths follow refresh,
+    /// scrolling and resize without going through all processes. Last column fills the rest
+    fn fitted_table_columns(
+        &self,
+        search_results: &ProcessSearchResults,
+        area: Rect,
+    ) -> Vec<Constraint> {
+        let rows = visible_rows(
+            area,
+            self.process_table.offset(),
+            self.process_table.selected(),
+            search_results.len(),
+        );
+        let headers: Vec<u16> = self
+            .table_columns
+            .iter()
+            .map(|column| Span::raw(self.column_header_title(*column)).width() as u16)
+            .collect();
+        let widths: Vec<u16> = self
+            .table_columns
+            .iter()
+            .zip(&headers)
+            .map(|(column, header)| {
+                let content = search_results
+                    .iter()
+                    .skip(rows.start)
+                    .take(rows.len())
+                    .map(|prc| self.cell_width(*column, prc))
+                    .max()
+                    .unwrap_or(0);
+                content.max(*header).min(column_max_width(*column))
+            })
+            .collect();
+        let available = table_columns_area(area).width;
+        fit_table_columns(&self.table_columns, widths, &headers, available)
+    }
+
+    fn cell_width(&self, column: Column, prc: &Process) -> u16 {
+        let text = match column {
+            Column::User => prc.user_name.clone(),
+            Column::Pid => prc.pid.to_string(),
+            Column::Parent => prc.parent_as_string(),
+            Column::Started => format_start_time(self.columns.start_time, prc.start_timestamp),
+            Column::Time => prc.run_time_as_string(),
+            Column::Mem => prc.memory_as_string(),
+            Column::Cpu => prc.cpu_usage_as_string(),
+            Column::Threads => prc.threads_as_string(),
+            Column::Read => prc.io_read_as_string(),
+            Column::Write => prc.io_write_as_string(),
+            Column::Tty => prc.tty_as_string().to_string(),
+            Column::Fds => prc.open_fds_as_string(),
+            Column::Cmd => format!(
+                "{}{}{}",
+                prc.tree_prefix,
+                prc.cmd,
+                prc.group_count_as_string()
+            ),
+            Column::CmdPath => prc.cmd_path.clone().unwrap_or_default(),
+        };
+        Span::raw(text).width().try_into().unwrap_or(u16::MAX)
     }
 
     fn render_process_details(
@@ -269,9 +1168,64 @@ impl Tui {
         area: Rect,
     ) {
         let selected_process = search_results.nth(self.get_selected_row_index());
-        let lines = process_details_lines(selected_process);
+        let pid = selected_process.map(|prc| prc.pid);
+        let start_time_format = self.columns.start_time;
+        //NOTE: history takes the right side of info tab, the rest of details is too narrow for it
+        let usage_samples = pid
+            .filter(|_| self.details_tab == DetailsTab::Info)
+            .filter(|_| area.width >= MIN_WIDTH_WITH_USAGE_HISTORY)
+            .and_then(|pid| {
+                let count = USAGE_HISTORY_WIDTH.saturating_sub(2 + USAGE_LABEL_WIDTH) as usize;
+                self.usage_history.samples(pid, count)
+            });
+        let area = match usage_samples {
+            Some(samples) => {
+                let [details, history] = Layout::horizontal([
+                    Constraint::Min(0),
+                    Constraint::Length(USAGE_HISTORY_WIDTH),
+                ])
+                .areas(area);
+                self.render_usage_history(f, &samples, history);
+                details
+            }
+            None => area,
+        };
+        let lines = match self.details_tab {
+            DetailsTab::Info => {
+                let connections = self.connections_of(pid);
+                //NOTE: listening sockets found by scan are shown when sockets cannot be read
+                let connections = match selected_process {
+                    Some(prc) if connections.is_empty() => prc.connections.as_slice(),
+                    _ => connections,
+                };
+                process_details_lines(selected_process, connections, start_time_format)
+            }
+            DetailsTab::Environment => environment_lines(self.environment_of(pid)),
+            DetailsTab::Files => {
+                let limit = self.open_files_limit;
+                open_files_lines(self.open_files_of(pid), limit)
+            }
+            DetailsTab::Network => {
+                let connections = self.connections_of(pid);
+                let connections = match selected_process {
+                    Some(prc) if connections.is_empty() => prc.connections.as_slice(),
+                    _ => connections,
+                };
+                network_lines(pid.map(|_| connections))
+            }
+        };
 
-        self.update_process_details_number_of_lines(area, selected_process);
+        self.update_process_details_number_of_lines(area, &lines);
+        //NOTE: wrapped content gets shorter when details get wider
+        let scroll = self.details_scroll();
+        scroll.offset = clamp_scroll_offset(
+            scroll.offset,
+            scroll.number_of_lines,
+            area.height.saturating_sub(2),
+        );
+        let scroll_offset = scroll.offset;
+        let scrollbar_state =
+            details_scrollbar_state(scroll.number_of_lines, scroll.offset, area.height);
 
         let info_footer = Paragraph::new(lines)
             .wrap(Wrap { trim: false })
@@ -280,56 +1234,547 @@ impl Tui {
                 Block::default()
                     .borders(Borders::ALL)
                     .title(
-                        Title::from(" Process Details ")
+                        Title::from(details_tabs_title(self.details_tab))
                             .alignment(Alignment::Left)
                             .position(Position::Top),
                     )
                     // .border_style(Style::new().fg(app.colors.footer_border_color))
-                    .border_type(BorderType::Rounded),
+                    .border_set(self.symbols.rounded_border),
             )
-            .scroll((self.process_details_scroll_offset, 0));
+            .scroll((scroll_offset, 0));
         f.render_widget(info_footer, area);
-        f.render_stateful_widget(
-            Scrollbar::default()
-                .orientation(ScrollbarOrientation::VerticalRight)
-                .thumb_symbol("")
-                .track_symbol(None)
-                .begin_symbol(Some("↑"))
-                .end_symbol(Some("↓")),
-            area,
-            &mut self.process_details_scroll_state,
-        );
+        if let Some(mut scrollbar_state) = scrollbar_state {
+            f.render_stateful_widget(
+                Scrollbar::default()
+                    .orientation(ScrollbarOrientation::VerticalRight)
+                    .thumb_symbol("")
+                    .track_symbol(None)
+                    .begin_symbol(Some(self.symbols.scroll_up))
+                    .end_symbol(Some(self.symbols.scroll_down)),
+                area,
+                &mut scrollbar_state,
+            );
+        }
     }
 
-    fn update_process_details_number_of_lines(
-        &mut self,
-        area: Rect,
-        selected_process: Option<&Process>,
-    ) {
-        let content_width = area.width - 2;
+    /// Sparklines of cpu usage and memory, cpu is scaled to at least 100% so idle process looks idle
+    fn render_usage_history(&self, f: &mut Frame, samples: &UsageSamples, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" History ")
+            .border_set(self.symbols.rounded_border);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let [cpu, memory] =
+            Layout::vertical([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).areas(inner);
+        let style = Style::new().fg(self.theme.selected_style_fg);
+        let cpu_max = samples.cpu.iter().copied().max().unwrap_or(0).max(1000);
+        for (area, label, data, max) in [
+            (cpu, "CPU", &samples.cpu, cpu_max),
+            (memory, "MEM", &samples.memory, 0),
+        ] {
+            let [label_area, sparkline_area] =
+                Layout::horizontal([Constraint::Length(USAGE_LABEL_WIDTH), Constraint::Min(0)])
+                    .areas(area);
+            f.render_widget(Paragraph::new(label), label_area);
+            let sparkline = Sparkline::default().data(data).style(style);
+            //NOTE: memory is scaled to its own maximum, so growth is visible
+            let sparkline = match max {
+                0 => sparkline,
+                max => sparkline.max(max),
+            };
+            f.render_widget(sparkline, sparkline_area);
+        }
+    }
 
-        match selected_process {
-            Some(process) => {
-                let args_number_of_lines =
-                    (process.args.chars().count() as u16 / content_width) + 1;
-                self.process_details_number_of_lines = args_number_of_lines + 2;
-            }
-            None => {
-                self.process_details_number_of_lines = 1;
-            }
+    fn connections_of(&mut self, pid: Option<u32>) -> &[Connection] {
+        let Some(pid) = pid else {
+            return &[];
+        };
+        if self
+            .connections
+            .as_ref()
+            .is_none_or(|(cached, _)| *cached != pid)
+        {
+            self.connections = Some((pid, process_connections(pid)));
+        }
+        self.connections
+            .as_ref()
+            .map(|(_, connections)| connections.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn environment_of(&mut self, pid: Option<u32>) -> Option<&Result<Vec<String>>> {
+        let pid = pid?;
+        if self
+            .environment
+            .as_ref()
+            .is_none_or(|(cached, _)| *cached != pid)
+        {
+            self.environment = Some((pid, process_environment(pid)));
+        }
+        self.environment.as_ref().map(|(_, variables)| variables)
+    }
+
+    fn open_files_of(&mut self, pid: Option<u32>) -> Option<&Result<Vec<OpenFile>>> {
+        let pid = pid?;
+        if self
+            .open_files
+            .as_ref()
+            .is_none_or(|(cached, _)| *cached != pid)
+        {
+            self.open_files = Some((pid, process_open_files(pid)));
+        }
+        self.open_files.as_ref().map(|(_, files)| files)
+    }
+
+    fn update_process_details_number_of_lines(&mut self, area: Rect, lines: &[Line]) {
+        let content_width = area.width.saturating_sub(2);
+        self.details_scroll().number_of_lines = wrapped_line_count(lines, content_width);
+    }
+}
+
+/// Number of rows lines take when wrapped to given width, same as rendered by details paragraph
+fn wrapped_line_count(lines: &[Line], content_width: u16) -> u16 {
+    let count = Paragraph::new(lines.to_vec())
+        .wrap(Wrap { trim: false })
+        .line_count(content_width);
+    count.try_into().unwrap_or(u16::MAX)
+}
+
+fn search_area(search_text: &str) -> TextArea<'static> {
+    let mut search_area = TextArea::from(search_text.lines());
+    search_area.move_cursor(tui_textarea::CursorMove::End);
+    search_area
+}
+
+fn active_filters_label(active_filters: usize) -> String {
+    match active_filters {
+        0 => String::new(),
+        1 => " | 1 filter".to_string(),
+        n => format!(" | {} filters", n),
+    }
+}
+
+fn case_sensitivity_label(case_sensitivity: CaseSensitivity) -> &'static str {
+    match case_sensitivity {
+        CaseSensitivity::Smart => "[smart] ",
+        CaseSensitivity::Sensitive => "[case] ",
+        CaseSensitivity::Insensitive => "[nocase] ",
+    }
+}
+
+fn search_mode_badge(search_mode: SearchBy) -> &'static str {
+    match search_mode {
+        SearchBy::Args => "ARGS",
+        SearchBy::Port => "PORT",
+        SearchBy::User => "USER",
+        SearchBy::Path => "PATH",
+        _ => "NAME",
+    }
+}
+
+fn totals_label(totals: &ResultsTotals) -> String {
+    match totals.count {
+        0 => " no matches ".to_string(),
+        1 => format!(
+            " 1 process | MEM {} | CPU {:.1}% ",
+            totals.memory_as_string(),
+            totals.cpu_usage
+        ),
+        n => format!(
+            " {} processes | MEM {} | CPU {:.1}% ",
+            n,
+            totals.memory_as_string(),
+            totals.cpu_usage
+        ),
+    }
+}
+
+fn hidden_label(hidden: usize) -> String {
+    match hidden {
+        0 => String::new(),
+        n => format!(" ({} hidden)", n),
+    }
+}
+
+fn user_filter_label(user: Option<&str>) -> String {
+    match user {
+        Some(user) => format!(" | user: {}", user),
+        None => String::new(),
+    }
+}
+
+fn refreshing_label(refreshing: bool) -> &'static str {
+    match refreshing {
+        true => " | refreshing...",
+        false => "",
+    }
+}
+
+/// Empty table tells whether nothing matched or there are no scanned processes to search yet.
+/// Refresh running in background keeps previous results, so it does not count as scanning
+fn empty_table_message(number_of_results: usize, searched: bool) -> Option<&'static str> {
+    match (number_of_results, searched) {
+        (0, false) => Some("Scanning…"),
+        (0, true) => Some("No matching processes"),
+        _ => None,
+    }
+}
+
+//NOTE: message is centered in rows below the header
+fn render_empty_table_message(f: &mut Frame, area: Rect, msg: &str) {
+    let rows = area.inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let rows = Rect {
+        y: rows.y.saturating_add(1),
+        height: rows.height.saturating_sub(1),
+        ..rows
+    };
+    if rows.is_empty() {
+        return;
+    }
+    let line = Rect {
+        y: rows.y + rows.height / 2,
+        height: 1,
+        ..rows
+    };
+    f.render_widget(
+        Paragraph::new(msg)
+            .centered()
+            .style(Style::new().add_modifier(Modifier::DIM)),
+        line,
+    );
+}
+
+fn marked_label(marked: usize) -> String {
+    match marked {
+        0 => String::new(),
+        n => format!(" | {} marked", n),
+    }
+}
+
+//NOTE: ancestors shown only for context in tree view are dimmed
+fn row_style(style: Style, context_only: bool) -> Style {
+    match context_only {
+        true => style.add_modifier(Modifier::DIM),
+        false => style,
+    }
+}
+
+fn tree_node<'a>(prefix: Cow<'a, str>, name: Line<'a>) -> Line<'a> {
+    if prefix.is_empty() {
+        return name;
+    }
+    let mut spans = vec![Span::raw(prefix)];
+    spans.extend(name.spans);
+    Line::from(spans)
+}
+
+//NOTE: used for numbers, so byte length is the same as number of chars
+fn highlight_prefix(text: String, len: usize, highlight: Style) -> Line<'static> {
+    match text.get(..len) {
+        Some(prefix) if len > 0 => Line::from(vec![
+            Span::styled(prefix.to_string(), highlight),
+            Span::raw(text[len..].to_string()),
+        ]),
+        _ => Line::from(text),
+    }
+}
+
+fn column_title(column: Column) -> &'static str {
+    match column {
+        Column::User => "USER",
+        Column::Pid => "PID",
+        Column::Parent => "PARENT",
+        Column::Started => "STARTED",
+        Column::Time => "TIME",
+        Column::Mem => "MEM",
+        Column::Cpu => "CPU%",
+        Column::Threads => "THREADS",
+        Column::Read => "READ/s",
+        Column::Write => "WRITE/s",
+        Column::Tty => "TTY",
+        Column::Fds => "FDS",
+        Column::Cmd => "CMD",
+        Column::CmdPath => "CMD_PATH",
+    }
+}
+
+/// Sort applied when header of the column is chosen, disk io, terminal and open fds can't be sorted by
+fn column_sort(column: Column) -> Option<SortColumn> {
+    match column {
+        Column::User => Some(SortColumn::User),
+        Column::Pid => Some(SortColumn::Pid),
+        Column::Parent => Some(SortColumn::Parent),
+        Column::Started => Some(SortColumn::StartTime),
+        Column::Time => Some(SortColumn::RunTime),
+        Column::Mem => Some(SortColumn::Memory),
+        Column::Cpu => Some(SortColumn::Cpu),
+        Column::Threads => Some(SortColumn::Threads),
+        Column::Read | Column::Write | Column::Tty | Column::Fds => None,
+        Column::Cmd => Some(SortColumn::Cmd),
+        Column::CmdPath => Some(SortColumn::CmdPath),
+    }
+}
+
+fn column_width(column: Column) -> u16 {
+    match column {
+        Column::Mem | Column::Read | Column::Write => 7,
+        Column::Tty => 6,
+        Column::Cmd => 10,
+        Column::CmdPath => 20,
+        _ => 5,
+    }
+}
+
+/// Widest a fitted column gets, longer values are cut off like with fixed widths
+fn column_max_width(column: Column) -> u16 {
+    match column {
+        Column::User => 16,
+        Column::Pid | Column::Parent | Column::Time | Column::Cpu => 8,
+        Column::Threads | Column::Tty | Column::Fds => 10,
+        Column::Started | Column::Mem | Column::Read | Column::Write => 12,
+        Column::Cmd => 32,
+        Column::CmdPath => 60,
+    }
+}
+
+/// Space fitted columns leave at least to the last column (value matched by search)
+const MIN_SEARCH_COLUMN_WIDTH: u16 = 12;
+
+/// Columns get given widths, the last one fills the rest. When they don't fit into the available width,
+/// paths and names give way first (down to their header), so the other columns stay readable on narrow screens
+fn fit_table_columns(
+    columns: &[Column],
+    mut widths: Vec<u16>,
+    headers: &[u16],
+    available: u16,
+) -> Vec<Constraint> {
+    //NOTE: columns are separated by one space
+    let available = available.saturating_sub(columns.len() as u16);
+    let mut overflow =
+        (widths.iter().sum::<u16>() + MIN_SEARCH_COLUMN_WIDTH).saturating_sub(available);
+    for shrinkable in [Column::CmdPath, Column::Cmd] {
+        if let Some(i) = columns.iter().position(|column| *column == shrinkable) {
+            let cut = overflow.min(widths[i].saturating_sub(headers[i]));
+            widths[i] -= cut;
+            overflow -= cut;
+        }
+    }
+    widths
+        .into_iter()
+        .map(Constraint::Length)
+        .chain([Constraint::Fill(1)])
+        .collect()
+}
+
+/// Rows on screen, the table scrolls to selected row when it is outside of current window
+fn visible_rows(area: Rect, offset: usize, selected: Option<usize>, len: usize) -> Range<usize> {
+    //NOTE: borders and header take three rows
+    let height = area.height.saturating_sub(3) as usize;
+    let start = match selected {
+        Some(selected) if selected < offset => selected,
+        Some(selected) if selected >= offset + height => (selected + 1).saturating_sub(height),
+        _ => offset,
+    };
+    start.min(len)..(start + height).min(len)
+}
+
+/// Width of the last column (value matched by search) when all default columns are shown
+const DYNAMIC_COLUMN_WIDTH: u16 = 33;
+
+/// Columns of the table followed by the one with value matched by search.
+/// Extra columns take space from the last one, space left by hidden ones goes to command
+fn process_table_columns(columns: &[Column]) -> Vec<Constraint> {
+    let mut widths: Vec<u16> = columns.iter().map(|column| column_width(*column)).collect();
+    let free = 100u16.saturating_sub(widths.iter().sum());
+    let mut dynamic = free.min(DYNAMIC_COLUMN_WIDTH);
+    let spare = free - dynamic;
+    match columns.iter().position(|column| *column == Column::Cmd) {
+        Some(cmd) => widths[cmd] += spare,
+        None => dynamic += spare,
+    }
+    widths.push(dynamic);
+    widths.into_iter().map(Constraint::Percentage).collect()
+}
+
+/// Widths of table columns as laid out by the table widget (borders, selection symbol and spacing excluded)
+fn process_table_column_widths(constraints: &[Constraint], area: Rect) -> Vec<u16> {
+    Layout::horizontal(constraints)
+        .spacing(1)
+        .split(table_columns_area(area))
+        .iter()
+        .map(|rect| rect.width)
+        .collect()
+}
+
+/// Area columns are laid out in, without borders and selection symbol
+fn table_columns_area(area: Rect) -> Rect {
+    let inner = area.inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let [_, columns_area] =
+        Layout::horizontal([Constraint::Length(1), Constraint::Fill(0)]).areas(inner);
+    columns_area
+}
+
+/// When the first match would be cut off by the cell width, beginning of the text is replaced
+/// with ellipsis so the match stays visible
+fn highlight_visible_matches<'a>(
+    text: &'a str,
+    matched_indices: &[usize],
+    highlight: Style,
+    width: u16,
+) -> Line<'a> {
+    let width = width as usize;
+    let skip = match matched_indices.first() {
+        //NOTE: match is placed at one third of the cell, so some text before it is visible
+        Some(first) if width > 1 && *first >= width => first - width / 3,
+        _ => return highlight_matches(text, matched_indices, highlight),
+    };
+    highlight_skipped_matches(text, matched_indices, highlight, skip)
+}
+
+/// Text scrolled to the left by given number of chars, it is scrolled only until its end is visible
+fn highlight_scrolled_matches<'a>(
+    text: &'a str,
+    matched_indices: &[usize],
+    highlight: Style,
+    width: u16,
+    scroll: usize,
+) -> Line<'a> {
+    //NOTE: ellipsis takes one char of the cell
+    let scrollable = text
+        .chars()
+        .count()
+        .saturating_sub((width as usize).saturating_sub(1));
+    match scroll.min(scrollable) {
+        0 => highlight_visible_matches(text, matched_indices, highlight, width),
+        skip => highlight_skipped_matches(text, matched_indices, highlight, skip),
+    }
+}
+
+/// First chars are replaced by ellipsis, matches are shifted accordingly
+fn highlight_skipped_matches<'a>(
+    text: &'a str,
+    matched_indices: &[usize],
+    highlight: Style,
+    skip: usize,
+) -> Line<'a> {
+    let skip_bytes = text
+        .char_indices()
+        .nth(skip)
+        .map(|(byte_index, _)| byte_index)
+        .unwrap_or(text.len());
+    let shifted: Vec<usize> = matched_indices
+        .iter()
+        .filter_map(|i| i.checked_sub(skip))
+        .collect();
+    let mut line = highlight_matches(&text[skip_bytes..], &shifted, highlight);
+    line.spans.insert(0, Span::raw("…"));
+    line
+}
+
+/// Splits text into spans where chars at matched (sorted) indices are highlighted
+fn highlight_matches<'a>(text: &'a str, matched_indices: &[usize], highlight: Style) -> Line<'a> {
+    if matched_indices.is_empty() {
+        return Line::from(text);
+    }
+    let mut spans = vec![];
+    let mut group_start = 0;
+    let mut group_matched = false;
+    for (char_index, (byte_index, _)) in text.char_indices().enumerate() {
+        let matched = matched_indices.binary_search(&char_index).is_ok();
+        if matched != group_matched {
+            push_span(
+                &mut spans,
+                &text[group_start..byte_index],
+                group_matched,
+                highlight,
+            );
+            group_start = byte_index;
+            group_matched = matched;
         }
     }
+    push_span(&mut spans, &text[group_start..], group_matched, highlight);
+    Line::from(spans)
 }
 
-fn dynamic_search_column(search_result: &ProcessSearchResults) -> (&str, fn(&Process) -> &str) {
-    match search_result.search_by {
-        SearchBy::Port => ("PORT", |prc| prc.ports.as_deref().unwrap_or("")),
-        SearchBy::Args => ("ARGS", |prc| prc.args.as_str()),
-        _ => ("", |_| ""),
+fn push_span<'a>(spans: &mut Vec<Span<'a>>, text: &'a str, matched: bool, highlight: Style) {
+    if text.is_empty() {
+        return;
+    }
+    match matched {
+        true => spans.push(Span::styled(text, highlight)),
+        false => spans.push(Span::raw(text)),
+    }
+}
+
+type ValueGetter = fn(&Process, Style, u16) -> Line<'_>;
+
+/// Picks column for the most specific search, port search wins over args search.
+/// Path is shown only when CMD_PATH column is hidden
+fn dynamic_search_column(
+    search_result: &ProcessSearchResults,
+    cmd_path_shown: bool,
+) -> (String, ValueGetter) {
+    let searched_by = |search_by| search_result.search_by.contains(&search_by);
+    if searched_by(SearchBy::Port) {
+        let header = match search_result.port_filter.as_ref() {
+            Some(port_filter) => format!("PORT {}", port_filter),
+            None => "PORT".to_string(),
+        };
+        (header, |prc, highlight, width| {
+            let ports = prc.ports.as_deref().unwrap_or("");
+            highlight_visible_matches(ports, &prc.matches.ports, highlight, width)
+        })
+    } else if searched_by(SearchBy::Args) {
+        ("ARGS".to_string(), |prc, highlight, width| {
+            highlight_visible_matches(&prc.args, &prc.matches.args, highlight, width)
+        })
+    } else if searched_by(SearchBy::Path) && !cmd_path_shown {
+        ("PATH".to_string(), |prc, highlight, width| {
+            let path = prc.cmd_path.as_deref().unwrap_or("");
+            highlight_visible_matches(path, &prc.matches.cmd_path, highlight, width)
+        })
+    } else if let Some(memory_filter) = search_result.memory_filter.as_ref() {
+        (format!("MEM {}", memory_filter), |prc, _, _| {
+            Line::from(prc.memory_as_string())
+        })
+    } else if searched_by(SearchBy::ParentPid) {
+        ("PARENT".to_string(), |prc, _, _| {
+            Line::from(prc.parent_as_string())
+        })
+    } else if searched_by(SearchBy::State) {
+        ("STATE".to_string(), |prc, _, _| {
+            Line::from(prc.state.to_string())
+        })
+    } else if searched_by(SearchBy::Container) {
+        ("CONTAINER".to_string(), |prc, _, _| {
+            Line::from(prc.container_as_string())
+        })
+    } else if searched_by(SearchBy::Tty) {
+        ("TTY".to_string(), |prc, _, _| {
+            Line::from(prc.tty_as_string().to_string())
+        })
+    } else if searched_by(SearchBy::Unit) {
+        ("UNIT".to_string(), |prc, _, _| {
+            Line::from(prc.unit.clone().unwrap_or_default())
+        })
+    } else {
+        (String::new(), |_, _, _| Line::default())
     }
 }
 
-fn process_details_lines(selected_process: Option<&Process>) -> Vec<Line> {
+fn process_details_lines<'a>(
+    selected_process: Option<&'a Process>,
+    connections: &[Connection],
+    start_time_format: StartTimeFormat,
+) -> Vec<Line<'a>> {
     match selected_process {
         Some(prc) => {
             let ports = prc
@@ -337,51 +1782,1665 @@ fn process_details_lines(selected_process: Option<&Process>) -> Vec<Line> {
                 .as_deref()
                 .map(|p| format!(" PORTS: {}", p))
                 .unwrap_or("".to_string());
-            let parent = prc
-                .parent_pid
-                .map(|p| format!(" PARENT: {}", p))
+            let parent = match (prc.parent_pid, prc.parent_cmd.as_deref()) {
+                (Some(pid), Some(cmd)) => format!(" PARENT: {} ({})", pid, cmd),
+                (Some(pid), None) => format!(" PARENT: {}", pid),
+                (None, _) => "".to_string(),
+            };
+            let nice = prc
+                .nice
+                .map(|n| format!(" NICE: {}", n))
                 .unwrap_or("".to_string());
-            vec![
+            let real_user = prc
+                .other_real_user()
+                .map(|name| format!(" (real: {})", name))
+                .unwrap_or("".to_string());
+            let io = match prc.io_rate {
+                Some(_) => format!(
+                    " IO: R {}/s W {}/s",
+                    prc.io_read_as_string(),
+                    prc.io_write_as_string()
+                ),
+                None => " IO: -".to_string(),
+            };
+            let container = match prc.container_id {
+                Some(_) => format!(" CONTAINER: {}", prc.container_as_string()),
+                None => "".to_string(),
+            };
+            let unit = prc
+                .unit
+                .as_ref()
+                .map(|unit| format!(" UNIT: {}", unit))
+                .unwrap_or("".to_string());
+            let mut lines = vec![
                 Line::from(format!(
-                    "USER: {} PID: {}{} START_TIME: {}, RUN_TIME: {} MEMORY: {}MB{}",
+                    "USER: {}{} PID: {}{} STATE: {} START_TIME: {}, RUN_TIME: {} CPU_TIME: {} MEMORY: {} CPU: {}% THREADS: {} FDS: {} TTY: {}{}{}{}{}{}",
                     prc.user_name,
+                    real_user,
                     prc.pid,
                     parent,
-                    prc.start_time,
-                    prc.run_time,
-                    prc.memory / 1024 / 1024,
+                    prc.state,
+                    format_start_time(start_time_format, prc.start_timestamp),
+                    prc.run_time_as_string(),
+                    prc.cpu_time_as_string(),
+                    prc.memory_as_string(),
+                    prc.cpu_usage_as_string(),
+                    prc.threads_as_string(),
+                    prc.open_fds_as_string(),
+                    prc.tty_as_string(),
+                    io,
+                    nice,
+                    container,
+                    unit,
                     ports,
                 )),
                 Line::from(format!("CMD: {}", prc.exe())),
-                //FIXME: Sometimes args are too long and don't fit in details area
+                Line::from(format!("CWD: {}", prc.cwd_as_string())),
                 Line::from(format!("ARGS: {}", prc.args)),
-            ]
+            ];
+            if prc.exe_deleted {
+                lines.insert(
+                    2,
+                    Line::from("DELETED: binary on disk has changed since start"),
+                );
+            }
+            match connections.is_empty() {
+                true => lines.push(Line::from("CONNECTIONS: no network activity")),
+                false => {
+                    lines.push(Line::from("CONNECTIONS:"));
+                    lines.extend(connections.iter().map(|c| Line::from(format!("  {}", c))));
+                }
+            }
+            lines
+        }
+        None => vec![Line::from("No process selected")],
+    }
+}
+
+/// Variables one per line, error (i.e. permission denied) is shown as a single line
+fn environment_lines(environment: Option<&Result<Vec<String>>>) -> Vec<Line<'static>> {
+    match environment {
+        Some(Ok(variables)) if variables.is_empty() => {
+            vec![Line::from("Process has no environment variables")]
         }
+        Some(Ok(variables)) => variables
+            .iter()
+            .map(|variable| Line::from(variable.clone()))
+            .collect(),
+        Some(Err(err)) => vec![Line::from(err.to_string())],
         None => vec![Line::from("No process selected")],
     }
 }
 
-const HELP_TEXT: &str =
-    "ESC/<C+C> quit | <C+X> kill process | <C+R> refresh | <C+F> details forward | <C+B> details backward ";
+/// Every known field of the process with its label, arguments, ports and connections are listed one per line.
+/// Arguments read by pid are preferred, joined args can't be split when arguments contain commas
+fn process_details_text(
+    prc: &Process,
+    args: Option<&[String]>,
+    connections: &[Connection],
+    start_time_format: StartTimeFormat,
+) -> String {
+    let field = |label: &str, value: String| format!("{:<12} {}", format!("{}:", label), value);
+    let mut lines = vec![field("PID", prc.pid.to_string())];
+    if let Some(parent) = prc.parent_pid {
+        let cmd = prc
+            .parent_cmd
+            .as_ref()
+            .map(|cmd| format!(" ({})", cmd))
+            .unwrap_or_default();
+        lines.push(field("PARENT", format!("{}{}", parent, cmd)));
+    }
+    let real_user = prc
+        .other_real_user()
+        .map(|name| format!(" (real: {})", name))
+        .unwrap_or_default();
+    lines.extend([
+        field("USER", format!("{}{}", prc.user_name, real_user)),
+        field("STATE", prc.state.to_string()),
+        field(
+            "STARTED",
+            format_start_time(start_time_format, prc.start_timestamp),
+        ),
+        field("RUN_TIME", prc.run_time_as_string()),
+        field("CPU_TIME", prc.cpu_time_as_string()),
+        field("MEMORY", prc.memory_as_string()),
+        field("CPU", format!("{}%", prc.cpu_usage_as_string())),
+        field("THREADS", prc.threads_as_string()),
+        field("FDS", prc.open_fds_as_string()),
+        field("TTY", prc.tty_as_string().to_string()),
+    ]);
+    if let Some(nice) = prc.nice {
+        lines.push(field("NICE", nice.to_string()));
+    }
+    lines.push(field(
+        "IO",
+        match prc.io_rate {
+            Some(_) => format!(
+                "R {}/s W {}/s",
+                prc.io_read_as_string(),
+                prc.io_write_as_string()
+            ),
+            None => "-".to_string(),
+        },
+    ));
+    if prc.container_id.is_some() {
+        lines.push(field("CONTAINER", prc.container_as_string()));
+    }
+    if let Some(unit) = &prc.unit {
+        lines.push(field("UNIT", unit.clone()));
+    }
+    lines.push(field("CMD", prc.cmd.clone()));
+    lines.push(field("CMD_PATH", prc.exe().to_string()));
+    if prc.exe_deleted {
+        lines.push(field(
+            "DELETED",
+            "binary on disk has changed since start".to_string(),
+        ));
+    }
+    lines.push(field("CWD", prc.cwd_as_string().to_string()));
+    match args {
+        Some([]) => lines.push(field("ARGS", "-".to_string())),
+        Some(args) => {
+            lines.push("ARGS:".to_string());
+            lines.extend(args.iter().map(|arg| format!("  {}", arg)));
+        }
+        None => lines.push(field("ARGS", prc.args.clone())),
+    }
+    match prc.ports.as_deref() {
+        Some(ports) => {
+            lines.push("PORTS:".to_string());
+            lines.extend(ports.split(", ").map(|port| format!("  {}", port)));
+        }
+        None => lines.push(field("PORTS", "-".to_string())),
+    }
+    match connections.is_empty() {
+        true => lines.push(field("CONNECTIONS", "no network activity".to_string())),
+        false => {
+            lines.push("CONNECTIONS:".to_string());
+            lines.extend(connections.iter().map(|c| format!("  {}", c)));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Files listed up to the limit like in open files popup
+fn open_files_lines(files: Option<&Result<Vec<OpenFile>>>, limit: usize) -> Vec<Line<'static>> {
+    match files {
+        Some(Ok(files)) => open_files_text(files, limit)
+            .lines()
+            .map(|line| Line::from(line.to_string()))
+            .collect(),
+        Some(Err(err)) => vec![Line::from(err.to_string())],
+        None => vec![Line::from("No process selected")],
+    }
+}
+
+fn network_lines(connections: Option<&[Connection]>) -> Vec<Line<'static>> {
+    match connections {
+        Some([]) => vec![Line::from("No network activity")],
+        Some(connections) => connections
+            .iter()
+            .map(|connection| Line::from(connection.to_string()))
+            .collect(),
+        None => vec![Line::from("No process selected")],
+    }
+}
+
+/// Names of all tabs, the active one is highlighted
+fn details_tabs_title(active: DetailsTab) -> Line<'static> {
+    let mut spans = vec![Span::raw(" ")];
+    for tab in DetailsTab::ALL {
+        let style = match tab == active {
+            true => Style::new().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            false => Style::new().add_modifier(Modifier::DIM),
+        };
+        spans.push(Span::styled(format!(" {} ", tab.name()), style));
+        spans.push(Span::raw(" "));
+    }
+    Line::from(spans)
+}
+
+fn kill_summary_text(killed: &[u32], errors: &[String]) -> String {
+    let mut lines = vec![];
+    if !killed.is_empty() {
+        let pids: Vec<String> = killed.iter().map(|pid| pid.to_string()).collect();
+        lines.push(format!("Succeeded: {}", pids.join(", ")));
+    }
+    if !errors.is_empty() {
+        lines.push("Failed:".to_string());
+        lines.extend(errors.iter().map(|error| format!("  {}", error)));
+    }
+    lines.join("\n")
+}
 
-fn render_help(f: &mut Frame, error_message: Option<&str>, area: Rect) {
+fn open_files_text(files: &[OpenFile], limit: usize) -> String {
+    if files.is_empty() {
+        return "Process has no open files".to_string();
+    }
+    let mut lines: Vec<String> = files.iter().take(limit).map(|f| f.to_string()).collect();
+    if files.len() > limit {
+        lines.push(format!("… and {} more", files.len() - limit));
+    }
+    lines.join("\n")
+}
+
+/// Help bar generated from configured keybindings
+fn help_text(keys: &KeyConfig, enter_action: EnterAction) -> String {
+    format!(
+        "{} quit | {} kill process | {} {} | {} mark | {} refresh | {} sort | {} details forward | {} details backward | {} help ",
+        keys.quit.short(),
+        keys.kill.short(),
+        keys.enter.short(),
+        enter_action_label(enter_action),
+        keys.mark.short(),
+        keys.refresh.short(),
+        keys.sort_column.short(),
+        keys.details_forward.short(),
+        keys.details_backward.short(),
+        keys.help.short(),
+    )
+}
+
+fn enter_action_label(enter_action: EnterAction) -> &'static str {
+    match enter_action {
+        EnterAction::Details => "details",
+        EnterAction::Kill => "kill",
+        EnterAction::Print => "print",
+    }
+}
+
+fn normal_mode_help_text(keys: &KeyConfig) -> String {
+    format!(
+        "NORMAL: j/k move | h/l scroll cmd | g/G first/last | / search | {} quit | {} kill process | {} help ",
+        keys.quit.short(),
+        keys.kill.short(),
+        keys.help.short(),
+    )
+}
+
+fn keybindings_help(keys: &KeyConfig) -> String {
+    let key = |keys: String, description: &str| format!("{:<29}{}", keys, description);
+    let popup_scroll = format!("{} | {}", keys.popup_up, keys.popup_down);
+    [
+        key(keys.quit.to_string(), "quit"),
+        key(
+            keys.kill.to_string(),
+            "choose signal and kill marked or selected processes",
+        ),
+        key(
+            keys.kill_tree.to_string(),
+            "choose signal and kill selected process with all its descendants",
+        ),
+        key(
+            keys.enter.to_string(),
+            "open details, kill or print selected process as set by enter_action",
+        ),
+        #[cfg(unix)]
+        key(keys.pause.to_string(), "pause selected process (SIGSTOP)"),
+        #[cfg(unix)]
+        key(keys.resume.to_string(), "resume paused process (SIGCONT)"),
+        key(
+            keys.normal_mode.to_string(),
+            "normal mode, j/k move, h/l scroll commands, g/G first/last, / or i search",
+        ),
+        key(keys.mark.to_string(), "mark/unmark selected process"),
+        key(
+            keys.pin.to_string(),
+            "pin/unpin selected process, pinned ones are always shown at the top",
+        ),
+        key(
+            keys.select_parent.to_string(),
+            "select parent of selected process, it is added when it does not match",
+        ),
+        key(
+            keys.show_children.to_string(),
+            "search children of selected process",
+        ),
+        key(
+            keys.mark_all.to_string(),
+            "mark all processes shown in the table",
+        ),
+        key(
+            "ESC".to_string(),
+            "clear marks, quits when nothing is marked",
+        ),
+        key(keys.refresh.to_string(), "refresh processes list"),
+        key(keys.sort_column.to_string(), "sort by next column"),
+        key(keys.sort_order.to_string(), "toggle sort order"),
+        key(
+            keys.sort_picker.to_string(),
+            "pick sort column, picking the same column toggles order",
+        ),
+        key(keys.tree_view.to_string(), "toggle tree view"),
+        key(
+            format!("{} | {}", keys.collapse, keys.expand),
+            "collapse/expand selected subtree in tree view",
+        ),
+        key(
+            keys.group.to_string(),
+            "group processes running the same executable into one row",
+        ),
+        key(
+            format!("{} | RIGHT", keys.enter),
+            &format!(
+                "expand/collapse selected group, {} collapses it again",
+                keys.collapse
+            ),
+        ),
+        key(keys.bookmarks.to_string(), "search using bookmarked query"),
+        key(
+            keys.hide_self.to_string(),
+            "hide/show pik and its ancestors",
+        ),
+        key(
+            keys.search_mode.to_string(),
+            "search terms without prefix by name, args, port, user or path",
+        ),
+        key(keys.copy_pid.to_string(), "copy pid of selected process"),
+        key(
+            keys.environment.to_string(),
+            "show environment variables of selected process",
+        ),
+        key(
+            keys.open_files.to_string(),
+            "list open files of selected process",
+        ),
+        key(
+            keys.start_time_format.to_string(),
+            "show start time as time of day, time since start or date",
+        ),
+        key(
+            keys.export.to_string(),
+            "export processes shown in the table to file",
+        ),
+        key(
+            keys.details_tab.to_string(),
+            "switch process details between info, environment, files and network",
+        ),
+        key(
+            keys.toggle_details.to_string(),
+            "hide/show process details, table takes their space",
+        ),
+        key(
+            keys.copy_cmd.to_string(),
+            "copy command line of selected process",
+        ),
+        key(keys.regex_search.to_string(), "toggle regex search mode"),
+        key(
+            keys.fuzzy_search.to_string(),
+            "switch between fuzzy and exact (substring) search",
+        ),
+        key(
+            keys.case_sensitivity.to_string(),
+            "switch between smart case, case sensitive and insensitive search",
+        ),
+        key(
+            keys.details_forward.to_string(),
+            "scroll process details forward",
+        ),
+        key(
+            keys.details_backward.to_string(),
+            "scroll process details backward",
+        ),
+        key("TAB | DOWN | CTRL+J".to_string(), "select next process"),
+        key(
+            "SHIFT+TAB | UP | CTRL+K".to_string(),
+            "select previous process",
+        ),
+        key(
+            "CTRL+DOWN | CTRL+UP".to_string(),
+            "select last/first process",
+        ),
+        key(
+            "UP | DOWN".to_string(),
+            "browse search history, starting with empty search",
+        ),
+        key(
+            "PAGE DOWN | PAGE UP".to_string(),
+            "move selection by 10 rows",
+        ),
+        key(keys.help.to_string(), "show this help"),
+        String::new(),
+        "Row colors (dark preset, set in [theme]):".to_string(),
+        key("zombie_row_fg".to_string(), "red, zombie processes"),
+        key(
+            "stopped_row_fg".to_string(),
+            "blue, processes stopped by signal",
+        ),
+        key("root_row_fg".to_string(), "purple, processes run by root"),
+        key("own_row_fg".to_string(), "teal, processes run by you"),
+        key("row_fg".to_string(), "white, processes of other users"),
+        String::new(),
+        "In popups:".to_string(),
+        key(popup_scroll.clone(), "scroll by line"),
+        key("PAGE UP | PAGE DOWN".to_string(), "scroll by page"),
+        key("HOME | END".to_string(), "jump to top/bottom"),
+        key(format!("ESC | {}", keys.enter), "close popup"),
+        String::new(),
+        "In signal popup:".to_string(),
+        key(popup_scroll.clone(), "select signal"),
+        key(keys.enter.to_string(), "send selected signal"),
+        String::new(),
+        "In bookmarks popup:".to_string(),
+        key(popup_scroll, "select bookmark"),
+        key(keys.enter.to_string(), "search using selected bookmark"),
+    ]
+    .join("\n")
+}
+
+fn render_help(f: &mut Frame, message: Span, help_text: &str, area: Rect) {
     let rects = Layout::horizontal([Constraint::Percentage(25), Constraint::Percentage(75)])
         .horizontal_margin(1)
         .split(area);
-    let error = Paragraph::new(Span::from(error_message.unwrap_or("")).fg(Color::Red))
+    let message = Paragraph::new(message)
         .left_aligned()
         .block(Block::default().borders(Borders::NONE));
-    let help = Paragraph::new(Line::from(HELP_TEXT)).right_aligned();
-    f.render_widget(error, rects[0]);
+    let help = Paragraph::new(Line::from(help_text)).right_aligned();
+    f.render_widget(message, rects[0]);
     f.render_widget(help, rects[1]);
 }
 
-fn layout_rects(frame: &mut Frame) -> Rc<[Rect]> {
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
+/// Second click on the same row shortly after the first one
+fn is_double_click(last_click: Option<(usize, Instant)>, index: usize, now: Instant) -> bool {
+    last_click.is_some_and(|(last_index, at)| {
+        last_index == index && now.duration_since(at) <= DOUBLE_CLICK_INTERVAL
+    })
+}
+
+/// Index of process shown in given terminal row of the table, None for header, borders and empty rows
+fn table_row_at(area: Rect, offset: usize, number_of_items: usize, row: u16) -> Option<usize> {
+    //NOTE: rows start below top border and header, bottom border is excluded
+    let first_row = area.y + 2;
+    if row < first_row || row + 1 >= area.bottom() {
+        return None;
+    }
+    let index = offset + (row - first_row) as usize;
+    (index < number_of_items).then_some(index)
+}
+
+/// Rows of process table without borders and header
+fn table_viewport_rows(area: Rect) -> u16 {
+    area.height.saturating_sub(3)
+}
+
+/// Offset that shows selected row at the same relative position in resized viewport,
+/// viewport is not scrolled past the last row
+fn offset_after_resize(
+    selected: usize,
+    offset: usize,
+    previous_rows: u16,
+    rows: u16,
+    number_of_items: usize,
+) -> usize {
+    if rows == 0 {
+        return offset;
+    }
+    let position = selected.saturating_sub(offset).min(previous_rows as usize);
+    let relative = position as f64 / previous_rows.max(1) as f64;
+    let new_position = ((relative * rows as f64).round() as usize).min(rows as usize - 1);
+    selected
+        .saturating_sub(new_position)
+        .min(number_of_items.saturating_sub(rows as usize))
+}
+
+/// Details are not scrolled past their content, i.e. after the area got bigger
+fn clamp_scroll_offset(offset: u16, number_of_lines: u16, content_height: u16) -> u16 {
+    offset.min(number_of_lines.saturating_sub(content_height))
+}
+
+/// Scrollbar of details with given height including borders, None when whole content fits in
+fn details_scrollbar_state(
+    number_of_lines: u16,
+    offset: u16,
+    area_height: u16,
+) -> Option<ScrollbarState> {
+    let content_height = area_height.saturating_sub(2);
+    let scrollable = number_of_lines.saturating_sub(content_height);
+    if scrollable == 0 {
+        return None;
+    }
+    Some(ScrollbarState::new(scrollable as usize).position(offset.min(scrollable) as usize))
+}
+
+/// Search input, process table, process details and help bar. Hidden details give their space to the table
+fn layout_rects(area: Rect, details_hidden: bool) -> Rc<[Rect]> {
+    if details_hidden {
+        let [search, table, help] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(10),
+            Constraint::Length(1),
+        ])
+        .areas(area);
+        //NOTE: empty details area below the table keeps indices of other sections
+        let details = Rect::new(table.x, table.bottom(), table.width, 0);
+        return Rc::from([search, table, details, help]);
+    }
     Layout::vertical([
         Constraint::Length(1),
         Constraint::Min(10),
         Constraint::Max(7),
         Constraint::Length(1),
     ])
-    .split(frame.area())
+    .split(area)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use ratatui::{backend::TestBackend, buffer::Buffer, widgets::Widget, Terminal};
+    use sysinfo::Uid;
+
+    use crate::processes::{FileKind, IoRate, Protocol};
+
+    use super::*;
+
+    fn rendered_rows(lines: &[Line], width: u16) -> u16 {
+        //NOTE: buffer holds at most u16::MAX cells
+        let area = Rect::new(0, 0, width, u16::MAX / width);
+        let mut buffer = Buffer::empty(area);
+        Paragraph::new(lines.to_vec())
+            .wrap(Wrap { trim: false })
+            .render(area, &mut buffer);
+        let is_blank = |y: u16| (0..width).all(|x| buffer[(x, y)].symbol() == " ");
+        (0..area.height).rev().find(|y| !is_blank(*y)).unwrap() + 1
+    }
+
+    #[test]
+    fn should_count_wrapped_lines_of_all_process_details() {
+        let process = Process {
+            pid: 1234,
+            parent_pid: Some(1),
+            user_name: "root".to_string(),
+            cmd: "java".to_string(),
+            cmd_path: Some("/usr/lib/jvm/java-17-openjdk/bin/java".to_string()),
+            args: "-Dapp.name=billing,-jar,/opt/app.jar,".repeat(20),
+            ..Default::default()
+        };
+        let connections = vec![Connection {
+            protocol: Protocol::Tcp,
+            state: "ESTABLISHED",
+            local: "10.0.0.2:51234".parse().unwrap(),
+            remote: Some("93.184.216.34:443".parse().unwrap()),
+        }];
+        let lines = process_details_lines(Some(&process), &connections, StartTimeFormat::Time);
+        for width in [20, 37, 80] {
+            assert_eq!(
+                wrapped_line_count(&lines, width),
+                rendered_rows(&lines, width)
+            );
+        }
+        assert!(wrapped_line_count(&lines, 20) > 40);
+    }
+
+    #[test]
+    fn should_wrap_args_without_spaces_in_process_details() {
+        let args = format!("--classpath={}", "lib/dependency.jar:".repeat(30));
+        let process = Process {
+            args: args.clone(),
+            ..Default::default()
+        };
+        let lines = process_details_lines(Some(&process), &[], StartTimeFormat::Time);
+        let width = 37;
+        let area = Rect::new(0, 0, width, 200);
+        let mut buffer = Buffer::empty(area);
+        Paragraph::new(lines.clone())
+            .wrap(Wrap { trim: false })
+            .render(area, &mut buffer);
+        let rendered: String = (0..area.height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| buffer[(x, y)].symbol().to_string())
+            .collect::<String>()
+            .split_whitespace()
+            .collect();
+        //NOTE: whole token is rendered, it is broken into rows instead of being cut off
+        assert!(rendered.contains(&format!("ARGS:{}", args)));
+        assert_eq!(
+            wrapped_line_count(&lines, width),
+            rendered_rows(&lines, width)
+        );
+        assert!(wrapped_line_count(&lines, width) as usize > args.len() / width as usize);
+    }
+
+    #[test]
+    fn should_count_wrapped_lines_of_very_long_and_wide_args() {
+        let long_args = Process {
+            user_name: "a-very-long-user-name-of-some-service-account".to_string(),
+            args: "x".repeat(5000),
+            ..Default::default()
+        };
+        let wide_args = Process {
+            args: "--name=数据库服务器 --title=プロセス監視 ".repeat(20),
+            ..Default::default()
+        };
+        for process in [long_args, wide_args] {
+            let lines = process_details_lines(Some(&process), &[], StartTimeFormat::Time);
+            for width in [9, 20, 37, 80] {
+                assert_eq!(
+                    wrapped_line_count(&lines, width),
+                    rendered_rows(&lines, width)
+                );
+            }
+        }
+        let args = Line::from("数".repeat(100));
+        //NOTE: wide chars take two cells, so half of them fit on a row
+        assert_eq!(wrapped_line_count(std::slice::from_ref(&args), 20), 10);
+        assert_eq!(wrapped_line_count(&[args], 21), 10);
+    }
+
+    #[test]
+    fn should_scroll_details_exactly_to_the_last_line() {
+        let mut tui = Tui::new(
+            String::new(),
+            BTreeMap::new(),
+            KeyConfig::default(),
+            ColumnsConfig::default(),
+            Theme::dark(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let process = Process {
+            args: "数据".repeat(2500),
+            ..Default::default()
+        };
+        let lines = process_details_lines(Some(&process), &[], StartTimeFormat::Time);
+        let details = layout_rects(Rect::new(0, 0, 80, 24), false)[2];
+        tui.update_process_details_number_of_lines(details, &lines);
+        let content_height = details.height - 2;
+        let number_of_lines = tui.details_scroll().number_of_lines;
+        assert_eq!(number_of_lines, rendered_rows(&lines, details.width - 2));
+        for _ in 0..number_of_lines * 2 {
+            tui.process_details_down(&mut terminal.get_frame());
+        }
+        assert_eq!(
+            tui.details_scroll().offset,
+            number_of_lines - content_height
+        );
+    }
+
+    #[test]
+    fn should_count_wrapped_lines_of_environment() {
+        let variables = vec![
+            "HOME=/root".to_string(),
+            format!("PATH={}", "/usr/local/bin:/usr/bin".repeat(10)),
+            format!("CLASSPATH={}", "lib/app.jar".repeat(20)),
+        ];
+        let environment = Ok(variables);
+        let lines = environment_lines(Some(&environment));
+        assert_eq!(lines[0].to_string(), "HOME=/root");
+        for width in [20, 37, 80] {
+            assert_eq!(
+                wrapped_line_count(&lines, width),
+                rendered_rows(&lines, width)
+            );
+        }
+        assert!(wrapped_line_count(&lines, 20) > 20);
+    }
+
+    #[test]
+    fn should_show_environment_error_as_single_line() {
+        let environment = Err(anyhow::anyhow!("Permission denied"));
+        let text: Vec<String> = environment_lines(Some(&environment))
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(text, vec!["Permission denied"]);
+        assert_eq!(
+            environment_lines(Some(&Ok(vec![])))[0].to_string(),
+            "Process has no environment variables"
+        );
+    }
+
+    #[test]
+    fn should_list_succeeded_and_failed_kills() {
+        let errors = vec!["Permission denied killing PID 1: try running with sudo".to_string()];
+        assert_eq!(
+            kill_summary_text(&[12, 34], &errors),
+            "Succeeded: 12, 34\nFailed:\n  Permission denied killing PID 1: try running with sudo"
+        );
+        assert_eq!(kill_summary_text(&[12], &[]), "Succeeded: 12");
+        assert_eq!(kill_summary_text(&[], &errors).lines().count(), 2);
+    }
+
+    #[test]
+    fn should_cap_listed_open_files() {
+        let files: Vec<OpenFile> = (0..5)
+            .map(|fd| OpenFile {
+                fd,
+                kind: FileKind::Pipe,
+                target: format!("pipe:[{}]", 100 + fd),
+            })
+            .collect();
+        assert_eq!(
+            open_files_text(&files, 2),
+            "    0 PIPE pipe:[100]\n    1 PIPE pipe:[101]\n… and 3 more"
+        );
+        assert_eq!(open_files_text(&files, 5).lines().count(), 5);
+        assert_eq!(open_files_text(&[], 5), "Process has no open files");
+    }
+
+    #[test]
+    fn should_warn_about_deleted_binary_in_process_details() {
+        let mut process = Process {
+            cmd: "nginx".to_string(),
+            ..Default::default()
+        };
+        let text = |process: &Process| -> Vec<String> {
+            process_details_lines(Some(process), &[], StartTimeFormat::Time)
+                .iter()
+                .map(|line| line.to_string())
+                .collect()
+        };
+        assert!(!text(&process)
+            .iter()
+            .any(|line| line.starts_with("DELETED")));
+
+        process.exe_deleted = true;
+        assert_eq!(
+            text(&process)[1..3],
+            [
+                "CMD: nginx".to_string(),
+                "DELETED: binary on disk has changed since start".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn should_show_working_directory_in_process_details() {
+        let mut process = Process::default();
+        let cwd_line = |process: &Process| {
+            process_details_lines(Some(process), &[], StartTimeFormat::Time)[2].to_string()
+        };
+        assert_eq!(cwd_line(&process), "CWD: -");
+        process.cwd = Some("/home/user/project".to_string());
+        assert_eq!(cwd_line(&process), "CWD: /home/user/project");
+    }
+
+    #[test]
+    fn should_show_real_user_in_process_details_when_it_differs() {
+        let mut process = Process {
+            user_id: Uid::from_str("0").ok(),
+            real_user_id: Uid::from_str("0").ok(),
+            user_name: "root".to_string(),
+            real_user_name: "root".to_string(),
+            ..Default::default()
+        };
+        let first_line = |process: &Process| {
+            process_details_lines(Some(process), &[], StartTimeFormat::Time)[0].to_string()
+        };
+        assert!(first_line(&process).starts_with("USER: root PID: "));
+
+        process.real_user_id = Uid::from_str("65534").ok();
+        process.real_user_name = "nobody".to_string();
+        assert!(first_line(&process).starts_with("USER: root (real: nobody) PID: "));
+    }
+
+    #[test]
+    fn should_show_short_container_id_in_process_details() {
+        let mut process = Process {
+            pid: 1234,
+            ..Default::default()
+        };
+        let first_line = |process: &Process| {
+            process_details_lines(Some(process), &[], StartTimeFormat::Time)[0].to_string()
+        };
+        assert!(!first_line(&process).contains("CONTAINER"));
+
+        process.container_id = Some(format!("4f3c2a1b0e9d{}", "0".repeat(52)));
+        assert!(first_line(&process).ends_with(" CONTAINER: 4f3c2a1b0e9d"));
+        process.container_name = Some("web".to_string());
+        assert!(first_line(&process).ends_with(" CONTAINER: 4f3c2a1b0e9d web"));
+    }
+
+    #[test]
+    fn should_show_systemd_unit_in_process_details() {
+        let mut process = Process {
+            pid: 1234,
+            ..Default::default()
+        };
+        let first_line = |process: &Process| {
+            process_details_lines(Some(process), &[], StartTimeFormat::Time)[0].to_string()
+        };
+        assert!(!first_line(&process).contains("UNIT"));
+
+        process.unit = Some("nginx.service".to_string());
+        assert!(first_line(&process).ends_with(" UNIT: nginx.service"));
+    }
+
+    #[test]
+    fn should_list_connections_in_process_details() {
+        let process = Process {
+            pid: 1234,
+            ..Default::default()
+        };
+        let text = |lines: Vec<Line>| -> Vec<String> {
+            lines.iter().skip(4).map(|line| line.to_string()).collect()
+        };
+        assert_eq!(
+            text(process_details_lines(
+                Some(&process),
+                &[],
+                StartTimeFormat::Time
+            )),
+            vec!["CONNECTIONS: no network activity"]
+        );
+        let connections = vec![
+            Connection {
+                protocol: Protocol::Tcp,
+                state: "LISTEN",
+                local: "0.0.0.0:8080".parse().unwrap(),
+                remote: None,
+            },
+            Connection {
+                protocol: Protocol::Tcp,
+                state: "ESTABLISHED",
+                local: "127.0.0.1:8080".parse().unwrap(),
+                remote: Some("127.0.0.1:51234".parse().unwrap()),
+            },
+        ];
+        assert_eq!(
+            text(process_details_lines(
+                Some(&process),
+                &connections,
+                StartTimeFormat::Time
+            )),
+            vec![
+                "CONNECTIONS:",
+                "  TCP LISTEN      0.0.0.0:8080",
+                "  TCP ESTABLISHED 127.0.0.1:8080 -> 127.0.0.1:51234",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_highlight_matches_in_multibyte_text() {
+        let highlight = Style::new().bold();
+        let line = highlight_matches("/home/żółw/bin/zażółć", &[6, 7, 8, 9], highlight);
+        assert_eq!(
+            line.spans,
+            vec![
+                Span::raw("/home/"),
+                Span::styled("żółw", highlight),
+                Span::raw("/bin/zażółć"),
+            ]
+        );
+
+        let line = highlight_matches("zażółć", &[5], highlight);
+        assert_eq!(
+            line.spans,
+            vec![Span::raw("zażół"), Span::styled("ć", highlight)]
+        );
+    }
+
+    #[test]
+    fn should_highlight_prefix() {
+        let highlight = Style::new().bold();
+        let line = highlight_prefix("1234".to_string(), 2, highlight);
+        assert_eq!(
+            line.spans,
+            vec![Span::styled("12", highlight), Span::raw("34")]
+        );
+        let line = highlight_prefix("12".to_string(), 0, highlight);
+        assert_eq!(line.spans, vec![Span::raw("12")]);
+        let line = highlight_prefix("12".to_string(), 5, highlight);
+        assert_eq!(line.spans, vec![Span::raw("12")]);
+    }
+
+    #[test]
+    fn should_count_single_line_when_no_process_selected() {
+        let lines = process_details_lines(None, &[], StartTimeFormat::Time);
+        assert_eq!(wrapped_line_count(&lines, 40), 1);
+    }
+
+    #[test]
+    fn should_generate_help_from_configured_keys() {
+        assert_eq!(
+            help_text(&KeyConfig::default(), EnterAction::Details),
+            "ESC/<C+C> quit | <C+X> kill process | ENTER details | <C+SPACE> mark | <C+R> refresh | <C+S> sort | <C+F> details forward | <C+B> details backward | F1 help "
+        );
+        let keys: KeyConfig = toml::from_str(
+            r#"quit = ["ctrl+q"]
+kill = "alt+k"
+popup_up = "ctrl+p"
+mark = "alt+space"
+help = "f2""#,
+        )
+        .unwrap();
+        let help = help_text(&keys, EnterAction::Print);
+        assert!(
+            help.starts_with("<C+Q> quit | <A+K> kill process | ENTER print | <A+SPACE> mark |")
+        );
+        assert!(help.ends_with("| F2 help "));
+        assert!(normal_mode_help_text(&keys).ends_with("| F2 help "));
+        let help = keybindings_help(&keys);
+        assert!(help.starts_with("CTRL+Q                       quit\nALT+K  "));
+        assert!(help.contains("CTRL+P | DOWN                scroll by line"));
+    }
+
+    #[test]
+    fn should_show_totals_of_matching_processes() {
+        assert_eq!(totals_label(&ResultsTotals::default()), " no matches ");
+        let totals = ResultsTotals {
+            count: 3,
+            memory: 3 * 1024 * 1024 / 2,
+            cpu_usage: 12.34,
+        };
+        assert_eq!(
+            totals_label(&totals),
+            " 3 processes | MEM 1.5 MiB | CPU 12.3% "
+        );
+    }
+
+    #[test]
+    fn should_keep_match_visible_in_truncated_cell() {
+        let highlight = Style::new().add_modifier(Modifier::BOLD);
+        let line =
+            highlight_visible_matches("--config=/etc/app.toml", &[18, 19, 20, 21], highlight, 9);
+        assert_eq!(
+            line.spans,
+            vec![
+                Span::raw("…"),
+                Span::raw("pp."),
+                Span::styled("toml", highlight),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_not_scroll_text_when_match_fits_in_cell() {
+        let highlight = Style::new().add_modifier(Modifier::BOLD);
+        let line = highlight_visible_matches("--config=/etc/app.toml", &[2, 3], highlight, 9);
+        assert_eq!(line.spans[0], Span::raw("--"));
+        assert_eq!(line.spans[1], Span::styled("co", highlight));
+    }
+
+    #[test]
+    fn should_scroll_truncated_cell_until_its_end_is_visible() {
+        let highlight = Style::new().add_modifier(Modifier::BOLD);
+        let text = "/usr/lib/jvm/bin/java";
+        assert_eq!(
+            highlight_scrolled_matches(text, &[], highlight, 9, 0).spans,
+            vec![Span::raw(text)]
+        );
+        assert_eq!(
+            highlight_scrolled_matches(text, &[], highlight, 9, 8).spans,
+            vec![Span::raw("…"), Span::raw("/jvm/bin/java")]
+        );
+        assert_eq!(
+            highlight_scrolled_matches(text, &[17, 18, 19, 20], highlight, 9, 100).spans,
+            vec![
+                Span::raw("…"),
+                Span::raw("bin/"),
+                Span::styled("java", highlight),
+            ]
+        );
+        assert_eq!(
+            highlight_scrolled_matches("java", &[], highlight, 9, 8).spans,
+            vec![Span::raw("java")]
+        );
+    }
+
+    fn default_table_columns() -> Vec<Constraint> {
+        process_table_columns(&ColumnsConfig::default().table_columns())
+    }
+
+    #[test]
+    fn should_lay_out_columns_like_table() {
+        let constraints = default_table_columns();
+        let widths = process_table_column_widths(&constraints, Rect::new(0, 0, 102, 10));
+        assert_eq!(widths.len(), constraints.len());
+        assert_eq!(widths.iter().sum::<u16>() + 9, 99);
+    }
+
+    #[test]
+    fn should_add_threads_column_before_cmd() {
+        let default = default_table_columns();
+        let constraints = process_table_columns(
+            &ColumnsConfig {
+                threads: true,
+                ..Default::default()
+            }
+            .table_columns(),
+        );
+        assert_eq!(constraints.len(), default.len() + 1);
+        assert_eq!(constraints[7], Constraint::Percentage(5));
+        assert_eq!(
+            constraints[8..constraints.len() - 1],
+            default[7..default.len() - 1]
+        );
+        assert_eq!(constraints.last(), Some(&Constraint::Percentage(28)));
+        assert_eq!(default.last(), Some(&Constraint::Percentage(33)));
+    }
+
+    #[test]
+    fn should_add_io_columns_after_threads() {
+        let columns = ColumnsConfig {
+            threads: true,
+            io: true,
+            ..Default::default()
+        };
+        let constraints = process_table_columns(&columns.table_columns());
+        assert_eq!(constraints.len(), default_table_columns().len() + 3);
+        assert_eq!(
+            constraints[7..10],
+            [
+                Constraint::Percentage(5),
+                Constraint::Percentage(7),
+                Constraint::Percentage(7)
+            ]
+        );
+        assert_eq!(constraints.last(), Some(&Constraint::Percentage(14)));
+    }
+
+    #[test]
+    fn should_add_tty_column_after_other_optional_columns() {
+        let columns = ColumnsConfig {
+            threads: true,
+            io: true,
+            tty: true,
+            ..Default::default()
+        };
+        let constraints = process_table_columns(&columns.table_columns());
+        assert_eq!(constraints.len(), default_table_columns().len() + 4);
+        assert_eq!(constraints[10], Constraint::Percentage(6));
+        assert_eq!(constraints.last(), Some(&Constraint::Percentage(8)));
+    }
+
+    #[test]
+    fn should_give_space_of_hidden_columns_to_cmd() {
+        let constraints = process_table_columns(&[Column::Pid, Column::Cmd, Column::Mem]);
+        assert_eq!(
+            constraints,
+            [
+                Constraint::Percentage(5),
+                Constraint::Percentage(55),
+                Constraint::Percentage(7),
+                Constraint::Percentage(33)
+            ]
+        );
+        let constraints = process_table_columns(&[Column::Pid, Column::CmdPath]);
+        assert_eq!(constraints.last(), Some(&Constraint::Percentage(75)));
+    }
+
+    #[test]
+    fn should_fit_columns_into_available_width() {
+        let columns = [Column::Pid, Column::Cmd, Column::CmdPath];
+        let constraints = fit_table_columns(&columns, vec![5, 20, 40], &[3, 3, 8], 100);
+        assert_eq!(
+            constraints,
+            [
+                Constraint::Length(5),
+                Constraint::Length(20),
+                Constraint::Length(40),
+                Constraint::Fill(1)
+            ]
+        );
+        //NOTE: 3 spaces and 12 for the last column leave 45, path is cut first, then command
+        let constraints = fit_table_columns(&columns, vec![5, 20, 40], &[3, 3, 8], 60);
+        assert_eq!(constraints[..3], [5, 20, 20].map(Constraint::Length));
+        let constraints = fit_table_columns(&columns, vec![5, 20, 40], &[3, 3, 8], 30);
+        assert_eq!(constraints[..3], [5, 3, 8].map(Constraint::Length));
+    }
+
+    #[test]
+    fn should_fit_columns_to_rows_on_screen() {
+        //NOTE: 10 rows fit between borders and header
+        let area = Rect::new(0, 0, 80, 13);
+        assert_eq!(visible_rows(area, 0, Some(3), 100), 0..10);
+        assert_eq!(visible_rows(area, 20, Some(25), 100), 20..30);
+        assert_eq!(visible_rows(area, 20, Some(5), 100), 5..15);
+        assert_eq!(visible_rows(area, 20, Some(40), 100), 31..41);
+        assert_eq!(visible_rows(area, 95, None, 100), 95..100);
+        assert_eq!(visible_rows(area, 0, None, 0), 0..0);
+    }
+
+    #[test]
+    fn should_give_space_of_hidden_details_to_table() {
+        let area = Rect::new(0, 0, 80, 24);
+        let shown = layout_rects(area, false);
+        assert_eq!((shown[1].height, shown[2].height), (15, 7));
+        let hidden = layout_rects(area, true);
+        assert_eq!((hidden[1].height, hidden[2].height), (22, 0));
+        assert_eq!(hidden[3], shown[3]);
+        assert!(!hidden[2].contains((0, 22).into()));
+    }
+
+    #[test]
+    fn should_count_open_fds_of_selected_and_visible_rows() {
+        let tui = |fds: bool| {
+            let mut tui = Tui::new(
+                String::new(),
+                BTreeMap::new(),
+                KeyConfig::default(),
+                ColumnsConfig {
+                    fds,
+                    ..Default::default()
+                },
+                Theme::dark(),
+            );
+            tui.update_process_table_number_of_items(100);
+            tui.process_table_rows = 5;
+            tui.select_row_by_index(Some(2));
+            tui
+        };
+        assert_eq!(tui(false).rows_showing_open_fds(100), vec![2]);
+        assert_eq!(tui(true).rows_showing_open_fds(100), vec![0, 1, 2, 3, 4, 2]);
+        assert_eq!(tui(true).rows_showing_open_fds(3), vec![0, 1, 2, 2]);
+
+        let mut tui = tui(false);
+        tui.toggle_details();
+        assert!(tui.rows_showing_open_fds(100).is_empty());
+    }
+
+    #[test]
+    fn should_not_scroll_hidden_details() {
+        let mut tui = Tui::new(
+            String::new(),
+            BTreeMap::new(),
+            KeyConfig::default(),
+            ColumnsConfig::default(),
+            Theme::dark(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        tui.details_scroll().number_of_lines = 20;
+        tui.process_details_down(&mut terminal.get_frame());
+        tui.process_details_down(&mut terminal.get_frame());
+        assert_eq!(tui.details_scroll().offset, 2);
+
+        tui.toggle_details();
+        tui.process_details_down(&mut terminal.get_frame());
+        tui.process_details_up();
+        assert_eq!(tui.details_scroll().offset, 2);
+        tui.toggle_details();
+        assert_eq!(tui.details_scroll().offset, 0);
+    }
+
+    #[test]
+    fn should_keep_scroll_of_every_details_tab() {
+        let mut tui = Tui::new(
+            String::new(),
+            BTreeMap::new(),
+            KeyConfig::default(),
+            ColumnsConfig::default(),
+            Theme::dark(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        tui.details_scroll().number_of_lines = 20;
+        tui.process_details_down(&mut terminal.get_frame());
+        tui.cycle_details_tab();
+        assert_eq!(tui.details_tab, DetailsTab::Environment);
+        assert_eq!(tui.details_scroll().offset, 0);
+        tui.cycle_details_tab();
+        tui.cycle_details_tab();
+        assert_eq!(tui.details_tab, DetailsTab::Network);
+        tui.cycle_details_tab();
+        assert_eq!(tui.details_tab, DetailsTab::Info);
+        assert_eq!(tui.details_scroll().offset, 1);
+    }
+
+    #[test]
+    fn should_tell_apart_empty_results_and_scanning() {
+        assert_eq!(empty_table_message(0, true), Some("No matching processes"));
+        assert_eq!(empty_table_message(0, false), Some("Scanning…"));
+        assert_eq!(empty_table_message(3, true), None);
+        assert_eq!(empty_table_message(3, false), None);
+    }
+
+    #[test]
+    fn should_render_message_in_empty_table() {
+        let mut tui = Tui::new(
+            String::new(),
+            BTreeMap::new(),
+            KeyConfig::default(),
+            ColumnsConfig::default(),
+            Theme::dark(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+        let results = ProcessSearchResults::empty();
+        let mut render = |tui: &mut Tui| {
+            terminal
+                .draw(|f| tui.render_process_table(f, &results, f.area()))
+                .unwrap();
+            let buffer = terminal.backend().buffer();
+            (0..buffer.area.height)
+                .map(|y| {
+                    (0..buffer.area.width)
+                        .map(|x| buffer[(x, y)].symbol())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert!(render(&mut tui)[5].contains("Scanning…"));
+
+        //NOTE: refresh running in background does not hide that nothing matched
+        tui.update_process_table_number_of_items(0);
+        tui.set_refreshing(true);
+        let rows = render(&mut tui);
+        //NOTE: rows between header and bottom border are 2..=8
+        assert!(rows[5].contains("No matching processes"), "{}", rows[5]);
+        assert!(rows
+            .iter()
+            .enumerate()
+            .all(|(y, row)| y == 5 || !row.contains("No matching")));
+    }
+
+    #[test]
+    fn should_show_info_tab_after_search() {
+        let mut tui = Tui::new(
+            String::new(),
+            BTreeMap::new(),
+            KeyConfig::default(),
+            ColumnsConfig::default(),
+            Theme::dark(),
+        );
+        tui.update_process_table_number_of_items(3);
+        tui.cycle_details_tab();
+        assert_eq!(tui.details_tab, DetailsTab::Environment);
+        tui.update_process_table_number_of_items(2);
+        assert_eq!(tui.get_selected_row_index(), Some(0));
+        assert_eq!(tui.details_tab, DetailsTab::Info);
+        tui.update_process_table_number_of_items(0);
+        assert_eq!(tui.get_selected_row_index(), None);
+    }
+
+    #[test]
+    fn should_show_info_tab_of_newly_selected_process() {
+        let mut tui = Tui::new(
+            String::new(),
+            BTreeMap::new(),
+            KeyConfig::default(),
+            ColumnsConfig::default(),
+            Theme::dark(),
+        );
+        tui.update_process_table_number_of_items(3);
+        tui.select_row_by_index(Some(0));
+        tui.cycle_details_tab();
+        tui.select_row_by_index(Some(0));
+        assert_eq!(tui.details_tab, DetailsTab::Environment);
+        tui.select_row_by_index(Some(1));
+        assert_eq!(tui.details_tab, DetailsTab::Info);
+    }
+
+    #[test]
+    fn should_highlight_active_details_tab_in_title() {
+        let title = details_tabs_title(DetailsTab::Files);
+        assert_eq!(
+            title.to_string(),
+            "  Info   Environment   Files   Network  "
+        );
+        let active: Vec<_> = title
+            .spans
+            .iter()
+            .filter(|span| span.style.add_modifier.contains(Modifier::REVERSED))
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(active, vec![" Files "]);
+    }
+
+    #[test]
+    fn should_list_connections_in_network_tab() {
+        assert_eq!(
+            network_lines(Some(&[]))[0].to_string(),
+            "No network activity"
+        );
+        let connections = [Connection {
+            protocol: Protocol::Tcp,
+            state: "LISTEN",
+            local: "127.0.0.1:8080".parse().unwrap(),
+            remote: None,
+        }];
+        assert_eq!(network_lines(Some(&connections)).len(), 1);
+        assert_eq!(network_lines(None)[0].to_string(), "No process selected");
+    }
+
+    #[test]
+    fn should_show_terminal_in_process_details() {
+        let mut process = Process::default();
+        let first_line = |process: &Process| {
+            process_details_lines(Some(process), &[], StartTimeFormat::Time)[0].to_string()
+        };
+        assert!(first_line(&process).contains(" TTY: - IO:"));
+        process.tty = Some("pts/3".to_string());
+        assert!(first_line(&process).contains(" TTY: pts/3 IO:"));
+    }
+
+    #[test]
+    fn should_list_args_and_ports_one_per_line_in_details_popup() {
+        let process = Process {
+            pid: 4321,
+            parent_pid: Some(1),
+            parent_cmd: Some("systemd".to_string()),
+            cmd: "java".to_string(),
+            args: "-Dlist=a,b,-jar,app.jar".to_string(),
+            ports: Some("8080, 8443".to_string()),
+            ..Default::default()
+        };
+        let args = [
+            "-Dlist=a,b".to_string(),
+            "-jar".to_string(),
+            "app.jar".to_string(),
+        ];
+        let text = process_details_text(&process, Some(&args), &[], StartTimeFormat::Time);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "PID:         4321");
+        assert_eq!(lines[1], "PARENT:      1 (systemd)");
+        assert!(lines.contains(&"CMD:         java"));
+        assert!(text.contains("\nARGS:\n  -Dlist=a,b\n  -jar\n  app.jar\n"));
+        assert!(text.contains("\nPORTS:\n  8080\n  8443\n"));
+        assert!(text.ends_with("CONNECTIONS: no network activity"));
+
+        //NOTE: joined args are shown when process is gone
+        let text = process_details_text(&process, None, &[], StartTimeFormat::Time);
+        assert!(text.contains("\nARGS:        -Dlist=a,b,-jar,app.jar\n"));
+        let text = process_details_text(&process, Some(&[]), &[], StartTimeFormat::Time);
+        assert!(text.contains("\nARGS:        -\n"));
+    }
+
+    #[test]
+    fn should_show_parent_command_in_process_details() {
+        let mut process = Process {
+            pid: 4321,
+            ..Default::default()
+        };
+        let first_line = |process: &Process| {
+            process_details_lines(Some(process), &[], StartTimeFormat::Time)[0].to_string()
+        };
+        assert!(first_line(&process).contains(" PID: 4321 STATE:"));
+        process.parent_pid = Some(1234);
+        assert!(first_line(&process).contains(" PID: 4321 PARENT: 1234 STATE:"));
+        process.parent_cmd = Some("systemd".to_string());
+        assert!(first_line(&process).contains(" PID: 4321 PARENT: 1234 (systemd) STATE:"));
+    }
+
+    #[test]
+    fn should_show_cpu_time_next_to_run_time_in_process_details() {
+        let mut process = Process::default();
+        let first_line = |process: &Process| {
+            process_details_lines(Some(process), &[], StartTimeFormat::Time)[0].to_string()
+        };
+        assert!(first_line(&process).contains(" CPU_TIME: - MEMORY:"));
+        process.cpu_time = Some(Duration::from_secs(3723));
+        assert!(first_line(&process).contains(" CPU_TIME: 1:02:03 MEMORY:"));
+    }
+
+    #[test]
+    fn should_show_dash_for_unknown_io_rate_in_process_details() {
+        let mut process = Process::default();
+        let first_line = |process: &Process| {
+            process_details_lines(Some(process), &[], StartTimeFormat::Time)[0].to_string()
+        };
+        assert!(first_line(&process).ends_with(" IO: -"));
+
+        process.io_rate = Some(IoRate {
+            read: 2048,
+            write: 0,
+        });
+        assert!(first_line(&process).contains(" IO: R 2.0 KiB/s W 0 B/s"));
+    }
+
+    #[test]
+    fn should_keep_relative_position_of_selection_after_resize() {
+        //NOTE: selected row is in the middle of 20 rows, then of 10 and 40 rows
+        assert_eq!(offset_after_resize(60, 50, 20, 10, 100), 55);
+        assert_eq!(offset_after_resize(60, 50, 20, 40, 100), 40);
+        //NOTE: selected at the bottom stays at the bottom, so it is visible
+        assert_eq!(offset_after_resize(69, 50, 20, 10, 100), 60);
+        assert_eq!(offset_after_resize(50, 50, 20, 10, 100), 50);
+    }
+
+    #[test]
+    fn should_not_scroll_table_past_last_row_after_resize() {
+        assert_eq!(offset_after_resize(95, 80, 20, 40, 100), 60);
+        assert_eq!(offset_after_resize(5, 0, 20, 40, 10), 0);
+        assert_eq!(offset_after_resize(5, 3, 20, 0, 10), 3);
+    }
+
+    #[test]
+    fn should_clamp_details_scroll_to_content() {
+        assert_eq!(clamp_scroll_offset(3, 10, 5), 3);
+        assert_eq!(clamp_scroll_offset(8, 10, 5), 5);
+        assert_eq!(clamp_scroll_offset(4, 10, 20), 0);
+        assert_eq!(table_viewport_rows(Rect::new(0, 5, 80, 10)), 7);
+        assert_eq!(table_viewport_rows(Rect::new(0, 0, 80, 2)), 0);
+    }
+
+    #[test]
+    fn should_compute_details_scrollbar_state_inside_borders() {
+        //NOTE: area of 7 rows has 5 rows of content between borders
+        assert_eq!(details_scrollbar_state(5, 0, 7), None);
+        assert_eq!(details_scrollbar_state(3, 0, 7), None);
+        assert_eq!(details_scrollbar_state(0, 0, 0), None);
+        assert_eq!(
+            details_scrollbar_state(6, 0, 7),
+            Some(ScrollbarState::new(1).position(0))
+        );
+        assert_eq!(
+            details_scrollbar_state(20, 4, 7),
+            Some(ScrollbarState::new(15).position(4))
+        );
+        assert_eq!(
+            details_scrollbar_state(20, 30, 7),
+            Some(ScrollbarState::new(15).position(15))
+        );
+    }
+
+    #[test]
+    fn should_map_clicked_row_to_process_index() {
+        //NOTE: border at y=5, header at y=6, rows from y=7, bottom border at y=14
+        let area = Rect::new(0, 5, 80, 10);
+        assert_eq!(table_row_at(area, 0, 100, 5), None);
+        assert_eq!(table_row_at(area, 0, 100, 6), None);
+        assert_eq!(table_row_at(area, 0, 100, 7), Some(0));
+        assert_eq!(table_row_at(area, 0, 100, 13), Some(6));
+        assert_eq!(table_row_at(area, 0, 100, 14), None);
+        assert_eq!(table_row_at(area, 20, 100, 9), Some(22));
+        assert_eq!(table_row_at(area, 0, 2, 9), None);
+    }
+
+    #[test]
+    fn should_detect_double_click_on_the_same_row() {
+        let first = Instant::now();
+        let soon = first + Duration::from_millis(200);
+        assert!(is_double_click(Some((3, first)), 3, soon));
+        assert!(!is_double_click(Some((3, first)), 4, soon));
+        assert!(!is_double_click(
+            Some((3, first)),
+            3,
+            first + Duration::from_secs(1)
+        ));
+        assert!(!is_double_click(None, 3, soon));
+    }
+
+    #[test]
+    fn should_scroll_table_without_wrapping() {
+        let mut tui = Tui::new(
+            String::new(),
+            BTreeMap::new(),
+            KeyConfig::default(),
+            ColumnsConfig::default(),
+            Theme::dark(),
+        );
+        tui.scroll_table(true);
+        assert_eq!(tui.get_selected_row_index(), None);
+
+        tui.update_process_table_number_of_items(2);
+        tui.scroll_table(false);
+        assert_eq!(tui.get_selected_row_index(), Some(0));
+        tui.scroll_table(false);
+        assert_eq!(tui.get_selected_row_index(), Some(0));
+        tui.scroll_table(true);
+        tui.scroll_table(true);
+        assert_eq!(tui.get_selected_row_index(), Some(1));
+    }
+
+    #[test]
+    fn should_wrap_selection_at_both_ends_of_table() {
+        let mut tui = Tui::new(
+            String::new(),
+            BTreeMap::new(),
+            KeyConfig::default(),
+            ColumnsConfig::default(),
+            Theme::dark(),
+        );
+        tui.update_process_table_number_of_items(5);
+        tui.select_row_by_index(Some(0));
+        tui.select_previous_row(1);
+        assert_eq!(tui.get_selected_row_index(), Some(4));
+        tui.select_next_row(1);
+        assert_eq!(tui.get_selected_row_index(), Some(0));
+
+        tui.select_row_by_index(Some(2));
+        tui.select_previous_row(2);
+        assert_eq!(tui.get_selected_row_index(), Some(0));
+        tui.select_previous_row(10);
+        assert_eq!(tui.get_selected_row_index(), Some(4));
+        tui.select_previous_row(3);
+        assert_eq!(tui.get_selected_row_index(), Some(1));
+        tui.select_next_row(3);
+        assert_eq!(tui.get_selected_row_index(), Some(4));
+        tui.select_next_row(10);
+        assert_eq!(tui.get_selected_row_index(), Some(0));
+        tui.select_next_row(usize::MAX);
+        assert_eq!(tui.get_selected_row_index(), Some(0));
+    }
+
+    #[test]
+    fn should_stop_at_first_and_last_row_without_wrapping() {
+        let mut tui = Tui::new(
+            String::new(),
+            BTreeMap::new(),
+            KeyConfig::default(),
+            ColumnsConfig::default(),
+            Theme::dark(),
+        )
+        .with_wrap_selection(false);
+        tui.update_process_table_number_of_items(5);
+
+        tui.select_row_by_index(Some(1));
+        tui.select_previous_row(3);
+        assert_eq!(tui.get_selected_row_index(), Some(0));
+        tui.select_previous_row(1);
+        assert_eq!(tui.get_selected_row_index(), Some(0));
+        tui.select_next_row(3);
+        assert_eq!(tui.get_selected_row_index(), Some(3));
+        tui.select_next_row(10);
+        assert_eq!(tui.get_selected_row_index(), Some(4));
+        tui.select_next_row(usize::MAX);
+        assert_eq!(tui.get_selected_row_index(), Some(4));
+
+        tui.update_process_table_number_of_items(0);
+        tui.select_next_row(1);
+        assert_eq!(tui.get_selected_row_index(), None);
+        tui.select_previous_row(10);
+        assert_eq!(tui.get_selected_row_index(), None);
+    }
+
+    #[test]
+    fn should_navigate_with_vim_keys_in_normal_mode() {
+        let mut tui = Tui::new(
+            "java".to_string(),
+            BTreeMap::new(),
+            KeyConfig::default(),
+            ColumnsConfig::default(),
+            Theme::dark(),
+        );
+        tui.update_process_table_number_of_items(5);
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert!(!tui.handle_normal_mode_key(key(KeyCode::Char('j'))));
+
+        tui.toggle_normal_mode();
+        assert!(tui.handle_normal_mode_key(key(KeyCode::Char('j'))));
+        assert_eq!(tui.get_selected_row_index(), Some(1));
+        tui.handle_normal_mode_key(KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT));
+        assert_eq!(tui.get_selected_row_index(), Some(4));
+        tui.handle_normal_mode_key(key(KeyCode::Char('k')));
+        assert_eq!(tui.get_selected_row_index(), Some(3));
+        tui.handle_normal_mode_key(key(KeyCode::Char('g')));
+        assert_eq!(tui.get_selected_row_index(), Some(0));
+        assert!(tui.handle_normal_mode_key(key(KeyCode::Char('x'))));
+        assert!(tui.handle_normal_mode_key(key(KeyCode::Char('l'))));
+        assert!(tui.handle_normal_mode_key(key(KeyCode::Right)));
+        assert_eq!(tui.cmd_scroll, 2 * CMD_SCROLL_STEP);
+        tui.handle_normal_mode_key(key(KeyCode::Char('h')));
+        tui.handle_normal_mode_key(key(KeyCode::Left));
+        tui.handle_normal_mode_key(key(KeyCode::Left));
+        assert_eq!(tui.cmd_scroll, 0);
+        assert!(!tui.handle_normal_mode_key(key(KeyCode::Enter)));
+        assert!(
+            !tui.handle_normal_mode_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(tui.search_input_text(), "java");
+
+        tui.handle_normal_mode_key(key(KeyCode::Char('/')));
+        assert!(!tui.handle_normal_mode_key(key(KeyCode::Char('j'))));
+    }
+
+    #[test]
+    fn should_not_select_row_in_empty_table() {
+        let mut tui = Tui::new(
+            String::new(),
+            BTreeMap::new(),
+            KeyConfig::default(),
+            ColumnsConfig::default(),
+            Theme::dark(),
+        );
+        tui.select_next_row(1);
+        assert_eq!(tui.get_selected_row_index(), None);
+        tui.select_previous_row(10);
+        assert_eq!(tui.get_selected_row_index(), None);
+    }
+
+    #[test]
+    fn should_toggle_order_when_same_sort_column_is_picked() {
+        let mut tui = Tui::new(
+            String::new(),
+            BTreeMap::new(),
+            KeyConfig::default(),
+            ColumnsConfig::default(),
+            Theme::dark(),
+        );
+        let sort = |column, order| Some(Sort { column, order });
+        tui.sort_by(SortColumn::Memory);
+        assert_eq!(tui.sort(), sort(SortColumn::Memory, SortOrder::Ascending));
+        tui.sort_by(SortColumn::Memory);
+        assert_eq!(tui.sort(), sort(SortColumn::Memory, SortOrder::Descending));
+        tui.sort_by(SortColumn::Pid);
+        assert_eq!(tui.sort(), sort(SortColumn::Pid, SortOrder::Ascending));
+        assert!(!tui.sortable_columns().contains(&SortColumn::Threads));
+    }
+
+    #[test]
+    fn should_sort_by_threads_only_when_column_is_shown() {
+        let sorted_columns = |columns: ColumnsConfig| {
+            let mut tui = Tui::new(
+                String::new(),
+                BTreeMap::new(),
+                KeyConfig::default(),
+                columns,
+                Theme::dark(),
+            );
+            let mut sorted = vec![];
+            tui.cycle_sort_column();
+            while let Some(sort) = tui.sort() {
+                sorted.push(sort.column);
+                tui.cycle_sort_column();
+            }
+            sorted
+        };
+        assert!(!sorted_columns(ColumnsConfig::default()).contains(&SortColumn::Threads));
+        let visible = ColumnsConfig {
+            visible: Some(vec![Column::Pid, Column::Cmd]),
+            ..Default::default()
+        };
+        assert_eq!(
+            sorted_columns(visible),
+            vec![SortColumn::Pid, SortColumn::Cmd]
+        );
+        let threads = ColumnsConfig {
+            threads: true,
+            ..Default::default()
+        };
+        assert!(sorted_columns(threads).contains(&SortColumn::Threads));
+    }
 }
@@ -0,0 +1,83 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Local, TimeZone};
+
+use crate::config::StartTimeFormat;
+
+/// Formats process start time (seconds since epoch) in local time zone
+pub fn format_start_time(format: StartTimeFormat, start_timestamp: u64) -> String {
+    let start: DateTime<Local> = (UNIX_EPOCH + Duration::from_secs(start_timestamp)).into();
+    let now: DateTime<Local> = SystemTime::now().into();
+    format_start_time_at(format, start, now)
+}
+
+fn format_start_time_at<Tz: TimeZone>(
+    format: StartTimeFormat,
+    start: DateTime<Tz>,
+    now: DateTime<Tz>,
+) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    match format {
+        StartTimeFormat::Time => start.format("%H:%M:%S").to_string(),
+        StartTimeFormat::Relative => {
+            //NOTE: start time is rounded to seconds, so it can be slightly in the future
+            let seconds = (now - start).num_seconds().max(0);
+            format!("{} ago", relative_duration(seconds))
+        }
+        StartTimeFormat::Date if start.date_naive() == now.date_naive() => {
+            start.format("%H:%M").to_string()
+        }
+        StartTimeFormat::Date => start.format("%Y-%m-%d").to_string(),
+    }
+}
+
+/// Largest whole unit of the duration, i.e. '2h' for 2 hours 59 minutes
+fn relative_duration(seconds: i64) -> String {
+    match seconds {
+        0..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m", seconds / 60),
+        3600..86400 => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn should_format_time_of_day() {
+        let start = at("2024-05-01T14:32:05Z");
+        let formatted =
+            format_start_time_at(StartTimeFormat::Time, start, at("2024-05-03T10:00:00Z"));
+        assert_eq!(formatted, "14:32:05");
+    }
+
+    #[test]
+    fn should_format_time_since_start() {
+        let now = at("2024-05-03T10:00:00Z");
+        let relative =
+            |start: &str| format_start_time_at(StartTimeFormat::Relative, at(start), now);
+        assert_eq!(relative("2024-05-03T09:59:18Z"), "42s ago");
+        assert_eq!(relative("2024-05-03T09:48:00Z"), "12m ago");
+        assert_eq!(relative("2024-05-03T07:01:00Z"), "2h ago");
+        assert_eq!(relative("2024-04-30T09:00:00Z"), "3d ago");
+        assert_eq!(relative("2024-05-03T10:00:01Z"), "0s ago");
+    }
+
+    #[test]
+    fn should_format_date_for_processes_started_before_today() {
+        let now = at("2024-05-03T10:00:00Z");
+        let date = |start: &str| format_start_time_at(StartTimeFormat::Date, at(start), now);
+        assert_eq!(date("2024-05-03T00:15:00Z"), "00:15");
+        assert_eq!(date("2024-05-02T23:59:00Z"), "2024-05-02");
+    }
+}
@@ -0,0 +1,104 @@
+use std::borrow::Cow;
+
+use ratatui::symbols::{border, scrollbar};
+
+/// Glyphs used to draw borders, scrollbars and arrows, ascii ones work on dumb terminals
+#[derive(Debug, Clone)]
+pub struct Symbols {
+    /// Popups
+    pub(super) thick_border: border::Set,
+    /// Process table
+    pub(super) plain_border: border::Set,
+    /// Process details
+    pub(super) rounded_border: border::Set,
+    pub(super) scrollbar: scrollbar::Set,
+    pub(super) scroll_up: &'static str,
+    pub(super) scroll_down: &'static str,
+    pub(super) ascending: &'static str,
+    pub(super) descending: &'static str,
+    ascii: bool,
+}
+
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+const ASCII_SCROLLBAR: scrollbar::Set = scrollbar::Set {
+    track: "|",
+    thumb: "#",
+    begin: "^",
+    end: "v",
+};
+
+impl Symbols {
+    pub fn unicode() -> Self {
+        Self {
+            thick_border: border::THICK,
+            plain_border: border::PLAIN,
+            rounded_border: border::ROUNDED,
+            scrollbar: scrollbar::DOUBLE_VERTICAL,
+            scroll_up: "↑",
+            scroll_down: "↓",
+            ascending: "▲",
+            descending: "▼",
+            ascii: false,
+        }
+    }
+
+    pub fn ascii() -> Self {
+        Self {
+            thick_border: ASCII_BORDER,
+            plain_border: ASCII_BORDER,
+            rounded_border: ASCII_BORDER,
+            scrollbar: ASCII_SCROLLBAR,
+            scroll_up: "^",
+            scroll_down: "v",
+            ascending: "^",
+            descending: "v",
+            ascii: true,
+        }
+    }
+
+    /// Box drawing chars of tree view are replaced one by one, so prefix keeps its width
+    pub(super) fn tree_prefix<'a>(&self, prefix: &'a str) -> Cow<'a, str> {
+        if !self.ascii || prefix.is_ascii() {
+            return Cow::Borrowed(prefix);
+        }
+        let prefix = prefix
+            .chars()
+            .map(|c| match c {
+                '├' | '│' => '|',
+                '└' => '`',
+                '─' => '-',
+                '▸' => '>',
+                '▾' => 'v',
+                c if c.is_ascii() => c,
+                _ => '?',
+            })
+            .collect();
+        Cow::Owned(prefix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_draw_tree_prefix_with_ascii_chars() {
+        let prefix = "│  ├─ ▸ ";
+        assert_eq!(Symbols::unicode().tree_prefix(prefix), prefix);
+        let ascii = Symbols::ascii().tree_prefix(prefix);
+        assert_eq!(ascii, "|  |- > ");
+        assert_eq!(ascii.chars().count(), prefix.chars().count());
+        assert_eq!(Symbols::ascii().tree_prefix("└─ "), "`- ");
+        assert_eq!(Symbols::ascii().tree_prefix("▾ "), "v ");
+    }
+}
@@ -0,0 +1,185 @@
+use std::str::FromStr;
+
+use ratatui::style::{palette::tailwind, Color};
+
+use crate::config::{ThemeConfig, ThemePreset};
+
+pub struct Theme {
+    pub(super) row_fg: Color,
+    pub(super) selected_style_fg: Color,
+    pub(super) normal_row_color: Color,
+    pub(super) alt_row_color: Color,
+    pub(super) process_table_border_color: Color,
+    pub(super) match_highlight: Color,
+    pub(super) marked_row_color: Color,
+    /// Processes pinned to the top of the table
+    pub(super) pinned_row_color: Color,
+    pub(super) zombie_row_fg: Color,
+    /// Pik itself and its ancestors, shown when they are not hidden
+    pub(super) self_row_fg: Color,
+    /// Path of binary that was deleted or replaced since process started
+    pub(super) deleted_exe_fg: Color,
+    /// Processes stopped by signal, i.e. paused with SIGSTOP
+    pub(super) stopped_row_fg: Color,
+    /// Processes run by root
+    pub(super) root_row_fg: Color,
+    /// Processes run by the user running pik
+    pub(super) own_row_fg: Color,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            row_fg: tailwind::SLATE.c200,
+            selected_style_fg: tailwind::BLUE.c400,
+            normal_row_color: tailwind::SLATE.c950,
+            alt_row_color: tailwind::SLATE.c900,
+            process_table_border_color: tailwind::BLUE.c400,
+            match_highlight: tailwind::AMBER.c400,
+            marked_row_color: tailwind::INDIGO.c800,
+            pinned_row_color: tailwind::EMERALD.c900,
+            zombie_row_fg: tailwind::RED.c400,
+            self_row_fg: tailwind::ORANGE.c400,
+            deleted_exe_fg: tailwind::YELLOW.c400,
+            stopped_row_fg: tailwind::SKY.c400,
+            root_row_fg: tailwind::FUCHSIA.c300,
+            own_row_fg: tailwind::TEAL.c200,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            row_fg: tailwind::SLATE.c800,
+            selected_style_fg: tailwind::BLUE.c600,
+            normal_row_color: tailwind::SLATE.c50,
+            alt_row_color: tailwind::SLATE.c200,
+            process_table_border_color: tailwind::BLUE.c600,
+            match_highlight: tailwind::AMBER.c700,
+            marked_row_color: tailwind::INDIGO.c200,
+            pinned_row_color: tailwind::EMERALD.c100,
+            zombie_row_fg: tailwind::RED.c600,
+            self_row_fg: tailwind::ORANGE.c600,
+            deleted_exe_fg: tailwind::YELLOW.c700,
+            stopped_row_fg: tailwind::SKY.c700,
+            root_row_fg: tailwind::FUCHSIA.c700,
+            own_row_fg: tailwind::TEAL.c800,
+        }
+    }
+
+    //NOTE: no gray of basic palette works on both dark and light background, rows don't alternate
+    pub fn basic() -> Self {
+        Self {
+            row_fg: Color::Reset,
+            selected_style_fg: Color::Blue,
+            normal_row_color: Color::Reset,
+            alt_row_color: Color::Reset,
+            process_table_border_color: Color::Blue,
+            match_highlight: Color::Yellow,
+            marked_row_color: Color::Blue,
+            pinned_row_color: Color::DarkGray,
+            zombie_row_fg: Color::Red,
+            self_row_fg: Color::LightRed,
+            deleted_exe_fg: Color::LightYellow,
+            stopped_row_fg: Color::LightBlue,
+            root_row_fg: Color::Magenta,
+            own_row_fg: Color::Cyan,
+        }
+    }
+
+    /// Starts from the preset and overrides configured colors.
+    /// Colors that cannot be parsed keep preset value, warning is returned for each of them
+    pub fn from_config(config: &ThemeConfig) -> (Self, Vec<String>) {
+        let mut theme = match config.preset {
+            ThemePreset::Dark => Self::dark(),
+            ThemePreset::Light => Self::light(),
+            ThemePreset::Basic => Self::basic(),
+        };
+        let mut warnings = vec![];
+        for (name, value, color) in [
+            ("row_fg", &config.row_fg, &mut theme.row_fg),
+            (
+                "selected_style_fg",
+                &config.selected_style_fg,
+                &mut theme.selected_style_fg,
+            ),
+            (
+                "normal_row_color",
+                &config.normal_row_color,
+                &mut theme.normal_row_color,
+            ),
+            (
+                "alt_row_color",
+                &config.alt_row_color,
+                &mut theme.alt_row_color,
+            ),
+            (
+                "process_table_border_color",
+                &config.process_table_border_color,
+                &mut theme.process_table_border_color,
+            ),
+            (
+                "zombie_row_fg",
+                &config.zombie_row_fg,
+                &mut theme.zombie_row_fg,
+            ),
+            (
+                "stopped_row_fg",
+                &config.stopped_row_fg,
+                &mut theme.stopped_row_fg,
+            ),
+            ("root_row_fg", &config.root_row_fg, &mut theme.root_row_fg),
+            ("own_row_fg", &config.own_row_fg, &mut theme.own_row_fg),
+        ] {
+            let Some(value) = value else {
+                continue;
+            };
+            match Color::from_str(value) {
+                Ok(parsed) => *color = parsed,
+                Err(_) => warnings.push(format!("Invalid color '{}' of theme.{}", value, name)),
+            }
+        }
+        (theme, warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_override_preset_colors() {
+        let config = ThemeConfig {
+            preset: ThemePreset::Light,
+            row_fg: Some("#102030".to_string()),
+            alt_row_color: Some("blue".to_string()),
+            root_row_fg: Some("red".to_string()),
+            ..Default::default()
+        };
+        let (theme, warnings) = Theme::from_config(&config);
+        assert_eq!(theme.row_fg, Color::Rgb(0x10, 0x20, 0x30));
+        assert_eq!(theme.alt_row_color, Color::Blue);
+        assert_eq!(theme.root_row_fg, Color::Red);
+        assert_eq!(theme.own_row_fg, Theme::light().own_row_fg);
+        assert_eq!(theme.normal_row_color, Theme::light().normal_row_color);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn should_keep_preset_color_when_color_is_invalid() {
+        let config = ThemeConfig {
+            row_fg: Some("#12345".to_string()),
+            selected_style_fg: Some("bluish".to_string()),
+            ..Default::default()
+        };
+        let (theme, warnings) = Theme::from_config(&config);
+        assert_eq!(theme.row_fg, Theme::dark().row_fg);
+        assert_eq!(theme.selected_style_fg, Theme::dark().selected_style_fg);
+        assert_eq!(
+            warnings,
+            vec![
+                "Invalid color '#12345' of theme.row_fg",
+                "Invalid color 'bluish' of theme.selected_style_fg",
+            ]
+        );
+    }
+}
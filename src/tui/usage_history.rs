@@ -0,0 +1,101 @@
+use std::collections::VecDeque;
+
+/// Samples kept for selected process, older ones are dropped so pik running overnight does not grow
+const HISTORY_LEN: usize = 120;
+
+/// Cpu usage and memory of selected process sampled on every refresh, details show them as sparklines
+#[derive(Debug, Default)]
+pub struct UsageHistory {
+    pid: Option<u32>,
+    //NOTE: sparkline takes integers, cpu usage is stored in tenths of percent
+    cpu: VecDeque<u64>,
+    memory: VecDeque<u64>,
+}
+
+/// Last samples of one process, the oldest first
+#[derive(Debug, PartialEq, Eq)]
+pub struct UsageSamples {
+    pub cpu: Vec<u64>,
+    pub memory: Vec<u64>,
+}
+
+impl UsageHistory {
+    /// History of previously sampled process is dropped, i.e. after selection moved
+    pub fn sample(&mut self, pid: u32, cpu_usage: f32, memory: u64) {
+        if self.pid != Some(pid) {
+            self.pid = Some(pid);
+            self.cpu.clear();
+            self.memory.clear();
+        }
+        if self.cpu.len() == HISTORY_LEN {
+            self.cpu.pop_front();
+            self.memory.pop_front();
+        }
+        self.cpu
+            .push_back((cpu_usage.max(0.0) * 10.0).round() as u64);
+        self.memory.push_back(memory);
+    }
+
+    /// Up to `count` newest samples, None when process was not sampled
+    pub fn samples(&self, pid: u32, count: usize) -> Option<UsageSamples> {
+        if self.pid != Some(pid) || self.cpu.is_empty() {
+            return None;
+        }
+        let last = |samples: &VecDeque<u64>| {
+            let skip = samples.len().saturating_sub(count);
+            samples.iter().skip(skip).copied().collect()
+        };
+        Some(UsageSamples {
+            cpu: last(&self.cpu),
+            memory: last(&self.memory),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_keep_samples_of_one_process() {
+        let mut history = UsageHistory::default();
+        assert_eq!(history.samples(1234, 10), None);
+        history.sample(1234, 1.25, 100);
+        history.sample(1234, 50.0, 200);
+        assert_eq!(
+            history.samples(1234, 10),
+            Some(UsageSamples {
+                cpu: vec![13, 500],
+                memory: vec![100, 200],
+            })
+        );
+        assert_eq!(history.samples(99, 10), None);
+
+        history.sample(99, 3.0, 300);
+        assert_eq!(history.samples(1234, 10), None);
+        assert_eq!(
+            history.samples(99, 10),
+            Some(UsageSamples {
+                cpu: vec![30],
+                memory: vec![300],
+            })
+        );
+    }
+
+    #[test]
+    fn should_drop_oldest_samples_when_history_is_full() {
+        let mut history = UsageHistory::default();
+        for memory in 0..HISTORY_LEN as u64 * 3 {
+            history.sample(1234, 0.0, memory);
+        }
+        assert_eq!(history.cpu.len(), HISTORY_LEN);
+        assert_eq!(history.memory.len(), HISTORY_LEN);
+        let samples = history.samples(1234, 3).unwrap();
+        let last = HISTORY_LEN as u64 * 3 - 1;
+        assert_eq!(samples.memory, vec![last - 2, last - 1, last]);
+        assert_eq!(
+            history.samples(1234, 1000).unwrap().memory.len(),
+            HISTORY_LEN
+        );
+    }
+}
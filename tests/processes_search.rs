@@ -1,21 +1,39 @@
-use std::{thread, time::Duration};
+use std::{collections::HashSet, thread, time::Duration};
 
-use pik::processes::{FilterOptions, ProcessManager};
+use pik::processes::{
+    kill_signals, process_connections, FilterOptions, ProcessManager, ProcessSearchResults, Sort,
+    SortColumn, SortOrder,
+};
 
 use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
 
 #[test]
 fn should_find_cargo_process_by_cmd_name() {
     let mut process_manager = ProcessManager::new().unwrap();
-    let results = process_manager.find_processes("cargo", FilterOptions::default());
+    let results = process_manager
+        .find_processes("cargo", FilterOptions::default())
+        .unwrap();
     assert!(!results.is_empty());
     assert!(results.iter().all(|p| fuzzy_matches(&p.cmd, "cargo")));
 }
 
+#[test]
+fn should_rank_exact_cmd_matches_first() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let results = process_manager
+        .find_processes("cargo", FilterOptions::default())
+        .unwrap();
+    let first = results.nth(Some(0)).unwrap();
+    assert!(first.cmd.to_lowercase().contains("cargo"));
+    assert!(!first.matches.cmd.is_empty());
+}
+
 #[test]
 fn should_find_cargo_process_by_cmd_path() {
     let mut process_manager = ProcessManager::new().unwrap();
-    let results = process_manager.find_processes("/cargo", FilterOptions::default());
+    let results = process_manager
+        .find_processes("/cargo", FilterOptions::default())
+        .unwrap();
     assert!(!results.is_empty());
     assert!(results
         .iter()
@@ -25,7 +43,9 @@ fn should_find_cargo_process_by_cmd_path() {
 #[test]
 fn should_find_cargo_process_by_name_path_or_args() {
     let mut process_manager = ProcessManager::new().unwrap();
-    let results = process_manager.find_processes("~cargo", FilterOptions::default());
+    let results = process_manager
+        .find_processes("~cargo", FilterOptions::default())
+        .unwrap();
     assert!(!results.is_empty());
     assert!(results
         .iter()
@@ -37,7 +57,9 @@ fn should_find_cargo_process_by_name_path_or_args() {
 #[test]
 fn should_find_cargo_process_by_args() {
     let mut process_manager = ProcessManager::new().unwrap();
-    let results = process_manager.find_processes("-test", FilterOptions::default());
+    let results = process_manager
+        .find_processes("-test", FilterOptions::default())
+        .unwrap();
     assert!(!results.is_empty());
     assert!(results.iter().all(|p| fuzzy_matches(&p.args, "test")));
 }
@@ -50,40 +72,637 @@ fn should_find_cargo_process_by_port() {
     // NOTE: Someties system needs time to notice the port is in use
     thread::sleep(Duration::from_millis(250));
     let mut process_manager = ProcessManager::new().unwrap();
-    let results = process_manager.find_processes(&format!(":{}", port), FilterOptions::default());
+    let results = process_manager
+        .find_processes(&format!(":{}", port), FilterOptions::default())
+        .unwrap();
     assert!(!results.is_empty());
     assert!(results.iter().all(|p| p.ports == Some(format!("{}", port))));
+    assert!(results.iter().all(|p| p
+        .connections
+        .iter()
+        .all(|c| c.state == "LISTEN" && c.local.port() == port)));
 }
 
 #[test]
 fn should_find_cargo_process_by_pid() {
     let mut process_manager = ProcessManager::new().unwrap();
-    let results = process_manager.find_processes("cargo", FilterOptions::default());
+    let results = process_manager
+        .find_processes("cargo", FilterOptions::default())
+        .unwrap();
     let cargo_process_pid = results.nth(Some(0)).map(|r| r.pid).unwrap();
 
     let restults = process_manager
-        .find_processes(&format!("!{}", cargo_process_pid), FilterOptions::default());
-    assert_eq!(restults.len(), 1);
-    assert_eq!(restults.nth(Some(0)).unwrap().pid, cargo_process_pid);
+        .find_processes(&format!("#{}", cargo_process_pid), FilterOptions::default())
+        .unwrap();
+    assert!(restults.iter().any(|p| p.pid == cargo_process_pid));
+    assert!(restults.iter().all(|p| p
+        .pid
+        .to_string()
+        .starts_with(&cargo_process_pid.to_string())));
+}
+
+#[test]
+fn should_find_cargo_process_children_by_parent_pid() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let current_pid = std::process::id();
+    let parent_pid = process_manager
+        .find_processes(&format!("#{}", current_pid), FilterOptions::default())
+        .unwrap()
+        .iter()
+        .find(|p| p.pid == current_pid)
+        .and_then(|p| p.parent_pid)
+        .unwrap();
+
+    let results = process_manager
+        .find_processes(&format!("^{}", parent_pid), FilterOptions::default())
+        .unwrap();
+    assert!(results.iter().any(|p| p.pid == current_pid));
+    assert!(results.iter().all(|p| p.parent_pid == Some(parent_pid)));
+}
+
+#[test]
+fn should_resolve_parent_that_does_not_match_query() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let current_pid = std::process::id();
+    let mut results = process_manager
+        .find_processes(&format!("#{}", current_pid), FilterOptions::default())
+        .unwrap();
+    let own = results.iter().find(|p| p.pid == current_pid).unwrap();
+    let parent_pid = own.parent_pid.unwrap();
+    let parent_cmd = own.parent_cmd.clone().unwrap();
+    assert_eq!(results.position(parent_pid), None);
+
+    let parent = process_manager.process(parent_pid).unwrap();
+    assert_eq!(parent.cmd, parent_cmd);
+    let len = results.len();
+    results.insert_context(0, parent);
+    assert_eq!(results.len(), len + 1);
+    assert_eq!(results.position(parent_pid), Some(0));
+    assert!(results.nth(Some(0)).unwrap().context_only);
+    assert!(process_manager.process(u32::MAX).is_none());
 }
 
 #[test]
 fn should_find_cargo_process_by_process_family() {
     let mut process_manager = ProcessManager::new().unwrap();
-    let results = process_manager.find_processes("cargo", FilterOptions::default());
+    let results = process_manager
+        .find_processes("cargo", FilterOptions::default())
+        .unwrap();
     let cargo_process_pid = results.nth(Some(0)).map(|r| r.pid).unwrap();
 
     let results = process_manager
-        .find_processes(&format!("@{}", cargo_process_pid), FilterOptions::default());
+        .find_processes(&format!("@{}", cargo_process_pid), FilterOptions::default())
+        .unwrap();
     assert!(!results.is_empty());
     assert!(results
         .iter()
         .all(|p| p.pid == cargo_process_pid || p.parent_pid == Some(cargo_process_pid)));
 }
 
+#[test]
+fn should_find_cargo_process_by_regex() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let results = process_manager
+        .find_processes("re:^car.o$", FilterOptions::default())
+        .unwrap();
+    assert!(!results.is_empty());
+    assert!(results.iter().all(|p| p.cmd == "cargo"));
+}
+
+#[test]
+fn should_find_cargo_process_in_regex_mode() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let options = FilterOptions {
+        regex_search: true,
+        ..Default::default()
+    };
+    let results = process_manager
+        .find_processes("^car.o test", options)
+        .unwrap();
+    assert!(!results.is_empty());
+    assert!(results.iter().all(|p| p.cmd == "cargo"));
+}
+
+#[test]
+fn should_find_cargo_process_by_user() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let results = process_manager
+        .find_processes("cargo", FilterOptions::default())
+        .unwrap();
+    let user_name = results.nth(Some(0)).map(|r| r.user_name.clone()).unwrap();
+
+    let results = process_manager
+        .find_processes(&format!("@{}", user_name), FilterOptions::default())
+        .unwrap();
+    assert!(!results.is_empty());
+    assert!(results.iter().all(|p| p.user_name == user_name));
+}
+
+#[test]
+fn should_limit_processes_to_given_user() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let error = process_manager.filter_by_user("no-such-user").unwrap_err();
+    assert_eq!(error.to_string(), "User 'no-such-user' not found");
+
+    process_manager.filter_by_user("me").unwrap();
+    let options = FilterOptions {
+        include_all_processes: true,
+        ..Default::default()
+    };
+    let results = process_manager.find_processes("cargo", options).unwrap();
+    assert!(!results.is_empty());
+    let user_name = results.user_filter.clone().unwrap();
+    assert!(results.iter().all(|p| p.user_name == user_name));
+
+    process_manager.filter_by_user(&user_name).unwrap();
+    let results = process_manager.find_processes("", options).unwrap();
+    assert!(results.iter().all(|p| p.user_name == user_name));
+}
+
+#[test]
+fn should_sort_processes_by_pid_numerically() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let mut results = process_manager
+        .find_processes("", FilterOptions::default())
+        .unwrap();
+    results.sort(Sort {
+        column: SortColumn::Pid,
+        order: SortOrder::Descending,
+    });
+    let pids: Vec<u32> = results.iter().map(|p| p.pid).collect();
+    assert!(pids.windows(2).all(|w| w[0] >= w[1]));
+}
+
+#[test]
+fn should_sort_processes_by_cpu_usage_after_refresh() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    process_manager.refresh();
+    let mut results = process_manager
+        .find_processes("", FilterOptions::default())
+        .unwrap();
+    results.sort(Sort {
+        column: SortColumn::Cpu,
+        order: SortOrder::Descending,
+    });
+    let cpu_usages: Vec<f32> = results.iter().map(|p| p.cpu_usage).collect();
+    assert!(cpu_usages.windows(2).all(|w| w[0] >= w[1]));
+    assert!(cpu_usages.iter().all(|cpu| *cpu >= 0.0));
+}
+
 fn fuzzy_matches(value: &str, pattern: &str) -> bool {
     SkimMatcherV2::default()
         .fuzzy_match(value, pattern)
         .unwrap_or(0)
         > 0
 }
+
+#[test]
+fn should_show_cargo_process_with_ancestors_in_tree_view() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let options = FilterOptions {
+        tree_view: true,
+        ..Default::default()
+    };
+    let results = process_manager.find_processes("cargo", options).unwrap();
+    let items: Vec<_> = results.iter().collect();
+    assert!(items.iter().any(|p| p.cmd == "cargo" && !p.context_only));
+    for (index, prc) in items.iter().enumerate() {
+        if let Some(parent_index) = prc
+            .parent_pid
+            .and_then(|parent| items.iter().position(|p| p.pid == parent))
+        {
+            assert!(parent_index < index);
+            assert!(!prc.tree_prefix.is_empty());
+        }
+        if prc.context_only {
+            assert!(items[index + 1..]
+                .iter()
+                .any(|p| p.parent_pid == Some(prc.pid)));
+        }
+    }
+}
+
+#[test]
+fn should_sum_memory_and_cpu_of_matching_processes() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let results = process_manager
+        .find_processes("", FilterOptions::default())
+        .unwrap();
+    let totals = results.totals();
+    assert_eq!(totals.count, results.len());
+    assert_eq!(totals.memory, results.iter().map(|p| p.memory).sum::<u64>());
+
+    let results = process_manager
+        .find_processes(
+            "no-process-should-match-this-query",
+            FilterOptions::default(),
+        )
+        .unwrap();
+    assert_eq!(results.totals().count, 0);
+}
+
+#[test]
+fn should_hide_descendants_of_collapsed_process_in_tree_view() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let own_pid = std::process::id();
+    let options = FilterOptions {
+        tree_view: true,
+        ..Default::default()
+    };
+    let mut results = process_manager
+        .find_processes(&format!("#{}", own_pid), options)
+        .unwrap();
+    let own = results.nth(results.position(own_pid)).unwrap();
+    let parent_pid = own.parent_pid.unwrap();
+    assert!(results.has_children(parent_pid));
+    let all = results.len();
+
+    results.collapse(&HashSet::from([parent_pid]));
+    assert!(results.position(own_pid).is_none());
+    assert!(results.len() < all);
+    let parent = results.nth(results.position(parent_pid)).unwrap();
+    assert!(parent.tree_prefix.ends_with("▸ "));
+
+    results.sort(Sort {
+        column: SortColumn::Pid,
+        order: SortOrder::Descending,
+    });
+    assert!(results.position(own_pid).is_none());
+    results.collapse(&HashSet::new());
+    assert!(results.position(own_pid).is_some());
+    assert_eq!(results.len(), all);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn should_know_io_rate_of_own_process_after_refresh() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let own_pid = std::process::id();
+    let query = format!("#{}", own_pid);
+    process_manager.refresh();
+    let results = process_manager
+        .find_processes(&query, FilterOptions::default())
+        .unwrap();
+    let own = results.nth(results.position(own_pid)).unwrap();
+    assert!(own.io_rate.is_some());
+    assert_ne!(own.io_read_as_string(), "-");
+}
+
+#[cfg(unix)]
+#[test]
+fn should_pause_and_resume_process() {
+    use pik::processes::{PAUSE_SIGNAL, RESUME_SIGNAL};
+
+    let mut child = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .unwrap();
+    let mut process_manager = ProcessManager::new().unwrap();
+    let query = format!("#{}", child.id());
+    let is_stopped = |process_manager: &mut ProcessManager| {
+        thread::sleep(Duration::from_millis(100));
+        process_manager.refresh();
+        let results = process_manager
+            .find_processes(&query, FilterOptions::default())
+            .unwrap();
+        results
+            .nth(results.position(child.id()))
+            .unwrap()
+            .is_stopped()
+    };
+    assert!(!is_stopped(&mut process_manager));
+
+    process_manager
+        .kill_process(child.id(), PAUSE_SIGNAL)
+        .unwrap();
+    assert!(is_stopped(&mut process_manager));
+    process_manager
+        .kill_process(child.id(), RESUME_SIGNAL)
+        .unwrap();
+    assert!(!is_stopped(&mut process_manager));
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn should_hide_own_process_and_ancestors() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let own_pid = std::process::id();
+    let query = format!("#{}", own_pid);
+    let options = FilterOptions {
+        hide_self: true,
+        ..Default::default()
+    };
+    let results = process_manager.find_processes(&query, options).unwrap();
+    assert!(results.position(own_pid).is_none());
+    assert!(results.hidden >= 1);
+
+    let results = process_manager
+        .find_processes(&query, FilterOptions::default())
+        .unwrap();
+    let own = results.nth(results.position(own_pid)).unwrap();
+    assert!(own.is_self_or_ancestor);
+    assert_eq!(results.hidden, 0);
+}
+
+#[test]
+fn should_keep_pinned_process_at_the_top_whatever_is_searched() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let own_pid = std::process::id();
+    assert!(process_manager.toggle_pin(own_pid));
+    let query = "no-process-should-match-this-query";
+    let results = process_manager
+        .find_processes(query, FilterOptions::default())
+        .unwrap();
+    assert_eq!(results.position(own_pid), Some(0));
+    assert!(results.nth(Some(0)).unwrap().pinned);
+
+    process_manager.refresh();
+    let options = FilterOptions {
+        tree_view: true,
+        hide_self: true,
+        ..Default::default()
+    };
+    let mut results = process_manager.find_processes("", options).unwrap();
+    results.sort(Sort {
+        column: SortColumn::Pid,
+        order: SortOrder::Descending,
+    });
+    assert_eq!(results.position(own_pid), Some(0));
+    assert!(results.iter().skip(1).all(|p| !p.pinned));
+
+    assert!(!process_manager.toggle_pin(own_pid));
+    let results = process_manager
+        .find_processes(query, FilterOptions::default())
+        .unwrap();
+    assert!(results.is_empty());
+}
+
+#[test]
+fn should_list_process_subtree_children_first() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let own_pid = std::process::id();
+    assert_eq!(
+        process_manager.process_subtree(own_pid).last(),
+        Some(&own_pid)
+    );
+
+    let results = process_manager
+        .find_processes(&format!("#{}", own_pid), FilterOptions::default())
+        .unwrap();
+    let own = results.nth(results.position(own_pid)).unwrap();
+    let parent_pid = own.parent_pid.unwrap();
+    let subtree = process_manager.process_subtree(parent_pid);
+    let own_index = subtree.iter().position(|pid| *pid == own_pid).unwrap();
+    assert!(own_index < subtree.len() - 1);
+    assert_eq!(subtree.last(), Some(&parent_pid));
+}
+
+#[test]
+fn should_list_listening_connection_of_process() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let connections = process_connections(std::process::id());
+    assert!(connections
+        .iter()
+        .any(|c| c.state == "LISTEN" && c.local.port() == port));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn should_count_threads_of_process() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let (sender, receiver) = std::sync::mpsc::channel::<()>();
+    let thread = thread::spawn(move || receiver.recv());
+    process_manager.refresh();
+
+    let own_pid = std::process::id();
+    let results = process_manager
+        .find_processes(&format!("#{}", own_pid), FilterOptions::default())
+        .unwrap();
+    let own = results.nth(results.position(own_pid)).unwrap();
+    assert!(own.threads.is_some_and(|threads| threads >= 2));
+
+    sender.send(()).unwrap();
+    thread.join().unwrap().unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn should_count_open_fds_only_of_requested_processes() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let own_pid = std::process::id();
+    let mut results = process_manager
+        .find_processes("", FilterOptions::default())
+        .unwrap();
+    assert!(results.iter().all(|prc| prc.open_fds.is_none()));
+
+    let own = results.position(own_pid).unwrap();
+    assert!(results.load_open_fds([own, usize::MAX]));
+    assert!(results.nth(Some(own)).unwrap().open_fds.unwrap() >= 3);
+    assert_eq!(
+        results.iter().filter(|prc| prc.open_fds.is_some()).count(),
+        1
+    );
+    assert!(!results.load_open_fds([own]));
+}
+
+#[cfg(unix)]
+#[test]
+fn should_find_process_by_nice_value() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    process_manager.refresh();
+
+    let own_pid = std::process::id();
+    let error = process_manager
+        .find_processes(&format!("#{} nice>-21", own_pid), FilterOptions::default())
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid nice '-21', accepted values: -20 to 19"
+    );
+    let results = process_manager
+        .find_processes(&format!("#{} nice>-20", own_pid), FilterOptions::default())
+        .unwrap();
+    let own = results.nth(results.position(own_pid)).unwrap();
+    assert!(own.nice.is_some());
+}
+
+#[cfg(unix)]
+#[test]
+fn should_keep_selection_of_process_after_refresh() {
+    let mut first = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .unwrap();
+    let mut second = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .unwrap();
+    let query = format!("^{}", std::process::id());
+    let mut process_manager = ProcessManager::new().unwrap();
+    let results = process_manager
+        .find_processes(&query, FilterOptions::default())
+        .unwrap();
+    assert!(results.position(first.id()).is_some());
+    let second_index = results.position(second.id()).unwrap();
+
+    second.kill().unwrap();
+    second.wait().unwrap();
+    process_manager.refresh();
+    let results = process_manager
+        .find_processes(&query, FilterOptions::default())
+        .unwrap();
+    assert_eq!(
+        results.selection_after_refresh(first.id(), second_index),
+        results.position(first.id())
+    );
+    //NOTE: exited process falls back to the nearest row
+    let fallback = results
+        .selection_after_refresh(second.id(), second_index)
+        .unwrap();
+    assert_eq!(fallback, second_index.min(results.len() - 1));
+    assert!(results.position(second.id()).is_none());
+    assert!(results.nth(Some(fallback)).is_some());
+
+    first.kill().unwrap();
+    first.wait().unwrap();
+    let results = process_manager
+        .find_processes(&format!("#{}", u32::MAX), FilterOptions::default())
+        .unwrap();
+    assert_eq!(
+        results.selection_after_refresh(second.id(), second_index),
+        None
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn should_find_process_running_deleted_binary() {
+    let binary = std::env::temp_dir().join(format!("pik-sleep-{}", std::process::id()));
+    std::fs::copy("/bin/sleep", &binary).unwrap();
+    let mut child = std::process::Command::new(&binary)
+        .arg("30")
+        .spawn()
+        .unwrap();
+    std::fs::remove_file(&binary).unwrap();
+
+    let mut process_manager = ProcessManager::new().unwrap();
+    let results = process_manager
+        .find_processes("deleted:pik-sleep", FilterOptions::default())
+        .unwrap();
+    let found = results.nth(results.position(child.id()));
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let found = found.unwrap();
+    assert!(found.exe_deleted);
+    assert_eq!(found.cmd_path.as_deref(), binary.to_str());
+}
+
+#[cfg(unix)]
+#[test]
+fn should_refresh_processes_in_background() {
+    let mut process_manager = ProcessManager::new().unwrap();
+    let mut child = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .unwrap();
+    let query = format!("#{}", child.id());
+    process_manager.start_refresh();
+    //NOTE: refresh requested again while scanning is done after the running one
+    process_manager.start_refresh();
+    assert!(process_manager.is_refreshing());
+    let started = std::time::Instant::now();
+    while !process_manager.finish_refresh() {
+        assert!(started.elapsed() < Duration::from_secs(10));
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert!(!process_manager.is_refreshing());
+    let results = process_manager
+        .find_processes(&query, FilterOptions::default())
+        .unwrap();
+    assert!(results.position(child.id()).is_some());
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    process_manager.start_refresh();
+    process_manager.wait_for_refresh();
+    assert!(!process_manager.is_refreshing());
+    let results = process_manager
+        .find_processes(&query, FilterOptions::default())
+        .unwrap();
+    assert!(results.position(child.id()).is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn should_group_processes_running_the_same_executable() {
+    let mut children: Vec<_> = (0..3)
+        .map(|_| {
+            std::process::Command::new("sleep")
+                .arg("30")
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+    let pids: Vec<u32> = children.iter().map(|child| child.id()).collect();
+    let query = format!("^{}", std::process::id());
+    let mut process_manager = ProcessManager::new().unwrap();
+    let mut results = process_manager
+        .find_processes(&query, FilterOptions::default())
+        .unwrap();
+    let totals = results.totals();
+
+    results.group_by_exe(&HashSet::new());
+    let group = results
+        .iter()
+        .find(|p| p.group.is_some() && p.cmd == "sleep")
+        .unwrap();
+    let members = results.group_members(group);
+    let count = group.group.unwrap().count;
+    assert_eq!(count, members.len());
+    assert!(pids.iter().all(|pid| members.contains(pid)));
+    assert!(group.cmd_line.starts_with("sleep"));
+    assert_eq!(results.totals(), totals);
+    let collapsed_len = results.len();
+    let visible = |results: &ProcessSearchResults| {
+        results
+            .iter()
+            .filter(|p| p.group.is_none() && members.contains(&p.pid))
+            .map(|p| p.tree_prefix.clone())
+            .collect::<Vec<_>>()
+    };
+    assert!(visible(&results).is_empty());
+
+    let exe = group.exe().to_string();
+    results.group_by_exe(&HashSet::from([exe]));
+    assert_eq!(results.len(), collapsed_len + count);
+    assert_eq!(visible(&results), vec!["  "; count]);
+    assert_eq!(results.totals(), totals);
+
+    for child in children.iter_mut() {
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn should_know_when_killed_process_terminated() {
+    let mut child = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .unwrap();
+    let process_manager = ProcessManager::new().unwrap();
+    assert!(!process_manager.is_terminated(child.id()));
+
+    process_manager
+        .kill_process(child.id(), kill_signals()[0])
+        .unwrap();
+    thread::sleep(Duration::from_millis(100));
+    //NOTE: child is a zombie until it is waited for, it counts as terminated
+    assert!(process_manager.is_terminated(child.id()));
+    child.wait().unwrap();
+    assert!(process_manager.is_terminated(child.id()));
+}